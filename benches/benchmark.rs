@@ -1,7 +1,7 @@
 use std::{path::Path, time::Duration};
 
 use criterion::{Criterion, criterion_group, criterion_main};
-use oneocr_rs::{ImageInput, OcrOptions, OneOcrError};
+use oneocr_rs::{ImageInput, OcrEnginePool, OcrOptions, OneOcrError};
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("ocr_bench_group");
@@ -10,6 +10,10 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.warm_up_time(Duration::from_secs(10));
     group.bench_function("ocr_simple", |b| b.iter(ocr_simple));
     group.bench_function("ocr_advance", |b| b.iter(ocr_advance));
+
+    // The pool's pipelines are created once, outside the measured loop.
+    let pool = OcrEnginePool::new(4, OcrOptions::default()).expect("failed to create pool");
+    group.bench_function("ocr_pool_batch", |b| b.iter(|| ocr_pool_batch(&pool)));
     group.finish();
 }
 
@@ -28,10 +32,8 @@ pub fn ocr_simple() -> Result<(), OneOcrError> {
 #[inline]
 pub fn ocr_advance() -> Result<(), OneOcrError> {
     // Create a new OCR instance
-    let ocr_options = OcrOptions {
-        include_word_level_details: true,
-        ..Default::default()
-    };
+    let mut ocr_options = OcrOptions::default();
+    ocr_options.include_word_level_details = true;
     let ocr_engine = oneocr_rs::OcrEngine::new_with_options(ocr_options)?;
 
     // Perform OCR on an image
@@ -41,5 +43,17 @@ pub fn ocr_advance() -> Result<(), OneOcrError> {
     Ok(())
 }
 
+#[inline]
+pub fn ocr_pool_batch(pool: &OcrEnginePool) -> Result<(), OneOcrError> {
+    // Perform OCR on a batch of images, spread over the pool's engines
+    let image_path = Path::new("./assets/sample.jpg");
+    let inputs = (0..8).map(|_| ImageInput::FilePath(image_path.to_path_buf()));
+    for result in pool.run_batch(inputs) {
+        result?;
+    }
+
+    Ok(())
+}
+
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
@@ -1,6 +1,6 @@
-use image::Rgba;
+use image::{DynamicImage, Rgba};
 use imageproc::drawing::draw_line_segment_mut;
-use oneocr_rs::{OcrEngine, OcrOptions, OneOcrError};
+use oneocr_rs::{BoundingBox, OcrEngine, OcrOptions, OneOcrError};
 use std::path::Path;
 
 // cargo run --example bbox_draw -- "/path/to/input/image.jpg" "/path/to/draw_output.jpg"
@@ -18,10 +18,8 @@ fn main() -> Result<(), OneOcrError> {
     let output_image_path = Path::new(&output_image_path);
 
     // Create a new OCR instance
-    let options = OcrOptions {
-        include_word_level_details: true,
-        ..Default::default()
-    };
+    let mut options = OcrOptions::default();
+    options.include_word_level_details = true;
     let ocr_engine = OcrEngine::new_with_options(options)?;
 
     // Set to the max recognition line count possible.
@@ -38,34 +36,10 @@ fn main() -> Result<(), OneOcrError> {
     let word_color = Rgba([0u8, 255u8, 0u8, 255u8]); // Green for words
 
     for line in &ocr_result.lines {
-        let line_bbox = line.bounding_box;
+        draw_box(&mut img, &line.bounding_box, line_color);
 
-        // Draw the outline of the line bounding box
-        let p1 = (line_bbox.top_left.x, line_bbox.top_left.y);
-        let p2 = (line_bbox.top_right.x, line_bbox.top_right.y);
-        let p3 = (line_bbox.bottom_right.x, line_bbox.bottom_right.y);
-        let p4 = (line_bbox.bottom_left.x, line_bbox.bottom_left.y);
-
-        draw_line_segment_mut(&mut img, p1, p2, line_color);
-        draw_line_segment_mut(&mut img, p2, p3, line_color);
-        draw_line_segment_mut(&mut img, p3, p4, line_color);
-        draw_line_segment_mut(&mut img, p4, p1, line_color); // Close the polygon
-
-        if let Some(words) = &line.words {
-            for word in words {
-                let word_bbox = word.bounding_box;
-
-                // Draw the outline of the word bounding box
-                let wp1 = (word_bbox.top_left.x, word_bbox.top_left.y);
-                let wp2 = (word_bbox.top_right.x, word_bbox.top_right.y);
-                let wp3 = (word_bbox.bottom_right.x, word_bbox.bottom_right.y);
-                let wp4 = (word_bbox.bottom_left.x, word_bbox.bottom_left.y);
-
-                draw_line_segment_mut(&mut img, wp1, wp2, word_color);
-                draw_line_segment_mut(&mut img, wp2, wp3, word_color);
-                draw_line_segment_mut(&mut img, wp3, wp4, word_color);
-                draw_line_segment_mut(&mut img, wp4, wp1, word_color); // Close the polygon
-            }
+        for word in line.words.iter().flatten() {
+            draw_box(&mut img, &word.bounding_box, word_color);
         }
     }
 
@@ -79,3 +53,12 @@ fn main() -> Result<(), OneOcrError> {
 
     Ok(())
 }
+
+/// Draws the outline of a bounding box, closing the polygon from the last corner to the first.
+fn draw_box(img: &mut DynamicImage, bounding_box: &BoundingBox, color: Rgba<u8>) {
+    let corners = bounding_box.corners();
+    for (i, start) in corners.iter().enumerate() {
+        let end = corners[(i + 1) % corners.len()];
+        draw_line_segment_mut(img, (start.x, start.y), (end.x, end.y), color);
+    }
+}
@@ -11,10 +11,8 @@ fn main() -> Result<(), OneOcrError> {
     let image_path = Path::new(&input_image_path);
 
     // Create a new OCR instance
-    let ocr_options = OcrOptions {
-        include_word_level_details: true,
-        ..Default::default()
-    };
+    let mut ocr_options = OcrOptions::default();
+    ocr_options.include_word_level_details = true;
     let ocr_engine = OcrEngine::new_with_options(ocr_options)?;
 
     // Set to the max recognition line count possible.
@@ -31,9 +29,9 @@ fn main() -> Result<(), OneOcrError> {
         println!("Line: {}", line.text);
         println!("{}", line.bounding_box);
 
-        let (handwritten, confidence) = line.get_line_style()?;
         println!(
-            "Line style: handwritten: {handwritten}, handwritten style confidence: {confidence}"
+            "Line style: {:?}, style confidence: {:.2}",
+            line.style, line.style_confidence
         );
 
         if let Some(words) = &line.words {
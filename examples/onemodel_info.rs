@@ -0,0 +1,34 @@
+use oneocr_rs::{OneModel, OneOcrError};
+use std::path::Path;
+
+// cargo run --example onemodel_info -- "/path/to/oneocr.onemodel" ["/path/to/extract/dir"]
+
+fn main() -> Result<(), OneOcrError> {
+    let model_path = std::env::args()
+        .nth(1)
+        .unwrap_or("./target/debug/oneocr.onemodel".to_string());
+    let extract_dir = std::env::args().nth(2);
+
+    // Decrypt the model container
+    let model = OneModel::open(Path::new(&model_path))?;
+
+    // Print the embedded entries
+    println!("Header: {} bytes", model.header().len());
+    for entry in model.entries() {
+        println!(
+            "{:>3} {:<10} {:>10} bytes  {}",
+            entry.index,
+            entry.kind,
+            entry.size(),
+            entry.sha256()
+        );
+    }
+
+    // Optionally extract them to disk
+    if let Some(extract_dir) = extract_dir {
+        let paths = model.extract_all(Path::new(&extract_dir))?;
+        println!("Extracted {} entries to {extract_dir}", paths.len());
+    }
+
+    Ok(())
+}
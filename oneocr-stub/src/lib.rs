@@ -0,0 +1,574 @@
+//! A scriptable stub of the OneOCR C API.
+//!
+//! This library exports the same functions as `oneocr.dll`, so it can be loaded through
+//! `oneocr_rs::DllBackend::load` on any platform. Instead of running OCR, it serves the lines and
+//! words of a JSON [`Script`] set with `StubSetScript`, and can be scripted to fail any function
+//! with a given error code or to hand out null pointers.
+//!
+//! `StubLiveObjectCount` returns the number of init options, pipelines, process options and
+//! results that have been created and not released yet, which lets tests check for leaks.
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The error code returned for null handles, out-of-range indices and invalid images.
+pub const E_INVALID_ARGUMENT: i32 = 3;
+
+/// The behavior of the stub.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Script {
+    /// The image angle of every result.
+    pub image_angle: f32,
+    /// The lines of every result.
+    pub lines: Vec<ScriptLine>,
+    /// Functions that fail, by name, with the error code they return.
+    pub errors: HashMap<String, i32>,
+    /// Functions that succeed but hand out a null pointer, by name.
+    pub null_pointers: HashSet<String>,
+}
+
+/// A line served by the stub.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScriptLine {
+    pub text: String,
+    /// The corners as `[x1, y1, x2, y2, x3, y3, x4, y4]`, clockwise from the top left.
+    pub bounding_box: [f32; 8],
+    /// 0 = Handwritten, 1 = Printed
+    pub style: i32,
+    pub style_confidence: f32,
+    pub words: Vec<ScriptWord>,
+}
+
+/// A word served by the stub.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScriptWord {
+    pub text: String,
+    pub bounding_box: [f32; 8],
+    pub confidence: f32,
+}
+
+static SCRIPT: Mutex<Option<Arc<Script>>> = Mutex::new(None);
+static LIVE_OBJECTS: AtomicI64 = AtomicI64::new(0);
+
+fn script() -> Arc<Script> {
+    SCRIPT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Returns the error code scripted for a function, if any.
+fn scripted_error(name: &str) -> Option<i32> {
+    script().errors.get(name).copied()
+}
+
+/// Returns `true` if a function is scripted to hand out a null pointer.
+fn scripted_null(name: &str) -> bool {
+    script().null_pointers.contains(name)
+}
+
+/// Moves an object to the heap and counts it as live.
+fn into_handle<T>(object: T) -> *mut c_void {
+    LIVE_OBJECTS.fetch_add(1, Ordering::SeqCst);
+    Box::into_raw(Box::new(object)) as *mut c_void
+}
+
+/// Drops an object created by `into_handle`.
+unsafe fn release_handle<T>(handle: *mut c_void) {
+    if !handle.is_null() {
+        LIVE_OBJECTS.fetch_sub(1, Ordering::SeqCst);
+        drop(unsafe { Box::from_raw(handle as *mut T) });
+    }
+}
+
+/// Returns the error code scripted for `$name`, or `E_INVALID_ARGUMENT` if a pointer is null.
+macro_rules! check {
+    ($name:literal $(, $ptr:expr)*) => {
+        if let Some(code) = scripted_error($name) {
+            return code;
+        }
+        $(
+            if $ptr.is_null() {
+                return E_INVALID_ARGUMENT;
+            }
+        )*
+    };
+}
+
+#[repr(C, packed)]
+pub struct RawImage {
+    pub t: i32,
+    pub col: i32,
+    pub row: i32,
+    pub _unk: i32,
+    pub step: i64,
+    pub data_ptr: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RawBBox(pub [f32; 8]);
+
+struct InitOptions {
+    _delay_load: u8,
+}
+
+struct Pipeline;
+
+struct ProcessOptions {
+    max_recognition_line_count: i32,
+    resize_resolution: (i32, i32),
+}
+
+struct OcrResult {
+    image_angle: f32,
+    lines: Vec<Line>,
+}
+
+struct Line {
+    text: CString,
+    bounding_box: RawBBox,
+    style: i32,
+    style_confidence: f32,
+    words: Vec<Word>,
+}
+
+struct Word {
+    text: CString,
+    bounding_box: RawBBox,
+    confidence: f32,
+}
+
+fn c_string(text: &str) -> CString {
+    CString::new(text.replace('\0', "")).unwrap_or_default()
+}
+
+/// Replaces the script. Returns 0 on success or `E_INVALID_ARGUMENT` if `json` is not a valid script.
+///
+/// # Safety
+///
+/// `json` must be null or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn StubSetScript(json: *const c_char) -> i32 {
+    if json.is_null() {
+        return E_INVALID_ARGUMENT;
+    }
+    let json = unsafe { CStr::from_ptr(json) }.to_string_lossy();
+    match serde_json::from_str::<Script>(&json) {
+        Ok(script) => {
+            *SCRIPT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(script));
+            0
+        }
+        Err(_) => E_INVALID_ARGUMENT,
+    }
+}
+
+/// Returns the number of objects created and not released yet.
+#[unsafe(no_mangle)]
+pub extern "system" fn StubLiveObjectCount() -> i64 {
+    LIVE_OBJECTS.load(Ordering::SeqCst)
+}
+
+/// # Safety
+///
+/// `init_option` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn CreateOcrInitOptions(init_option: *mut *mut c_void) -> i32 {
+    check!("CreateOcrInitOptions", init_option);
+    unsafe { *init_option = into_handle(InitOptions { _delay_load: 1 }) };
+    0
+}
+
+/// # Safety
+///
+/// `init_option` must be null or created by `CreateOcrInitOptions`.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn OcrInitOptionsSetUseModelDelayLoad(
+    init_option: *mut c_void,
+    delay_load: u8,
+) -> i32 {
+    check!("OcrInitOptionsSetUseModelDelayLoad", init_option);
+    unsafe { (*(init_option as *mut InitOptions))._delay_load = delay_load };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid; strings must be NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn CreateOcrPipeline(
+    model_path: *const c_char,
+    key: *const c_char,
+    ctx: *mut c_void,
+    pipeline: *mut *mut c_void,
+) -> i32 {
+    check!("CreateOcrPipeline", model_path, key, ctx, pipeline);
+    let handle = if scripted_null("CreateOcrPipeline") {
+        ptr::null_mut()
+    } else {
+        into_handle(Pipeline)
+    };
+    unsafe { *pipeline = handle };
+    0
+}
+
+/// # Safety
+///
+/// `option` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn CreateOcrProcessOptions(option: *mut *mut c_void) -> i32 {
+    check!("CreateOcrProcessOptions", option);
+    unsafe {
+        *option = into_handle(ProcessOptions {
+            max_recognition_line_count: 100,
+            resize_resolution: (1152, 768),
+        })
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn OcrProcessOptionsGetMaxRecognitionLineCount(
+    option: *mut c_void,
+    count: *mut i32,
+) -> i32 {
+    check!("OcrProcessOptionsGetMaxRecognitionLineCount", option, count);
+    unsafe { *count = (*(option as *mut ProcessOptions)).max_recognition_line_count };
+    0
+}
+
+/// # Safety
+///
+/// `option` must be null or created by `CreateOcrProcessOptions`.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn OcrProcessOptionsSetMaxRecognitionLineCount(
+    option: *mut c_void,
+    count: i32,
+) -> i32 {
+    check!("OcrProcessOptionsSetMaxRecognitionLineCount", option);
+    if !(0..=1000).contains(&count) {
+        return E_INVALID_ARGUMENT;
+    }
+    unsafe { (*(option as *mut ProcessOptions)).max_recognition_line_count = count };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn OcrProcessOptionsGetResizeResolution(
+    option: *mut c_void,
+    width: *mut i64,
+    height: *mut i64,
+) -> i32 {
+    check!(
+        "OcrProcessOptionsGetResizeResolution",
+        option,
+        width,
+        height
+    );
+    let (w, h) = unsafe { (*(option as *mut ProcessOptions)).resize_resolution };
+    unsafe {
+        *width = w as i64;
+        *height = h as i64;
+    }
+    0
+}
+
+/// # Safety
+///
+/// `option` must be null or created by `CreateOcrProcessOptions`.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn OcrProcessOptionsSetResizeResolution(
+    option: *mut c_void,
+    width: i32,
+    height: i32,
+) -> i32 {
+    check!("OcrProcessOptionsSetResizeResolution", option);
+    if !(1..=1152).contains(&width) || !(1..=768).contains(&height) {
+        return E_INVALID_ARGUMENT;
+    }
+    unsafe { (*(option as *mut ProcessOptions)).resize_resolution = (width, height) };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid; the image data must cover `row * step` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn RunOcrPipeline(
+    pipeline: *mut c_void,
+    image: *const RawImage,
+    process_options: *mut c_void,
+    result: *mut *mut c_void,
+) -> i32 {
+    check!("RunOcrPipeline", pipeline, image, process_options, result);
+    let image = unsafe { &*image };
+    let (t, col, row, step, data_ptr) = (image.t, image.col, image.row, image.step, image.data_ptr);
+    if t != 3 || col <= 0 || row <= 0 || step < col as i64 * 4 || data_ptr == 0 {
+        return E_INVALID_ARGUMENT;
+    }
+
+    if scripted_null("RunOcrPipeline") {
+        unsafe { *result = ptr::null_mut() };
+        return 0;
+    }
+
+    let max_lines =
+        unsafe { (*(process_options as *mut ProcessOptions)).max_recognition_line_count };
+    let script = script();
+    let lines = script
+        .lines
+        .iter()
+        .take(max_lines.max(0) as usize)
+        .map(|line| Line {
+            text: c_string(&line.text),
+            bounding_box: RawBBox(line.bounding_box),
+            style: line.style,
+            style_confidence: line.style_confidence,
+            words: line
+                .words
+                .iter()
+                .map(|word| Word {
+                    text: c_string(&word.text),
+                    bounding_box: RawBBox(word.bounding_box),
+                    confidence: word.confidence,
+                })
+                .collect(),
+        })
+        .collect();
+
+    unsafe {
+        *result = into_handle(OcrResult {
+            image_angle: script.image_angle,
+            lines,
+        })
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetImageAngle(result: *mut c_void, angle: *mut f32) -> i32 {
+    check!("GetImageAngle", result, angle);
+    unsafe { *angle = (*(result as *mut OcrResult)).image_angle };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrLineCount(result: *mut c_void, count: *mut i64) -> i32 {
+    check!("GetOcrLineCount", result, count);
+    unsafe { *count = (*(result as *mut OcrResult)).lines.len() as i64 };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrLine(
+    result: *mut c_void,
+    index: i64,
+    line: *mut *mut c_void,
+) -> i32 {
+    check!("GetOcrLine", result, line);
+    let lines = unsafe { &mut (*(result as *mut OcrResult)).lines };
+    let Some(found) = usize::try_from(index).ok().and_then(|i| lines.get_mut(i)) else {
+        return E_INVALID_ARGUMENT;
+    };
+    unsafe {
+        *line = if scripted_null("GetOcrLine") {
+            ptr::null_mut()
+        } else {
+            found as *mut Line as *mut c_void
+        }
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrLineContent(
+    line: *mut c_void,
+    content: *mut *const c_char,
+) -> i32 {
+    check!("GetOcrLineContent", line, content);
+    unsafe {
+        *content = if scripted_null("GetOcrLineContent") {
+            ptr::null()
+        } else {
+            (*(line as *mut Line)).text.as_ptr()
+        }
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrLineBoundingBox(
+    line: *mut c_void,
+    bbox: *mut *const RawBBox,
+) -> i32 {
+    check!("GetOcrLineBoundingBox", line, bbox);
+    unsafe {
+        *bbox = if scripted_null("GetOcrLineBoundingBox") {
+            ptr::null()
+        } else {
+            &(*(line as *mut Line)).bounding_box
+        }
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrLineStyle(
+    line: *mut c_void,
+    style: *mut i32,
+    confidence: *mut f32,
+) -> i32 {
+    check!("GetOcrLineStyle", line, style, confidence);
+    let line = unsafe { &*(line as *mut Line) };
+    unsafe {
+        *style = line.style;
+        *confidence = line.style_confidence;
+    }
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrLineWordCount(line: *mut c_void, count: *mut i64) -> i32 {
+    check!("GetOcrLineWordCount", line, count);
+    unsafe { *count = (*(line as *mut Line)).words.len() as i64 };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrWord(
+    line: *mut c_void,
+    index: i64,
+    word: *mut *mut c_void,
+) -> i32 {
+    check!("GetOcrWord", line, word);
+    let words = unsafe { &mut (*(line as *mut Line)).words };
+    let Some(found) = usize::try_from(index).ok().and_then(|i| words.get_mut(i)) else {
+        return E_INVALID_ARGUMENT;
+    };
+    unsafe {
+        *word = if scripted_null("GetOcrWord") {
+            ptr::null_mut()
+        } else {
+            found as *mut Word as *mut c_void
+        }
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrWordContent(
+    word: *mut c_void,
+    content: *mut *const c_char,
+) -> i32 {
+    check!("GetOcrWordContent", word, content);
+    unsafe {
+        *content = if scripted_null("GetOcrWordContent") {
+            ptr::null()
+        } else {
+            (*(word as *mut Word)).text.as_ptr()
+        }
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrWordBoundingBox(
+    word: *mut c_void,
+    bbox: *mut *const RawBBox,
+) -> i32 {
+    check!("GetOcrWordBoundingBox", word, bbox);
+    unsafe {
+        *bbox = if scripted_null("GetOcrWordBoundingBox") {
+            ptr::null()
+        } else {
+            &(*(word as *mut Word)).bounding_box
+        }
+    };
+    0
+}
+
+/// # Safety
+///
+/// Pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn GetOcrWordConfidence(word: *mut c_void, confidence: *mut f32) -> i32 {
+    check!("GetOcrWordConfidence", word, confidence);
+    unsafe { *confidence = (*(word as *mut Word)).confidence };
+    0
+}
+
+/// # Safety
+///
+/// `result` must be null or created by `RunOcrPipeline`, and not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn ReleaseOcrResult(result: *mut c_void) {
+    unsafe { release_handle::<OcrResult>(result) };
+}
+
+/// # Safety
+///
+/// `init_options` must be null or created by `CreateOcrInitOptions`, and not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn ReleaseOcrInitOptions(init_options: *mut c_void) {
+    unsafe { release_handle::<InitOptions>(init_options) };
+}
+
+/// # Safety
+///
+/// `pipeline` must be null or created by `CreateOcrPipeline`, and not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn ReleaseOcrPipeline(pipeline: *mut c_void) {
+    unsafe { release_handle::<Pipeline>(pipeline) };
+}
+
+/// # Safety
+///
+/// `process_options` must be null or created by `CreateOcrProcessOptions`, and not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn ReleaseOcrProcessOptions(process_options: *mut c_void) {
+    unsafe { release_handle::<ProcessOptions>(process_options) };
+}
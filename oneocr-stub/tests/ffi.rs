@@ -0,0 +1,241 @@
+//! End-to-end tests of the `oneocr-rs` FFI paths against the stub library.
+
+use image::{ImageBuffer, Rgba};
+use libloading::Library;
+use oneocr_rs::{DllBackend, LineStyle, OcrEngine, OcrOptions, OneOcrError};
+use std::ffi::{CString, c_char};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// The stub keeps its script in global state, so tests must not run concurrently.
+static STUB_LOCK: Mutex<()> = Mutex::new(());
+
+const SCRIPT: &str = r#"{
+    "image_angle": 1.25,
+    "lines": [
+        {
+            "text": "Hello world",
+            "bounding_box": [10, 10, 100, 10, 100, 30, 10, 30],
+            "style": 1,
+            "style_confidence": 0.9,
+            "words": [
+                { "text": "Hello", "bounding_box": [10, 10, 50, 10, 50, 30, 10, 30], "confidence": 0.98 },
+                { "text": "world", "bounding_box": [60, 10, 100, 10, 100, 30, 60, 30], "confidence": 0.87 }
+            ]
+        },
+        {
+            "text": "Handwritten",
+            "bounding_box": [10, 40, 100, 40, 100, 60, 10, 60],
+            "style": 0,
+            "style_confidence": 0.1,
+            "words": [
+                { "text": "Handwritten", "bounding_box": [10, 40, 100, 40, 100, 60, 10, 60], "confidence": 0.5 }
+            ]
+        }
+    ]
+}"#;
+
+struct Stub {
+    library: Library,
+    path: PathBuf,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Stub {
+    /// Loads the stub and sets its script, merged with `overrides`.
+    fn with_script(overrides: &str) -> Self {
+        let guard = STUB_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let path = std::env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join(libloading::library_filename("oneocr_stub"));
+        let library = unsafe { Library::new(&path) }.unwrap();
+
+        let mut script: serde_json::Value = serde_json::from_str(SCRIPT).unwrap();
+        let overrides: serde_json::Value = serde_json::from_str(overrides).unwrap();
+        for (key, value) in overrides.as_object().unwrap() {
+            script[key] = value.clone();
+        }
+        let script = CString::new(script.to_string()).unwrap();
+        let set_script = unsafe {
+            library
+                .get::<unsafe extern "system" fn(*const c_char) -> i32>(b"StubSetScript\0")
+                .unwrap()
+        };
+        assert_eq!(unsafe { set_script(script.as_ptr()) }, 0);
+
+        Self {
+            library,
+            path,
+            _guard: guard,
+        }
+    }
+
+    fn engine(&self, include_word_level_details: bool) -> Result<OcrEngine, OneOcrError> {
+        let mut options = OcrOptions::default();
+        options.include_word_level_details = include_word_level_details;
+        options.model_path = Some(model_dir().join("oneocr.onemodel"));
+        OcrEngine::new_with_backend(DllBackend::load(&self.path)?, options)
+    }
+
+    fn live_objects(&self) -> i64 {
+        unsafe {
+            self.library
+                .get::<unsafe extern "system" fn() -> i64>(b"StubLiveObjectCount\0")
+                .unwrap()()
+        }
+    }
+}
+
+fn model_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+}
+
+fn image() -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    ImageBuffer::from_pixel(32, 16, Rgba([0, 0, 0, 255]))
+}
+
+fn api_error(err: OneOcrError) -> (i32, String) {
+    match err {
+        OneOcrError::OcrApiError { result, message } => (result, message),
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn result_is_read_through_ffi() {
+    let stub = Stub::with_script("{}");
+    let engine = stub.engine(true).unwrap();
+
+    let result = engine.run(image().into()).unwrap();
+
+    assert_eq!(result.image_angle, 1.25);
+    assert_eq!(result.lines.len(), 2);
+    let line = &result.lines[0];
+    assert_eq!(line.text, "Hello world");
+    assert_eq!(line.bounding_box.top_right.x, 100.0);
+    assert_eq!(line.bounding_box.bottom_left.y, 30.0);
+    let words = line.words.as_ref().unwrap();
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[1].text, "world");
+    assert_eq!(words[1].confidence, 0.87);
+    assert_eq!(words[1].bounding_box.top_left.x, 60.0);
+    assert_eq!(line.style, LineStyle::Printed);
+    assert_eq!(line.style_confidence, 0.9);
+    assert_eq!(result.lines[1].style, LineStyle::Handwritten);
+    assert!((result.lines[1].style_confidence - 0.9).abs() < 1e-6);
+
+    drop(result);
+    drop(engine);
+    assert_eq!(stub.live_objects(), 0);
+}
+
+#[test]
+fn process_options_are_applied() {
+    let stub = Stub::with_script("{}");
+    let engine = stub.engine(false).unwrap();
+
+    engine.set_max_recognition_line_count(1).unwrap();
+    engine.set_resize_resolution(640, 480).unwrap();
+
+    assert_eq!(engine.get_max_recognition_line_count().unwrap(), 1);
+    assert_eq!(engine.get_resize_resolution().unwrap(), (640, 480));
+    let result = engine.run(image().into()).unwrap();
+    assert_eq!(result.lines.len(), 1);
+    assert!(result.lines[0].words.is_none());
+}
+
+#[test]
+fn error_codes_are_propagated_without_leaks() {
+    let stub = Stub::with_script(r#"{ "errors": { "GetOcrWordConfidence": 7 } }"#);
+    let engine = stub.engine(true).unwrap();
+
+    let (result, message) = api_error(engine.run(image().into()).unwrap_err());
+
+    assert_eq!(result, 7);
+    assert_eq!(message, "Failed to get word confidence");
+    drop(engine);
+    assert_eq!(stub.live_objects(), 0);
+}
+
+#[test]
+fn null_pointers_are_reported_without_leaks() {
+    for function in [
+        "RunOcrPipeline",
+        "GetOcrLine",
+        "GetOcrLineContent",
+        "GetOcrLineBoundingBox",
+        "GetOcrWord",
+        "GetOcrWordContent",
+        "GetOcrWordBoundingBox",
+    ] {
+        let stub = Stub::with_script(&format!(r#"{{ "null_pointers": ["{function}"] }}"#));
+        let engine = stub.engine(true).unwrap();
+
+        let (_, message) = api_error(engine.run(image().into()).unwrap_err());
+
+        assert_eq!(message, format!("{function} returned a null pointer."));
+        drop(engine);
+        assert_eq!(stub.live_objects(), 0, "{function} leaked objects");
+    }
+}
+
+#[test]
+fn failed_engine_creation_releases_handles() {
+    let stub = Stub::with_script(r#"{ "null_pointers": ["CreateOcrPipeline"] }"#);
+
+    let (_, message) = api_error(stub.engine(false).unwrap_err());
+
+    assert_eq!(message, "CreateOcrPipeline returned a null pointer.");
+    assert_eq!(stub.live_objects(), 0);
+}
+
+#[test]
+fn failed_pipeline_creation_propagates_error_code() {
+    let stub = Stub::with_script(r#"{ "errors": { "CreateOcrPipeline": 12 } }"#);
+
+    let (result, message) = api_error(stub.engine(false).unwrap_err());
+
+    assert_eq!(result, 12);
+    assert_eq!(message, "Failed to create OCR pipeline");
+    assert_eq!(stub.live_objects(), 0);
+}
+
+#[test]
+fn model_file_is_found_in_search_paths() {
+    let stub = Stub::with_script("{}");
+    let mut options = OcrOptions::default();
+    options.model_search_paths = vec![PathBuf::from("does-not-exist"), model_dir()];
+
+    assert!(OcrEngine::new_with_backend(DllBackend::load(&stub.path).unwrap(), options).is_ok());
+}
+
+#[test]
+fn missing_model_file_lists_searched_locations() {
+    let stub = Stub::with_script("{}");
+    let mut options = OcrOptions::default();
+    options.model_search_paths = vec![PathBuf::from("does-not-exist")];
+
+    let err =
+        OcrEngine::new_with_backend(DllBackend::load(&stub.path).unwrap(), options).unwrap_err();
+
+    match err {
+        OneOcrError::ModelFileNotFound { searched } => {
+            let exe_dir = std::env::current_exe()
+                .unwrap()
+                .parent()
+                .unwrap()
+                .to_path_buf();
+            assert_eq!(
+                searched[0],
+                Path::new("does-not-exist").join("oneocr.onemodel")
+            );
+            assert!(searched.contains(&exe_dir.join("oneocr.onemodel")));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+    assert_eq!(stub.live_objects(), 0);
+}
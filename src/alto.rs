@@ -0,0 +1,158 @@
+use crate::ocr_result::OcrResult;
+use crate::ocr_word::OcrWord;
+use crate::xml::escape;
+use std::fmt::Write;
+
+/// Metadata written to the `Description` of an ALTO document, see [`OcrResult::to_alto_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AltoOptions {
+    /// The file name of the recognized image, written as `sourceImageInformation`.
+    pub source_image_file_name: Option<String>,
+    /// The OCR model, written as the `applicationDescription` of the processing software.
+    pub model_name: String,
+}
+
+impl Default for AltoOptions {
+    fn default() -> Self {
+        AltoOptions {
+            source_image_file_name: None,
+            model_name: "OneOCR (oneocr.onemodel)".to_string(),
+        }
+    }
+}
+
+impl OcrResult {
+    /// Renders the result as an [ALTO v4](https://www.loc.gov/standards/alto/) document with the
+    /// default [`AltoOptions`].
+    pub fn to_alto(&self) -> String {
+        self.to_alto_with(&AltoOptions::default())
+    }
+
+    /// Renders the result as an ALTO v4 document, in pixels.
+    ///
+    /// Each of the [`blocks`](Self::blocks), grouped on the fly if the result has none, is a
+    /// `TextBlock` with the image angle as `ROTATION`. ALTO has no paragraph level, so the
+    /// lines of a block's paragraphs follow each other in its `TextBlock`. Each line is a
+    /// `TextLine`, and each word a `String` with its confidence as `WC`, separated by `SP`
+    /// elements. Without word-level details, each line holds a single `String`. Positions are
+    /// the axis-aligned bounds of the quadrilateral bounding boxes.
+    pub fn to_alto_with(&self, options: &AltoOptions) -> String {
+        let (width, height) = self.page_size();
+        let mut alto = String::new();
+        alto.push_str(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<alto xmlns=\"http://www.loc.gov/standards/alto/ns-v4#\" ",
+            "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+            "xsi:schemaLocation=\"http://www.loc.gov/standards/alto/ns-v4# ",
+            "http://www.loc.gov/alto/v4/alto-4-4.xsd\">\n",
+            "  <Description>\n",
+            "    <MeasurementUnit>pixel</MeasurementUnit>\n",
+        ));
+        // Writing to a `String` never fails.
+        if let Some(file_name) = &options.source_image_file_name {
+            let _ = writeln!(
+                alto,
+                "    <sourceImageInformation>\n      <fileName>{}</fileName>\n    </sourceImageInformation>",
+                escape(file_name)
+            );
+        }
+        let _ = write!(
+            alto,
+            concat!(
+                "    <Processing ID=\"OCR_0\">\n",
+                "      <processingCategory>contentGeneration</processingCategory>\n",
+                "      <processingStepDescription>text recognition</processingStepDescription>\n",
+                "      <processingSoftware>\n",
+                "        <softwareName>oneocr-rs</softwareName>\n",
+                "        <softwareVersion>{}</softwareVersion>\n",
+                "        <applicationDescription>{}</applicationDescription>\n",
+                "      </processingSoftware>\n",
+                "    </Processing>\n",
+                "  </Description>\n",
+                "  <Layout>\n",
+                "    <Page ID=\"page_1\" PHYSICAL_IMG_NR=\"1\" WIDTH=\"{width}\" HEIGHT=\"{height}\" PROCESSINGREFS=\"OCR_0\">\n",
+                "      <PrintSpace HPOS=\"0\" VPOS=\"0\" WIDTH=\"{width}\" HEIGHT=\"{height}\">\n",
+            ),
+            env!("CARGO_PKG_VERSION"),
+            escape(&options.model_name),
+            width = width,
+            height = height,
+        );
+
+        for (b, block) in self.current_blocks().iter().enumerate() {
+            let _ = write!(
+                alto,
+                "        <TextBlock ID=\"block_{}\"{}",
+                b + 1,
+                position(block.bounding_box.pixel_bounds())
+            );
+            if self.image_angle != 0.0 {
+                let _ = write!(alto, " ROTATION=\"{}\"", self.image_angle);
+            }
+            alto.push_str(">\n");
+            for &l in block
+                .paragraphs
+                .iter()
+                .flat_map(|paragraph| &paragraph.lines)
+            {
+                let line = &self.lines[l];
+                let _ = writeln!(
+                    alto,
+                    "          <TextLine ID=\"line_{}\"{}>",
+                    l + 1,
+                    position(line.bounding_box.pixel_bounds())
+                );
+                match &line.words {
+                    Some(words) => write_words(&mut alto, l + 1, words),
+                    None => {
+                        let _ = writeln!(
+                            alto,
+                            "            <String ID=\"string_{}_1\"{} CONTENT=\"{}\"/>",
+                            l + 1,
+                            position(line.bounding_box.pixel_bounds()),
+                            escape(&line.text)
+                        );
+                    }
+                }
+                alto.push_str("          </TextLine>\n");
+            }
+            alto.push_str("        </TextBlock>\n");
+        }
+        alto.push_str("      </PrintSpace>\n    </Page>\n  </Layout>\n</alto>\n");
+        alto
+    }
+}
+
+/// Writes the `String`s of a line's words, with an `SP` in each gap between them.
+fn write_words(alto: &mut String, line: usize, words: &[OcrWord]) {
+    let mut previous: Option<[u32; 4]> = None;
+    for (w, word) in words.iter().enumerate() {
+        let bounds = word.bounding_box.pixel_bounds();
+        if let Some([_, top, right, _]) = previous {
+            let _ = writeln!(
+                alto,
+                "            <SP HPOS=\"{right}\" VPOS=\"{top}\" WIDTH=\"{}\"/>",
+                bounds[0].saturating_sub(right)
+            );
+        }
+        let _ = writeln!(
+            alto,
+            "            <String ID=\"string_{line}_{}\"{} CONTENT=\"{}\" WC=\"{:.3}\"/>",
+            w + 1,
+            position(bounds),
+            escape(&word.text),
+            word.confidence.clamp(0.0, 1.0)
+        );
+        previous = Some(bounds);
+    }
+}
+
+/// Formats the `HPOS`, `VPOS`, `WIDTH` and `HEIGHT` attributes of `[left, top, right, bottom]`.
+fn position([left, top, right, bottom]: [u32; 4]) -> String {
+    format!(
+        " HPOS=\"{left}\" VPOS=\"{top}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+        right.saturating_sub(left),
+        bottom.saturating_sub(top)
+    )
+}
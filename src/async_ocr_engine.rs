@@ -0,0 +1,235 @@
+use crate::dll_backend::DllBackend;
+use crate::errors::OneOcrError;
+use crate::ocr_backend::OcrBackend;
+use crate::ocr_engine::OcrEngine;
+use crate::ocr_result::OcrResult;
+use crate::{ImageInput, OcrOptions, RunOptions};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Configuration of the workers and queue of an [`AsyncOcrEngine`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AsyncOptions {
+    /// The number of worker threads, each with its own engine and pipeline. Default is 1.
+    pub workers: usize,
+
+    /// The number of requests that can wait for a worker. Default is 16.
+    ///
+    /// Once the queue is full, [`run`](AsyncOcrEngine::run) waits for a free slot and
+    /// [`try_run`](AsyncOcrEngine::try_run) fails with [`OneOcrError::QueueFull`].
+    pub queue_capacity: usize,
+
+    /// The timeout applied to requests without their own timeout. Default is `None`.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for AsyncOptions {
+    fn default() -> Self {
+        AsyncOptions {
+            workers: 1,
+            queue_capacity: 16,
+            timeout: None,
+        }
+    }
+}
+
+/// A queued OCR request.
+struct Job {
+    input: ImageInput,
+    run_options: Option<RunOptions>,
+    reply: oneshot::Sender<Result<OcrResult, OneOcrError>>,
+}
+
+/// The `AsyncOcrEngine` struct runs OCR on dedicated worker threads and returns futures,
+/// so recognitions don't block the async executor.
+///
+/// Requests wait in a bounded queue until a worker picks them up. Dropping a request's future
+/// cancels it: a queued request is skipped, and the result of a running one is discarded.
+/// Timeouts work the same way; they need a tokio runtime with the time driver enabled.
+///
+/// The workers stop once the engine is dropped and the queued requests are done.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::{AsyncOcrEngine, AsyncOptions, ImageInput, OcrOptions};
+/// use std::path::PathBuf;
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), oneocr_rs::OneOcrError> {
+/// let mut async_options = AsyncOptions::default();
+/// async_options.workers = 2;
+/// async_options.timeout = Some(Duration::from_secs(5));
+/// let engine = AsyncOcrEngine::new(OcrOptions::default(), async_options)?;
+/// let result = engine.run(ImageInput::FilePath(PathBuf::from("sample.jpg"))).await?;
+/// println!("{} lines", result.lines.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncOcrEngine {
+    jobs: mpsc::Sender<Job>,
+    ocr_options: OcrOptions,
+    async_options: AsyncOptions,
+}
+
+impl AsyncOcrEngine {
+    /// Creates an async engine on `oneocr.dll`, loaded once from the default location.
+    pub fn new(ocr_options: OcrOptions, async_options: AsyncOptions) -> Result<Self, OneOcrError> {
+        Self::new_with_backend(DllBackend::new()?, ocr_options, async_options)
+    }
+
+    /// Creates an async engine whose workers share a custom [`OcrBackend`].
+    pub fn new_with_backend(
+        backend: impl OcrBackend + 'static,
+        ocr_options: OcrOptions,
+        async_options: AsyncOptions,
+    ) -> Result<Self, OneOcrError> {
+        Self::new_with_shared_backend(Arc::new(backend), ocr_options, async_options)
+    }
+
+    pub(crate) fn new_with_shared_backend(
+        backend: Arc<dyn OcrBackend>,
+        ocr_options: OcrOptions,
+        async_options: AsyncOptions,
+    ) -> Result<Self, OneOcrError> {
+        if async_options.workers == 0 {
+            return Err(OneOcrError::InvalidPoolSize(async_options.workers));
+        }
+        if async_options.queue_capacity == 0 {
+            return Err(OneOcrError::InvalidQueueCapacity(
+                async_options.queue_capacity,
+            ));
+        }
+        // Create every engine before starting any worker, so a failure leaves nothing running.
+        let engines = (0..async_options.workers)
+            .map(|_| OcrEngine::new_with_shared_backend(backend.clone(), ocr_options.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (sender, receiver) = mpsc::channel(async_options.queue_capacity);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for (i, engine) in engines.into_iter().enumerate() {
+            let receiver = receiver.clone();
+            std::thread::Builder::new()
+                .name(format!("oneocr-worker-{i}"))
+                .spawn(move || work(engine, &receiver))
+                .map_err(|e| OneOcrError::Other(format!("Failed to start OCR worker: {e}")))?;
+        }
+
+        Ok(Self {
+            jobs: sender,
+            ocr_options,
+            async_options,
+        })
+    }
+
+    /// Returns the options the workers' engines were created with.
+    pub fn options(&self) -> &OcrOptions {
+        &self.ocr_options
+    }
+
+    /// Returns the worker and queue configuration.
+    pub fn async_options(&self) -> &AsyncOptions {
+        &self.async_options
+    }
+
+    /// Returns the number of requests waiting for a worker.
+    pub fn queue_len(&self) -> usize {
+        self.async_options.queue_capacity - self.jobs.capacity()
+    }
+
+    /// Runs OCR on an image, waiting for room in the queue if it is full.
+    pub async fn run(&self, input: ImageInput) -> Result<OcrResult, OneOcrError> {
+        self.submit(input, None, true, self.async_options.timeout)
+            .await
+    }
+
+    /// Runs OCR on an image with options that only apply to this call,
+    /// see [`OcrEngine::run_with`].
+    pub async fn run_with(
+        &self,
+        input: ImageInput,
+        run_options: &RunOptions,
+    ) -> Result<OcrResult, OneOcrError> {
+        run_options.validate()?;
+        self.submit(input, Some(*run_options), true, self.async_options.timeout)
+            .await
+    }
+
+    /// Runs OCR on an image, failing with [`OneOcrError::Timeout`] if the result isn't ready
+    /// within `timeout`, including the time spent in the queue.
+    pub async fn run_with_timeout(
+        &self,
+        input: ImageInput,
+        timeout: Duration,
+    ) -> Result<OcrResult, OneOcrError> {
+        self.submit(input, None, true, Some(timeout)).await
+    }
+
+    /// Runs OCR on an image, failing right away with [`OneOcrError::QueueFull`]
+    /// instead of waiting if the queue is full.
+    pub async fn try_run(&self, input: ImageInput) -> Result<OcrResult, OneOcrError> {
+        self.submit(input, None, false, self.async_options.timeout)
+            .await
+    }
+
+    async fn submit(
+        &self,
+        input: ImageInput,
+        run_options: Option<RunOptions>,
+        wait_for_room: bool,
+        timeout: Option<Duration>,
+    ) -> Result<OcrResult, OneOcrError> {
+        let request = async {
+            let (reply, result) = oneshot::channel();
+            let job = Job {
+                input,
+                run_options,
+                reply,
+            };
+            if wait_for_room {
+                self.jobs
+                    .send(job)
+                    .await
+                    .map_err(|_| OneOcrError::WorkerStopped)?;
+            } else {
+                self.jobs.try_send(job).map_err(|e| match e {
+                    mpsc::error::TrySendError::Full(_) => OneOcrError::QueueFull,
+                    mpsc::error::TrySendError::Closed(_) => OneOcrError::WorkerStopped,
+                })?;
+            }
+            // The reply is dropped without a result only if the worker panicked.
+            result.await.map_err(|_| OneOcrError::WorkerStopped)?
+        };
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, request)
+                .await
+                .map_err(|_| OneOcrError::Timeout(timeout))?,
+            None => request.await,
+        }
+    }
+}
+
+/// Runs queued jobs on `engine` until the engine is dropped and the queue is empty.
+fn work(engine: OcrEngine, jobs: &Mutex<mpsc::Receiver<Job>>) {
+    loop {
+        let job = jobs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .blocking_recv();
+        let Some(job) = job else {
+            return;
+        };
+        // The caller dropped the future or timed out while the job was queued.
+        if job.reply.is_closed() {
+            continue;
+        }
+        let result = match job.run_options {
+            Some(run_options) => engine.run_with(job.input, &run_options),
+            None => engine.run(job.input),
+        };
+        let _ = job.reply.send(result);
+    }
+}
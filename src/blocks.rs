@@ -0,0 +1,318 @@
+use crate::bounding_box::BoundingBox;
+use crate::geometry::Rect;
+use crate::ocr_line::OcrLine;
+use crate::ocr_result::OcrResult;
+use crate::ocr_word::OcrWord;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A paragraph: consecutive lines of a [`Block`] read as running text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Paragraph {
+    /// The box enclosing the lines, oriented like the first line.
+    pub bounding_box: BoundingBox,
+    /// The indices of the lines in [`OcrResult::lines`], in reading order.
+    pub lines: Vec<usize>,
+}
+
+/// A block of text: paragraphs of the same column and font size, separated from the rest of
+/// the page by whitespace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    /// The box enclosing the paragraphs, oriented like the first line.
+    pub bounding_box: BoundingBox,
+    pub paragraphs: Vec<Paragraph>,
+}
+
+/// Options for [`OcrResult::group_blocks_with`]. Distances are in line heights, the mean
+/// height of the two lines compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct BlockOptions {
+    /// The widest vertical gap between two lines of a paragraph.
+    pub max_line_gap: f32,
+    /// The widest vertical gap between two paragraphs of a block.
+    pub max_paragraph_gap: f32,
+    /// The narrowest first-line indent that starts a new paragraph.
+    pub min_indent: f32,
+    /// How far before the right edge of a block a left-aligned line must end for the next
+    /// line to start a new paragraph.
+    pub min_short_line: f32,
+    /// The largest ratio between the heights of two lines of a block. Lines of a different
+    /// font size, such as headings, start a new block.
+    pub max_height_ratio: f32,
+}
+
+impl Default for BlockOptions {
+    fn default() -> Self {
+        Self {
+            max_line_gap: 0.8,
+            max_paragraph_gap: 1.5,
+            min_indent: 1.0,
+            min_short_line: 3.0,
+            max_height_ratio: 1.3,
+        }
+    }
+}
+
+impl Paragraph {
+    fn new(first_line: usize, result: &OcrResult) -> Self {
+        Paragraph {
+            bounding_box: result.lines[first_line].bounding_box,
+            lines: vec![first_line],
+        }
+    }
+
+    fn push(&mut self, line: usize, result: &OcrResult) {
+        self.bounding_box = self.bounding_box.merge(&result.lines[line].bounding_box);
+        self.lines.push(line);
+    }
+
+    /// Returns the lines of the paragraph.
+    pub fn ocr_lines<'a>(&'a self, result: &'a OcrResult) -> impl Iterator<Item = &'a OcrLine> {
+        self.lines
+            .iter()
+            .filter_map(|index| result.lines.get(*index))
+    }
+
+    /// Returns the words of the paragraph, for results with word-level details.
+    pub fn words<'a>(&'a self, result: &'a OcrResult) -> impl Iterator<Item = &'a OcrWord> {
+        self.ocr_lines(result)
+            .flat_map(|line| line.words.iter().flatten())
+    }
+
+    /// Returns the text of the paragraph as running text: lines are joined with a space,
+    /// words hyphenated at the end of a line are joined back together, and lines of scripts
+    /// written without spaces, such as Chinese, Japanese and Thai, are joined directly.
+    ///
+    /// A hyphen at the end of a line is dropped if the next line starts with a lowercase
+    /// letter, and kept otherwise.
+    pub fn text(&self, result: &OcrResult) -> String {
+        let mut text = String::new();
+        for line in self.ocr_lines(result) {
+            let line = line.text.trim();
+            let (Some(last), Some(next)) = (text.chars().last(), line.chars().next()) else {
+                text.push_str(line);
+                continue;
+            };
+            let before_hyphen = text.chars().rev().nth(1);
+            if last == '-' && before_hyphen.is_some_and(char::is_alphanumeric) {
+                // Keep the hyphen of compounds broken across lines, such as "COVID-19".
+                if next.is_lowercase() {
+                    text.pop();
+                }
+            } else if !(is_unspaced(last) && is_unspaced(next)) {
+                text.push(' ');
+            }
+            text.push_str(line);
+        }
+        text
+    }
+}
+
+impl Block {
+    /// Returns the lines of the block.
+    pub fn ocr_lines<'a>(&'a self, result: &'a OcrResult) -> impl Iterator<Item = &'a OcrLine> {
+        self.paragraphs
+            .iter()
+            .flat_map(|paragraph| paragraph.ocr_lines(result))
+    }
+
+    /// Returns the words of the block, for results with word-level details.
+    pub fn words<'a>(&'a self, result: &'a OcrResult) -> impl Iterator<Item = &'a OcrWord> {
+        self.ocr_lines(result)
+            .flat_map(|line| line.words.iter().flatten())
+    }
+
+    /// Returns the text of the block, one paragraph per line, see [`Paragraph::text`].
+    pub fn text(&self, result: &OcrResult) -> String {
+        self.paragraphs
+            .iter()
+            .map(|paragraph| paragraph.text(result))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Fingerprints of the lines and blocks of a result when the blocks were grouped, to tell
+/// whether the lines changed since.
+///
+/// It is bookkeeping rather than data, so it is neither serialized nor compared.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct GroupedLines(Option<(u64, u64)>);
+
+impl PartialEq for GroupedLines {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl GroupedLines {
+    /// Records the lines and blocks of `result` as grouped together.
+    pub(crate) fn of(result: &OcrResult) -> Self {
+        GroupedLines(Some((
+            lines_fingerprint(result),
+            blocks_fingerprint(result),
+        )))
+    }
+}
+
+fn hash_box(bounding_box: &BoundingBox, hasher: &mut impl Hasher) {
+    for corner in bounding_box.corners() {
+        (corner.x.to_bits(), corner.y.to_bits()).hash(hasher);
+    }
+}
+
+fn lines_fingerprint(result: &OcrResult) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for line in &result.lines {
+        line.text.hash(&mut hasher);
+        hash_box(&line.bounding_box, &mut hasher);
+    }
+    hasher.finish()
+}
+
+fn blocks_fingerprint(result: &OcrResult) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for block in &result.blocks {
+        hash_box(&block.bounding_box, &mut hasher);
+        for paragraph in &block.paragraphs {
+            hash_box(&paragraph.bounding_box, &mut hasher);
+            paragraph.lines.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+impl OcrResult {
+    /// Groups the lines into blocks and paragraphs with the default [`BlockOptions`].
+    pub fn group_blocks(&self) -> Vec<Block> {
+        self.group_blocks_with(&BlockOptions::default())
+    }
+
+    /// Groups the lines into blocks and paragraphs.
+    ///
+    /// Lines are visited in [reading order](Self::reading_order). A line starts a new block
+    /// if it is far below the previous line, doesn't overlap it horizontally, e.g. at the top
+    /// of the next column, or is of a different height. Within a block, a line starts a new
+    /// paragraph if it is below a larger gap than the lines of a paragraph, indented relative
+    /// to the previous line, or follows a left-aligned line that ended well before the right
+    /// edge of the block.
+    pub fn group_blocks_with(&self, options: &BlockOptions) -> Vec<Block> {
+        let bounds = self.deskewed_bounds();
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut previous: Option<usize> = None;
+        let mut block_right = 0.0;
+        for index in self.reading_order() {
+            let line = &bounds[index];
+            let (new_block, new_paragraph) = match previous {
+                None => (true, true),
+                Some(previous) => {
+                    let previous_line = &bounds[previous];
+                    let (height, previous_height) = (
+                        self.lines[index].bounding_box.height().max(1.0),
+                        self.lines[previous].bounding_box.height().max(1.0),
+                    );
+                    let line_height = (height + previous_height) / 2.0;
+                    let gap = (line.y - previous_line.bottom()) / line_height;
+                    let indent = (line.x - previous_line.x) / line_height;
+                    let new_block = gap > options.max_paragraph_gap
+                        || gap < -0.5
+                        || overlap(line, previous_line) <= 0.0
+                        || height.max(previous_height) / height.min(previous_height)
+                            > options.max_height_ratio;
+                    let short_line = indent.abs() < options.min_indent
+                        && (block_right - previous_line.right()) / line_height
+                            > options.min_short_line;
+                    let new_paragraph =
+                        gap > options.max_line_gap || indent >= options.min_indent || short_line;
+                    (new_block, new_paragraph)
+                }
+            };
+
+            match blocks.last_mut() {
+                Some(block) if !new_block => {
+                    match block.paragraphs.last_mut() {
+                        Some(paragraph) if !new_paragraph => paragraph.push(index, self),
+                        _ => block.paragraphs.push(Paragraph::new(index, self)),
+                    }
+                    block.bounding_box = block.bounding_box.merge(&self.lines[index].bounding_box);
+                    block_right = block_right.max(line.right());
+                }
+                _ => {
+                    blocks.push(Block {
+                        bounding_box: self.lines[index].bounding_box,
+                        paragraphs: vec![Paragraph::new(index, self)],
+                    });
+                    block_right = line.right();
+                }
+            }
+            previous = Some(index);
+        }
+        blocks
+    }
+
+    /// Sets [`blocks`](Self::blocks) to the blocks grouped with the default [`BlockOptions`].
+    pub fn with_blocks(mut self) -> Self {
+        self.blocks = self.group_blocks();
+        self.grouped_lines = GroupedLines::of(&self);
+        self
+    }
+
+    /// Returns the paragraphs of all blocks, in reading order.
+    pub fn paragraphs(&self) -> impl Iterator<Item = &Paragraph> {
+        self.blocks.iter().flat_map(|block| &block.paragraphs)
+    }
+
+    /// Whether [`blocks`](Self::blocks) don't hold every line exactly once, e.g. because lines
+    /// were added, removed or filtered after grouping, or the lines changed since
+    /// [`with_blocks`](Self::with_blocks) or [`from_json`](Self::from_json) while the blocks
+    /// didn't, e.g. because lines were reordered or replaced.
+    pub(crate) fn blocks_are_stale(&self) -> bool {
+        if let GroupedLines(Some((lines, blocks))) = self.grouped_lines
+            && blocks == blocks_fingerprint(self)
+            && lines != lines_fingerprint(self)
+        {
+            return true;
+        }
+        let mut grouped = vec![false; self.lines.len()];
+        for &index in self.paragraphs().flat_map(|paragraph| &paragraph.lines) {
+            match grouped.get_mut(index) {
+                Some(grouped) if !*grouped => *grouped = true,
+                _ => return true,
+            }
+        }
+        grouped.contains(&false)
+    }
+
+    /// Returns [`blocks`](Self::blocks), or the lines grouped afresh if they are missing or
+    /// stale.
+    pub(crate) fn current_blocks(&self) -> Cow<'_, [Block]> {
+        if self.blocks_are_stale() {
+            Cow::Owned(self.group_blocks())
+        } else {
+            Cow::Borrowed(&self.blocks)
+        }
+    }
+}
+
+/// Returns the width of the horizontal overlap of two rectangles, negative if they are apart.
+fn overlap(a: &Rect, b: &Rect) -> f32 {
+    a.right().min(b.right()) - a.x.max(b.x)
+}
+
+/// Whether `c` belongs to a script written without spaces between words.
+fn is_unspaced(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}'     // Thai, Lao
+        | '\u{1000}'..='\u{109F}'   // Myanmar
+        | '\u{1780}'..='\u{17FF}'   // Khmer
+        | '\u{3000}'..='\u{30FF}'   // CJK punctuation, Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK compatibility ideographs
+        | '\u{FF00}'..='\u{FFEF}'   // Full-width forms
+    )
+}
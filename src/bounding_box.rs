@@ -1,9 +1,9 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::ffi::RawBBox;
 
 /// This `Point` struct represents a point in 2D space with X and Y coordinates.
-#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
@@ -16,7 +16,7 @@ impl std::fmt::Display for Point {
 }
 
 /// This `BoundingBox` struct represents a bounding box in 2D space, used for OCR to tightly enclose detected text.
-#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub struct BoundingBox {
     pub top_left: Point,
     pub top_right: Point,
@@ -48,6 +48,21 @@ impl BoundingBox {
     }
 }
 
+impl BoundingBox {
+    /// Returns the smallest integer rectangle `[left, top, right, bottom]` enclosing the box,
+    /// clamped to non-negative pixel coordinates.
+    pub(crate) fn pixel_bounds(&self) -> [u32; 4] {
+        let bounds = self.bounds();
+        // `as` saturates, so negative coordinates become 0.
+        [
+            bounds.x.floor() as u32,
+            bounds.y.floor() as u32,
+            bounds.right().ceil() as u32,
+            bounds.bottom().ceil() as u32,
+        ]
+    }
+}
+
 impl std::fmt::Display for BoundingBox {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
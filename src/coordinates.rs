@@ -0,0 +1,201 @@
+use crate::bounding_box::BoundingBox;
+use crate::errors::OneOcrError;
+use crate::geometry::{Affine, Rect};
+use crate::ocr_result::OcrResult;
+use serde::{Deserialize, Serialize};
+
+/// A transform applied to an image before OCR, e.g. a crop or resize, recorded on an
+/// [`OcrResult`] so its boxes can be mapped back to the original image.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PreprocessingStep {
+    /// Maps coordinates of the image before this step to coordinates after it.
+    pub transform: Affine,
+    /// The width of the image before this step, or 0 if unknown.
+    pub source_width: u32,
+    /// The height of the image before this step, or 0 if unknown.
+    pub source_height: u32,
+}
+
+impl PreprocessingStep {
+    /// Creates a step from any transform of an image of the given size.
+    pub fn new(transform: Affine, source_width: u32, source_height: u32) -> Self {
+        PreprocessingStep {
+            transform,
+            source_width,
+            source_height,
+        }
+    }
+
+    /// A crop, or a tile, of an image of the given size, starting at `(x, y)`.
+    pub fn crop(x: u32, y: u32, source_width: u32, source_height: u32) -> Self {
+        Self::new(
+            Affine::translate(-(x as f32), -(y as f32)),
+            source_width,
+            source_height,
+        )
+    }
+
+    /// A resize of an image of the given size to `width` by `height`.
+    ///
+    /// Fails with [`OneOcrError::InvalidTransform`] if the source size is 0.
+    pub fn resize(
+        source_width: u32,
+        source_height: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, OneOcrError> {
+        if source_width == 0 || source_height == 0 {
+            return Err(OneOcrError::InvalidTransform(format!(
+                "can't resize an image of {source_width}x{source_height} pixels"
+            )));
+        }
+        Ok(Self::new(
+            Affine::scale(
+                width as f32 / source_width as f32,
+                height as f32 / source_height as f32,
+            ),
+            source_width,
+            source_height,
+        ))
+    }
+
+    /// A rotation of an image of the given size by `degrees`, clockwise on screen, onto a
+    /// canvas just large enough to hold it, like `image::imageops::rotate90`.
+    pub fn rotate(degrees: f32, source_width: u32, source_height: u32) -> Self {
+        let rotation = Affine::rotate(degrees);
+        let image = Rect::new(0.0, 0.0, source_width as f32, source_height as f32);
+        let bounds = BoundingBox::from(image).transform(&rotation).bounds();
+        Self::new(
+            rotation.then(&Affine::translate(-bounds.x, -bounds.y)),
+            source_width,
+            source_height,
+        )
+    }
+}
+
+impl OcrResult {
+    /// Records a transform that was applied to the image before it was recognized.
+    ///
+    /// Record the steps in the order they were applied. The boxes don't change; they are
+    /// mapped back through every step by [`to_original_coordinates`](Self::to_original_coordinates).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oneocr_rs::{OcrEngine, PreprocessingStep};
+    ///
+    /// let engine = OcrEngine::new().unwrap();
+    /// let scan = image::open("scan.png").unwrap();
+    /// // Recognize the bottom half of the scan, at half the resolution.
+    /// let half = scan.crop_imm(0, 1000, 2000, 1000).thumbnail(1000, 500);
+    /// let result = engine
+    ///     .run(half.into())
+    ///     .unwrap()
+    ///     .with_preprocessing(PreprocessingStep::crop(0, 1000, 2000, 2000))
+    ///     .with_preprocessing(PreprocessingStep::resize(2000, 1000, 1000, 500).unwrap());
+    /// let in_scan = result.to_original_coordinates().unwrap();
+    /// ```
+    pub fn with_preprocessing(mut self, step: PreprocessingStep) -> Self {
+        self.preprocessing.push(step);
+        self
+    }
+
+    /// Returns the result with every line and word box transformed.
+    ///
+    /// The boxes move into a new coordinate space, so the transform is appended to
+    /// [`preprocessing`](Self::preprocessing) like any other step: afterwards,
+    /// [`to_original_coordinates`](Self::to_original_coordinates) undoes this transform too
+    /// and still returns boxes in the pixels of the original image. This is what lets
+    /// [`normalized`](Self::normalized) results map back. To keep the transformed boxes as
+    /// the original coordinates instead, clear `preprocessing` afterwards.
+    ///
+    /// The image size becomes the size of the transformed image's bounds; the image angle is
+    /// kept as is.
+    pub fn transform(&self, transform: &Affine) -> OcrResult {
+        let mut result = self.map_boxes(|bounding_box| bounding_box.transform(transform));
+        result.preprocessing.push(PreprocessingStep::new(
+            *transform,
+            self.image_width,
+            self.image_height,
+        ));
+        if self.image_width > 0 && self.image_height > 0 {
+            let image = BoundingBox::from(Rect::new(
+                0.0,
+                0.0,
+                self.image_width as f32,
+                self.image_height as f32,
+            ));
+            let bounds = image.transform(transform).bounds();
+            result.image_width = bounds.width.round().max(1.0) as u32;
+            result.image_height = bounds.height.round().max(1.0) as u32;
+        }
+        result
+    }
+
+    /// Returns the result with its boxes mapped back through every
+    /// [preprocessing step](Self::preprocessing), into the pixels of the original image.
+    ///
+    /// The image size becomes the size recorded by the first step, and the steps are cleared.
+    /// Fails with [`OneOcrError::InvalidTransform`] if a step can't be undone, such as a
+    /// scale by 0.
+    pub fn to_original_coordinates(&self) -> Result<OcrResult, OneOcrError> {
+        let Some(first) = self.preprocessing.first() else {
+            return Ok(self.clone());
+        };
+        let inverse = self
+            .preprocessing
+            .iter()
+            .fold(Affine::IDENTITY, |chain, step| chain.then(&step.transform))
+            .inverse()
+            .ok_or_else(|| {
+                OneOcrError::InvalidTransform("the preprocessing steps can't be undone".to_string())
+            })?;
+        let mut result = self.map_boxes(|bounding_box| bounding_box.transform(&inverse));
+        result.preprocessing.clear();
+        result.image_width = first.source_width;
+        result.image_height = first.source_height;
+        Ok(result)
+    }
+
+    /// Returns the result with coordinates relative to the image size, from 0 to 1, for
+    /// storage independent of the resolution. The image size becomes 1 by 1.
+    ///
+    /// Fails with [`OneOcrError::InvalidTransform`] if the image size is unknown.
+    pub fn normalized(&self) -> Result<OcrResult, OneOcrError> {
+        if self.image_width == 0 || self.image_height == 0 {
+            return Err(OneOcrError::InvalidTransform(
+                "normalizing needs the image size".to_string(),
+            ));
+        }
+        Ok(self.transform(&Affine::scale(
+            1.0 / self.image_width as f32,
+            1.0 / self.image_height as f32,
+        )))
+    }
+
+    /// Returns a [normalized](Self::normalized) result scaled to pixels of an image of the
+    /// given size.
+    pub fn denormalized(&self, width: u32, height: u32) -> OcrResult {
+        let mut result = self.transform(&Affine::scale(width as f32, height as f32));
+        result.image_width = width;
+        result.image_height = height;
+        result
+    }
+
+    fn map_boxes(&self, map: impl Fn(&BoundingBox) -> BoundingBox) -> OcrResult {
+        let mut result = self.clone();
+        for line in &mut result.lines {
+            line.bounding_box = map(&line.bounding_box);
+            for word in line.words.iter_mut().flatten() {
+                word.bounding_box = map(&word.bounding_box);
+            }
+        }
+        for block in &mut result.blocks {
+            block.bounding_box = map(&block.bounding_box);
+            for paragraph in &mut block.paragraphs {
+                paragraph.bounding_box = map(&paragraph.bounding_box);
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,767 @@
+use crate::bounding_box::{BoundingBox, Point};
+use crate::errors::OneOcrError;
+use crate::ocr_backend::{Handle, OcrBackend};
+use crate::onemodel::{ModelEntryKind, OneModel};
+use crate::result_store::{ResultStore, StoredLine, StoredResult, StoredWord};
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tract_onnx::prelude::*;
+
+type Graph = TypedRunnableModel<TypedModel>;
+
+/// Tuning parameters of the [`CpuBackend`] pipeline.
+///
+/// The defaults follow the usual DBNet (detection) and CRNN (recognition) conventions.
+/// Input sizes declared by the ONNX graphs always take precedence over these values.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct CpuOptions {
+    /// The per-channel (RGB) mean subtracted from detector inputs scaled to `0.0..=1.0`.
+    pub detector_mean: [f32; 3],
+    /// The per-channel (RGB) standard deviation detector inputs are divided by.
+    pub detector_std: [f32; 3],
+    /// The probability above which a pixel of the detector output is considered text.
+    pub text_threshold: f32,
+    /// The minimum mean probability of a text region.
+    pub box_threshold: f32,
+    /// The minimum width and height of a text region, in pixels of the detector output.
+    pub min_box_size: f32,
+    /// How far text regions are grown, relative to their area divided by their perimeter.
+    pub unclip_ratio: f32,
+    /// The maximum horizontal gap between two regions of the same line, relative to the line height.
+    pub max_word_gap: f32,
+    /// The height line crops are resized to, unless the recognizer declares it.
+    pub recognizer_height: u32,
+    /// The mean subtracted from recognizer inputs scaled to `0.0..=1.0`.
+    pub recognizer_mean: f32,
+    /// The standard deviation recognizer inputs are divided by.
+    pub recognizer_std: f32,
+    /// Which recognizer graph of the model to use, counting from 0.
+    pub recognizer_index: usize,
+    /// Which character map of the model to use, counting from 0.
+    pub character_map_index: usize,
+}
+
+impl Default for CpuOptions {
+    fn default() -> Self {
+        Self {
+            detector_mean: [0.485, 0.456, 0.406],
+            detector_std: [0.229, 0.224, 0.225],
+            text_threshold: 0.3,
+            box_threshold: 0.5,
+            min_box_size: 3.0,
+            unclip_ratio: 1.5,
+            max_word_gap: 1.5,
+            recognizer_height: 48,
+            recognizer_mean: 0.5,
+            recognizer_std: 0.5,
+            recognizer_index: 0,
+            character_map_index: 0,
+        }
+    }
+}
+
+/// The `CpuBackend` struct is an [`OcrBackend`] running the ONNX graphs of a `.onemodel` on the CPU.
+///
+/// It needs neither `oneocr.dll` nor Windows: the model container is decrypted with
+/// [`OneModel`] and its graphs are run with [tract](https://github.com/sonos/tract).
+/// Each image goes through four stages:
+///  1. Text detection: the detector graph produces a text probability map, which is thresholded
+///     into connected regions.
+///  2. Line grouping: regions overlapping vertically and separated by small gaps form a line.
+///  3. Recognition: each line is cropped, resized to the recognizer height and run through the
+///     recognizer graph.
+///  4. Decoding: the recognizer outputs are greedily CTC-decoded with the character map. Words
+///     are split on spaces and placed along the line from the time steps of their characters.
+///
+/// The pipeline has no style classifier and no angle estimation: every line is reported as
+/// printed with a style confidence of `0.0`, meaning the style is unknown, so filters such as
+/// [`OcrResult::lines_with_style_confidence`](crate::OcrResult::lines_with_style_confidence)
+/// never count CPU lines as confidently printed. The image angle is always `0.0`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::{CpuBackend, OcrEngine, OcrOptions};
+/// use std::path::Path;
+///
+/// let engine = OcrEngine::new_with_backend(CpuBackend::new(), OcrOptions::default()).unwrap();
+/// let result = engine.run(Path::new("image.jpg").into()).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct CpuBackend {
+    options: CpuOptions,
+    store: Mutex<ResultStore>,
+    pipelines: Mutex<HashMap<Handle, Arc<CpuPipeline>>>,
+}
+
+impl CpuBackend {
+    /// Creates a backend with the default [`CpuOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a backend with custom [`CpuOptions`].
+    pub fn with_options(options: CpuOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the options of this backend.
+    pub fn options(&self) -> &CpuOptions {
+        &self.options
+    }
+
+    fn store(&self) -> MutexGuard<'_, ResultStore> {
+        // The table stays consistent even if another thread panicked while holding the lock.
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn pipelines(&self) -> MutexGuard<'_, HashMap<Handle, Arc<CpuPipeline>>> {
+        self.pipelines.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn pipeline(&self, pipeline: Handle) -> Result<Arc<CpuPipeline>, OneOcrError> {
+        self.pipelines()
+            .get(&pipeline)
+            .cloned()
+            .ok_or_else(|| OneOcrError::OcrApiError {
+                result: -1,
+                message: format!("Invalid handle: {:#x}", pipeline.into_raw()),
+            })
+    }
+}
+
+/// The graphs and character map of a loaded model.
+struct CpuPipeline {
+    detector: Graph,
+    recognizer: Graph,
+    character_map: CharacterMap,
+}
+
+impl std::fmt::Debug for CpuPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CpuPipeline")
+            .field("classes", &self.character_map.classes.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CpuPipeline {
+    fn load(model: &OneModel, options: &CpuOptions) -> Result<Self, OneOcrError> {
+        let entry = |kind: ModelEntryKind, index: usize| {
+            model.entries_of_kind(kind).nth(index).ok_or_else(|| {
+                OneOcrError::InvalidModelFormat(format!("The model has no {kind} #{index}"))
+            })
+        };
+        let character_map = entry(ModelEntryKind::CharacterMap, options.character_map_index)?;
+        Ok(Self {
+            detector: load_graph(&entry(ModelEntryKind::Detector, 0)?.data, "detector")?,
+            recognizer: load_graph(
+                &entry(ModelEntryKind::Recognizer, options.recognizer_index)?.data,
+                "recognizer",
+            )?,
+            character_map: CharacterMap::parse(&String::from_utf8_lossy(&character_map.data)),
+        })
+    }
+
+    fn run(
+        &self,
+        image: &RgbaImage,
+        options: &CpuOptions,
+        max_line_count: usize,
+        max_size: (i32, i32),
+    ) -> Result<StoredResult, OneOcrError> {
+        let regions = self.detect(image, options, max_size)?;
+        let mut lines = Vec::new();
+        for region in group_lines(regions, options.max_word_gap) {
+            if lines.len() >= max_line_count {
+                break;
+            }
+            if let Some(line) = self.recognize(image, &region, options)? {
+                lines.push(line);
+            }
+        }
+        Ok(StoredResult {
+            image_angle: 0.0,
+            lines,
+        })
+    }
+
+    /// Finds the text regions of an image, in image coordinates.
+    fn detect(
+        &self,
+        image: &RgbaImage,
+        options: &CpuOptions,
+        max_size: (i32, i32),
+    ) -> Result<Vec<Rect>, OneOcrError> {
+        let (width, height) = image.dimensions();
+        let (declared_height, declared_width) = declared_input_size(&self.detector);
+        let scale = (max_size.0 as f32 / width as f32)
+            .min(max_size.1 as f32 / height as f32)
+            .min(1.0);
+        let round_to_32 = |size: u32| (((size as f32 * scale) / 32.0).round() as u32).max(1) * 32;
+        let input_width = declared_width.unwrap_or_else(|| round_to_32(width));
+        let input_height = declared_height.unwrap_or_else(|| round_to_32(height));
+
+        let resized = imageops::resize(image, input_width, input_height, FilterType::Triangle);
+        let input = image_tensor(&resized, options.detector_mean, options.detector_std)?;
+        let outputs = self
+            .detector
+            .run(tvec!(input.into()))
+            .map_err(|e| inference_error("detector", e))?;
+        let map = outputs[0]
+            .to_array_view::<f32>()
+            .map_err(|e| inference_error("detector", e))?;
+        let shape = map.shape();
+        let (map_height, map_width) = match shape {
+            [.., h, w] if h * w == map.len() => (*h, *w),
+            _ => {
+                return Err(OneOcrError::InferenceError(format!(
+                    "Unexpected detector output shape {shape:?}"
+                )));
+            }
+        };
+        let probabilities: Vec<f32> = map.iter().copied().collect();
+
+        let scale_x = width as f32 / map_width as f32;
+        let scale_y = height as f32 / map_height as f32;
+        Ok(find_regions(&probabilities, map_width, map_height, options)
+            .into_iter()
+            .map(|region| Rect {
+                left: (region.left * scale_x).max(0.0),
+                top: (region.top * scale_y).max(0.0),
+                right: (region.right * scale_x).min(width as f32),
+                bottom: (region.bottom * scale_y).min(height as f32),
+            })
+            .collect())
+    }
+
+    /// Recognizes the text of a line region. Returns `None` if nothing was decoded.
+    fn recognize(
+        &self,
+        image: &RgbaImage,
+        line: &Rect,
+        options: &CpuOptions,
+    ) -> Result<Option<StoredLine>, OneOcrError> {
+        let x = line.left.floor() as u32;
+        let y = line.top.floor() as u32;
+        let crop_width = (line.right.ceil() as u32).saturating_sub(x).max(1);
+        let crop_height = (line.bottom.ceil() as u32).saturating_sub(y).max(1);
+        let crop = imageops::crop_imm(image, x, y, crop_width, crop_height).to_image();
+
+        let (declared_height, declared_width) = declared_input_size(&self.recognizer);
+        let input_height = declared_height.unwrap_or(options.recognizer_height);
+        let scale = input_height as f32 / crop_height as f32;
+        let scaled_width = ((crop_width as f32 * scale).round() as u32).max(1);
+        let input_width = declared_width.unwrap_or(scaled_width);
+        // Lines are stretched to the recognizer height and padded with white on the right, or
+        // squeezed into a fixed input width.
+        let resized_width = scaled_width.min(input_width);
+        let scale_x = resized_width as f32 / crop_width as f32;
+
+        let resized = imageops::resize(&crop, resized_width, input_height, FilterType::Triangle);
+        let mut canvas = RgbaImage::from_pixel(input_width, input_height, Rgba([255; 4]));
+        imageops::overlay(&mut canvas, &resized, 0, 0);
+
+        let mean = [options.recognizer_mean; 3];
+        let std = [options.recognizer_std; 3];
+        let outputs = self
+            .recognizer
+            .run(tvec!(image_tensor(&canvas, mean, std)?.into()))
+            .map_err(|e| inference_error("recognizer", e))?;
+        let logits = outputs[0]
+            .to_array_view::<f32>()
+            .map_err(|e| inference_error("recognizer", e))?;
+        // Batch and channel axes of size 1 are dropped, leaving (time steps, classes).
+        let dims: Vec<usize> = logits.shape().iter().copied().filter(|d| *d != 1).collect();
+        let (steps, classes) = match dims[..] {
+            [steps, classes] => (steps, classes),
+            [classes] => (1, classes),
+            _ => {
+                return Err(OneOcrError::InferenceError(format!(
+                    "Unexpected recognizer output shape {:?}",
+                    logits.shape()
+                )));
+            }
+        };
+        let logits: Vec<f32> = logits.iter().copied().collect();
+        let decoded = self
+            .character_map
+            .decode(&probabilities(logits, classes), classes);
+
+        // Maps a time step boundary to an x coordinate in the image.
+        let step_x = |step: usize| {
+            let input_x = step as f32 * input_width as f32 / steps as f32;
+            (x as f32 + input_x / scale_x).clamp(line.left, line.right)
+        };
+        let words: Vec<StoredWord> = split_words(&decoded)
+            .into_iter()
+            .map(|chars| StoredWord {
+                text: chars.iter().map(|c| c.text.as_str()).collect(),
+                confidence: chars.iter().map(|c| c.confidence).sum::<f32>() / chars.len() as f32,
+                bounding_box: Rect {
+                    left: step_x(chars[0].step),
+                    top: line.top,
+                    right: step_x(chars[chars.len() - 1].step + 1),
+                    bottom: line.bottom,
+                }
+                .bounding_box(),
+            })
+            .collect();
+        if words.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(StoredLine {
+            text: words
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            bounding_box: line.bounding_box(),
+            // Printed, with no confidence: the model's style classifier isn't run.
+            style: 1,
+            style_confidence: 0.0,
+            words,
+        }))
+    }
+}
+
+fn load_graph(data: &[u8], name: &str) -> Result<Graph, OneOcrError> {
+    tract_onnx::onnx()
+        .model_for_read(&mut &data[..])
+        .and_then(|model| model.into_optimized())
+        .and_then(|model| model.into_runnable())
+        .map_err(|e| OneOcrError::InvalidModelFormat(format!("Failed to load the {name}: {e}")))
+}
+
+fn inference_error(name: &str, e: impl std::fmt::Display) -> OneOcrError {
+    OneOcrError::InferenceError(format!("Failed to run the {name}: {e}"))
+}
+
+/// Returns the `(height, width)` of the first input of an NCHW graph, where they are fixed.
+fn declared_input_size(graph: &Graph) -> (Option<u32>, Option<u32>) {
+    let Ok(fact) = graph.model().input_fact(0) else {
+        return (None, None);
+    };
+    let dim = |axis: usize| {
+        (fact.shape.rank() == 4)
+            .then(|| fact.shape[axis].to_i64().ok())
+            .flatten()
+            .and_then(|size| u32::try_from(size).ok())
+            .filter(|size| *size > 0)
+    };
+    (dim(2), dim(3))
+}
+
+/// Converts an image to a normalized `1x3xHxW` tensor.
+fn image_tensor(image: &RgbaImage, mean: [f32; 3], std: [f32; 3]) -> Result<Tensor, OneOcrError> {
+    let (width, height) = image.dimensions();
+    let plane = (width * height) as usize;
+    let mut data = vec![0.0f32; 3 * plane];
+    for (i, pixel) in image.pixels().enumerate() {
+        for channel in 0..3 {
+            data[channel * plane + i] =
+                (pixel[channel] as f32 / 255.0 - mean[channel]) / std[channel];
+        }
+    }
+    Tensor::from_shape(&[1, 3, height as usize, width as usize], &data)
+        .map_err(|e| OneOcrError::InferenceError(format!("Failed to build input tensor: {e}")))
+}
+
+/// An axis-aligned rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl Rect {
+    fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+
+    /// Whether `other` continues this line: it overlaps vertically by at least half of the
+    /// smaller height and starts at most `max_gap` line heights after this one ends.
+    fn continues_with(&self, other: &Rect, max_gap: f32) -> bool {
+        let overlap = self.bottom.min(other.bottom) - self.top.max(other.top);
+        let gap = other.left - self.right;
+        overlap >= 0.5 * self.height().min(other.height())
+            && gap <= max_gap * self.height().max(other.height())
+    }
+
+    fn union(&mut self, other: &Rect) {
+        self.left = self.left.min(other.left);
+        self.top = self.top.min(other.top);
+        self.right = self.right.max(other.right);
+        self.bottom = self.bottom.max(other.bottom);
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            top_left: Point {
+                x: self.left,
+                y: self.top,
+            },
+            top_right: Point {
+                x: self.right,
+                y: self.top,
+            },
+            bottom_right: Point {
+                x: self.right,
+                y: self.bottom,
+            },
+            bottom_left: Point {
+                x: self.left,
+                y: self.bottom,
+            },
+        }
+    }
+}
+
+/// Thresholds a probability map and returns its 4-connected text regions, grown by the unclip
+/// ratio, in map coordinates.
+fn find_regions(
+    probabilities: &[f32],
+    width: usize,
+    height: usize,
+    options: &CpuOptions,
+) -> Vec<Rect> {
+    let mut visited = vec![false; probabilities.len()];
+    let mut regions = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0..probabilities.len() {
+        if visited[start] || probabilities[start] <= options.text_threshold {
+            continue;
+        }
+        visited[start] = true;
+        stack.push(start);
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0, 0);
+        let (mut score, mut count) = (0.0f32, 0usize);
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % width, index / width);
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+            score += probabilities[index];
+            count += 1;
+
+            let neighbours = [
+                (x > 0).then(|| index - 1),
+                (x + 1 < width).then(|| index + 1),
+                (y > 0).then(|| index - width),
+                (y + 1 < height).then(|| index + width),
+            ];
+            for neighbour in neighbours.into_iter().flatten() {
+                if !visited[neighbour] && probabilities[neighbour] > options.text_threshold {
+                    visited[neighbour] = true;
+                    stack.push(neighbour);
+                }
+            }
+        }
+
+        let region_width = (max_x - min_x + 1) as f32;
+        let region_height = (max_y - min_y + 1) as f32;
+        if score / (count as f32) < options.box_threshold
+            || region_width < options.min_box_size
+            || region_height < options.min_box_size
+        {
+            continue;
+        }
+        let offset = region_width * region_height * options.unclip_ratio
+            / (2.0 * (region_width + region_height));
+        regions.push(Rect {
+            left: (min_x as f32 - offset).max(0.0),
+            top: (min_y as f32 - offset).max(0.0),
+            right: ((max_x + 1) as f32 + offset).min(width as f32),
+            bottom: ((max_y + 1) as f32 + offset).min(height as f32),
+        });
+    }
+    regions
+}
+
+/// Merges text regions into lines, sorted top to bottom.
+fn group_lines(mut regions: Vec<Rect>, max_gap: f32) -> Vec<Rect> {
+    regions.sort_by(|a, b| a.left.total_cmp(&b.left));
+    let mut lines: Vec<Rect> = Vec::new();
+    for region in regions {
+        match lines
+            .iter_mut()
+            .find(|line| line.continues_with(&region, max_gap))
+        {
+            Some(line) => line.union(&region),
+            None => lines.push(region),
+        }
+    }
+    lines.sort_by(|a, b| a.top.total_cmp(&b.top).then(a.left.total_cmp(&b.left)));
+    lines
+}
+
+/// Turns recognizer outputs into per-class probabilities, applying a softmax to each time step
+/// unless the outputs already are probabilities.
+fn probabilities(mut logits: Vec<f32>, classes: usize) -> Vec<f32> {
+    let is_distribution = |row: &[f32]| {
+        row.iter().all(|p| (0.0..=1.0).contains(p)) && (row.iter().sum::<f32>() - 1.0).abs() < 1e-3
+    };
+    if logits.chunks(classes).all(is_distribution) {
+        return logits;
+    }
+    for row in logits.chunks_mut(classes) {
+        let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let mut sum = 0.0;
+        for value in row.iter_mut() {
+            *value = (*value - max).exp();
+            sum += *value;
+        }
+        row.iter_mut().for_each(|value| *value /= sum);
+    }
+    logits
+}
+
+/// A decoded character and the time step it was emitted at.
+#[derive(Debug, Clone)]
+struct DecodedChar {
+    text: String,
+    confidence: f32,
+    step: usize,
+}
+
+/// Splits decoded characters into words on whitespace.
+fn split_words(chars: &[DecodedChar]) -> Vec<&[DecodedChar]> {
+    chars
+        .split(|c| c.text.trim().is_empty())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Maps recognizer classes to text.
+///
+/// Two layouts are understood:
+///  - `<text> <class>` on every line, e.g. `a 12`. Classes that are not listed (usually 0 or
+///    the last class) are the CTC blank.
+///  - One entry per line, for classes 1, 2, ... in order. Class 0 is the CTC blank.
+#[derive(Debug, Clone)]
+struct CharacterMap {
+    classes: Vec<Option<String>>,
+}
+
+impl CharacterMap {
+    fn parse(text: &str) -> Self {
+        let lines: Vec<&str> = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .collect();
+        let indexed: Option<Vec<(&str, usize)>> = lines
+            .iter()
+            .map(|line| {
+                let (text, class) = line.rsplit_once(' ')?;
+                let text = if text.is_empty() { " " } else { text };
+                Some((text, class.parse().ok()?))
+            })
+            .collect();
+
+        let mut classes = Vec::new();
+        match indexed {
+            Some(indexed) => {
+                for (text, class) in indexed {
+                    if classes.len() <= class {
+                        classes.resize(class + 1, None);
+                    }
+                    classes[class] = Some(text.to_string());
+                }
+            }
+            None => {
+                classes.push(None);
+                classes.extend(lines.iter().map(|line| Some(line.to_string())));
+            }
+        }
+        Self { classes }
+    }
+
+    /// Greedy CTC decoding: takes the most likely class of each time step, collapses repeats
+    /// and drops blanks.
+    fn decode(&self, probabilities: &[f32], classes: usize) -> Vec<DecodedChar> {
+        let mut decoded = Vec::new();
+        let mut previous = None;
+        for (step, row) in probabilities.chunks(classes).enumerate() {
+            let (class, confidence) =
+                row.iter()
+                    .copied()
+                    .enumerate()
+                    .fold((0, f32::NEG_INFINITY), |best, (class, p)| {
+                        if p > best.1 { (class, p) } else { best }
+                    });
+            if previous != Some(class)
+                && let Some(Some(text)) = self.classes.get(class)
+            {
+                decoded.push(DecodedChar {
+                    text: text.clone(),
+                    confidence,
+                    step,
+                });
+            }
+            previous = Some(class);
+        }
+        decoded
+    }
+}
+
+impl OcrBackend for CpuBackend {
+    fn supports_concurrent_runs(&self) -> bool {
+        // Each run gets its own tract state, and the handle table is only locked briefly.
+        true
+    }
+
+    fn create_init_options(&self) -> Result<Handle, OneOcrError> {
+        Ok(self.store().create_init_options())
+    }
+
+    fn set_use_model_delay_load(
+        &self,
+        init_options: Handle,
+        _delay_load: bool,
+    ) -> Result<(), OneOcrError> {
+        self.store().check_init_options(init_options)
+    }
+
+    fn create_pipeline(
+        &self,
+        model_path: &CStr,
+        key: &CStr,
+        init_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        self.store().check_init_options(init_options)?;
+        let model_path = model_path.to_string_lossy();
+        let model = OneModel::open_with_key(Path::new(model_path.as_ref()), key.to_bytes())?;
+        let pipeline = Arc::new(CpuPipeline::load(&model, &self.options)?);
+        let handle = self.store().create_pipeline();
+        self.pipelines().insert(handle, pipeline);
+        Ok(handle)
+    }
+
+    fn create_process_options(&self) -> Result<Handle, OneOcrError> {
+        Ok(self.store().create_process_options())
+    }
+
+    fn get_max_recognition_line_count(&self, process_options: Handle) -> Result<i32, OneOcrError> {
+        let mut store = self.store();
+        let (count, _) = store.process_options(process_options)?;
+        Ok(*count)
+    }
+
+    fn set_max_recognition_line_count(
+        &self,
+        process_options: Handle,
+        count: i32,
+    ) -> Result<(), OneOcrError> {
+        *self.store().process_options(process_options)?.0 = count;
+        Ok(())
+    }
+
+    fn get_resize_resolution(&self, process_options: Handle) -> Result<(i64, i64), OneOcrError> {
+        let mut store = self.store();
+        let (_, (width, height)) = store.process_options(process_options)?;
+        Ok((*width as i64, *height as i64))
+    }
+
+    fn set_resize_resolution(
+        &self,
+        process_options: Handle,
+        width: i32,
+        height: i32,
+    ) -> Result<(), OneOcrError> {
+        *self.store().process_options(process_options)?.1 = (width, height);
+        Ok(())
+    }
+
+    fn run_pipeline(
+        &self,
+        pipeline: Handle,
+        image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        process_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        let pipeline = self.pipeline(pipeline)?;
+        let (max_line_count, max_size) = {
+            let mut store = self.store();
+            let (count, resolution) = store.process_options(process_options)?;
+            (usize::try_from(*count).unwrap_or(0), *resolution)
+        };
+        // The store is not locked while the graphs run, so other threads can read results.
+        let result = pipeline.run(image, &self.options, max_line_count, max_size)?;
+        Ok(self.store().insert_result(result))
+    }
+
+    fn get_image_angle(&self, result: Handle) -> Result<f32, OneOcrError> {
+        Ok(self.store().result(result)?.image_angle)
+    }
+
+    fn get_line_count(&self, result: Handle) -> Result<i64, OneOcrError> {
+        Ok(self.store().result(result)?.lines.len() as i64)
+    }
+
+    fn get_line(&self, result: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        self.store().get_line(result, index)
+    }
+
+    fn get_line_content(&self, line: Handle) -> Result<String, OneOcrError> {
+        Ok(self.store().line(line)?.text.clone())
+    }
+
+    fn get_line_bounding_box(&self, line: Handle) -> Result<BoundingBox, OneOcrError> {
+        Ok(self.store().line(line)?.bounding_box)
+    }
+
+    fn get_line_style(&self, line: Handle) -> Result<(i32, f32), OneOcrError> {
+        let store = self.store();
+        let line = store.line(line)?;
+        Ok((line.style, line.style_confidence))
+    }
+
+    fn get_line_word_count(&self, line: Handle) -> Result<i64, OneOcrError> {
+        Ok(self.store().line(line)?.words.len() as i64)
+    }
+
+    fn get_word(&self, line: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        self.store().get_word(line, index)
+    }
+
+    fn get_word_content(&self, word: Handle) -> Result<String, OneOcrError> {
+        Ok(self.store().word(word)?.text.clone())
+    }
+
+    fn get_word_bounding_box(&self, word: Handle) -> Result<BoundingBox, OneOcrError> {
+        Ok(self.store().word(word)?.bounding_box)
+    }
+
+    fn get_word_confidence(&self, word: Handle) -> Result<f32, OneOcrError> {
+        Ok(self.store().word(word)?.confidence)
+    }
+
+    fn release_result(&self, result: Handle) {
+        self.store().release_result(result);
+    }
+
+    fn release_init_options(&self, init_options: Handle) {
+        self.store().release(init_options);
+    }
+
+    fn release_pipeline(&self, pipeline: Handle) {
+        self.pipelines().remove(&pipeline);
+        self.store().release(pipeline);
+    }
+
+    fn release_process_options(&self, process_options: Handle) {
+        self.store().release(process_options);
+    }
+}
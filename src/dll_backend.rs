@@ -0,0 +1,360 @@
+use crate::ONE_OCR_LIBRARY_NAME;
+use crate::bounding_box::BoundingBox;
+use crate::errors::OneOcrError;
+use crate::ocr_backend::{Handle, OcrBackend};
+use image::{ImageBuffer, Rgba};
+use std::ffi::{CStr, c_char, c_void};
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+
+// FFI types
+use crate::ffi::{OneOcrApi, RawBBox, RawImage};
+// Macros
+use crate::check_ocr_call;
+
+/// The `DllBackend` struct is the [`OcrBackend`] backed by `oneocr.dll`.
+///
+/// The library is opened at runtime and every function is resolved up front, so a missing
+/// library or symbol is reported when the backend is created rather than on first use.
+/// Any shared library exporting the same C functions can be loaded, e.g. a `.so` stub on Linux.
+///
+/// `oneocr.dll` does not document its threading guarantees. Its options, results, lines and
+/// words are independent objects, but whether one pipeline may run several images at once is
+/// unknown, so this backend keeps the default of [`OcrBackend::supports_concurrent_runs`] and
+/// `OcrEngine` serializes its pipeline runs.
+#[derive(Clone)]
+pub struct DllBackend {
+    api: Arc<OneOcrApi>,
+}
+
+impl DllBackend {
+    /// Loads the OneOCR library from the default location.
+    ///
+    /// This is `oneocr.dll` on Windows (searched next to the executable first) and
+    /// `liboneocr.so` on Linux (searched in the dynamic linker's search path).
+    pub fn new() -> Result<Self, OneOcrError> {
+        Self::load(libloading::library_filename(ONE_OCR_LIBRARY_NAME))
+    }
+
+    /// Loads the OneOCR library from `path`, e.g. `oneocr.dll` in the Snipping Tool install folder.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OneOcrError> {
+        let api = OneOcrApi::load(path.as_ref())?;
+        Ok(Self { api: Arc::new(api) })
+    }
+
+    /// Returns the path the library was loaded from.
+    pub fn path(&self) -> &Path {
+        self.api.path()
+    }
+}
+
+/// Returns an error if `function` succeeded but handed out a null pointer.
+fn check_not_null<T>(ptr: *const T, function: &str) -> Result<(), OneOcrError> {
+    if ptr.is_null() {
+        return Err(OneOcrError::OcrApiError {
+            result: -1,
+            message: format!("{function} returned a null pointer."),
+        });
+    }
+    Ok(())
+}
+
+impl std::fmt::Debug for DllBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DllBackend")
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+impl OcrBackend for DllBackend {
+    fn create_init_options(&self) -> Result<Handle, OneOcrError> {
+        let mut init_options: *mut c_void = ptr::null_mut();
+        check_ocr_call!(
+            unsafe { (self.api.CreateOcrInitOptions)(&mut init_options) },
+            "Failed to create init options"
+        );
+        check_not_null(init_options, "CreateOcrInitOptions")?;
+        Ok(Handle::from_ptr(init_options))
+    }
+
+    fn set_use_model_delay_load(
+        &self,
+        init_options: Handle,
+        delay_load: bool,
+    ) -> Result<(), OneOcrError> {
+        check_ocr_call!(
+            unsafe {
+                (self.api.OcrInitOptionsSetUseModelDelayLoad)(
+                    init_options.as_ptr(),
+                    delay_load as u8,
+                )
+            },
+            "Failed to set model delay load"
+        );
+        Ok(())
+    }
+
+    fn create_pipeline(
+        &self,
+        model_path: &CStr,
+        key: &CStr,
+        init_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        let mut pipeline: *mut c_void = ptr::null_mut();
+        check_ocr_call!(
+            unsafe {
+                (self.api.CreateOcrPipeline)(
+                    model_path.as_ptr(),
+                    key.as_ptr(),
+                    init_options.as_ptr(),
+                    &mut pipeline,
+                )
+            },
+            "Failed to create OCR pipeline"
+        );
+        check_not_null(pipeline, "CreateOcrPipeline")?;
+        Ok(Handle::from_ptr(pipeline))
+    }
+
+    fn create_process_options(&self) -> Result<Handle, OneOcrError> {
+        let mut process_options: *mut c_void = ptr::null_mut();
+        check_ocr_call!(
+            unsafe { (self.api.CreateOcrProcessOptions)(&mut process_options) },
+            "Failed to create OCR process options"
+        );
+        check_not_null(process_options, "CreateOcrProcessOptions")?;
+        Ok(Handle::from_ptr(process_options))
+    }
+
+    fn get_max_recognition_line_count(&self, process_options: Handle) -> Result<i32, OneOcrError> {
+        let mut count: i32 = 0;
+        check_ocr_call!(
+            unsafe {
+                (self.api.OcrProcessOptionsGetMaxRecognitionLineCount)(
+                    process_options.as_ptr(),
+                    &mut count,
+                )
+            },
+            "Failed to get max recognition line count"
+        );
+        Ok(count)
+    }
+
+    fn set_max_recognition_line_count(
+        &self,
+        process_options: Handle,
+        count: i32,
+    ) -> Result<(), OneOcrError> {
+        check_ocr_call!(
+            unsafe {
+                (self.api.OcrProcessOptionsSetMaxRecognitionLineCount)(
+                    process_options.as_ptr(),
+                    count,
+                )
+            },
+            "Failed to set max recognition line count"
+        );
+        Ok(())
+    }
+
+    fn get_resize_resolution(&self, process_options: Handle) -> Result<(i64, i64), OneOcrError> {
+        let mut width: i64 = 0;
+        let mut height: i64 = 0;
+        check_ocr_call!(
+            unsafe {
+                (self.api.OcrProcessOptionsGetResizeResolution)(
+                    process_options.as_ptr(),
+                    &mut width,
+                    &mut height,
+                )
+            },
+            "Failed to get resize resolution"
+        );
+        Ok((width, height))
+    }
+
+    fn set_resize_resolution(
+        &self,
+        process_options: Handle,
+        width: i32,
+        height: i32,
+    ) -> Result<(), OneOcrError> {
+        check_ocr_call!(
+            unsafe {
+                (self.api.OcrProcessOptionsSetResizeResolution)(
+                    process_options.as_ptr(),
+                    width,
+                    height,
+                )
+            },
+            "Failed to set resize resolution"
+        );
+        Ok(())
+    }
+
+    fn run_pipeline(
+        &self,
+        pipeline: Handle,
+        image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        process_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        let (rows, cols) = (image.height() as i32, image.width() as i32);
+        let step = (image.sample_layout().height_stride) as i64;
+        let data_ptr = image.as_ptr() as i64;
+        let raw_image = RawImage {
+            t: 3, // RGBA format identifier expected by the C API
+            col: cols,
+            row: rows,
+            _unk: 0,
+            step,
+            data_ptr,
+        };
+
+        let mut ocr_result: *mut c_void = ptr::null_mut();
+        check_ocr_call!(
+            unsafe {
+                (self.api.RunOcrPipeline)(
+                    pipeline.as_ptr(),
+                    &raw_image,
+                    process_options.as_ptr(),
+                    &mut ocr_result,
+                )
+            },
+            "Failed to run OCR pipeline"
+        );
+        check_not_null(ocr_result, "RunOcrPipeline")?;
+        Ok(Handle::from_ptr(ocr_result))
+    }
+
+    fn get_image_angle(&self, result: Handle) -> Result<f32, OneOcrError> {
+        let mut angle: f32 = 0.0;
+        check_ocr_call!(
+            unsafe { (self.api.GetImageAngle)(result.as_ptr(), &mut angle) },
+            "Failed to get image angle"
+        );
+        Ok(angle)
+    }
+
+    fn get_line_count(&self, result: Handle) -> Result<i64, OneOcrError> {
+        let mut line_count: i64 = 0;
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrLineCount)(result.as_ptr(), &mut line_count) },
+            "Failed to get line count"
+        );
+        Ok(line_count)
+    }
+
+    fn get_line(&self, result: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        let mut line: *mut c_void = ptr::null_mut();
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrLine)(result.as_ptr(), index, &mut line) },
+            "Failed to get line"
+        );
+        check_not_null(line, "GetOcrLine")?;
+        Ok(Handle::from_ptr(line))
+    }
+
+    fn get_line_content(&self, line: Handle) -> Result<String, OneOcrError> {
+        let mut line_content: *const c_char = ptr::null();
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrLineContent)(line.as_ptr(), &mut line_content) },
+            "Failed to get line content"
+        );
+        check_not_null(line_content, "GetOcrLineContent")?;
+        let line_content_cstr = unsafe { CStr::from_ptr(line_content) };
+        Ok(line_content_cstr.to_string_lossy().to_string())
+    }
+
+    fn get_line_bounding_box(&self, line: Handle) -> Result<BoundingBox, OneOcrError> {
+        let mut bounding_box_ptr: *const RawBBox = ptr::null();
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrLineBoundingBox)(line.as_ptr(), &mut bounding_box_ptr) },
+            "Failed to get line bounding box"
+        );
+
+        check_not_null(bounding_box_ptr, "GetOcrLineBoundingBox")?;
+
+        let raw_bbox = unsafe { ptr::read(bounding_box_ptr) };
+        Ok(BoundingBox::new(raw_bbox))
+    }
+
+    fn get_line_style(&self, line: Handle) -> Result<(i32, f32), OneOcrError> {
+        let mut style: i32 = 0;
+        let mut confidence: f32 = 0.0;
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrLineStyle)(line.as_ptr(), &mut style, &mut confidence) },
+            "Failed to get OCR line style"
+        );
+        Ok((style, confidence))
+    }
+
+    fn get_line_word_count(&self, line: Handle) -> Result<i64, OneOcrError> {
+        let mut word_count: i64 = 0;
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrLineWordCount)(line.as_ptr(), &mut word_count) },
+            "Failed to get word count"
+        );
+        Ok(word_count)
+    }
+
+    fn get_word(&self, line: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        let mut word: *mut c_void = ptr::null_mut();
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrWord)(line.as_ptr(), index, &mut word) },
+            "Failed to get word"
+        );
+        check_not_null(word, "GetOcrWord")?;
+        Ok(Handle::from_ptr(word))
+    }
+
+    fn get_word_content(&self, word: Handle) -> Result<String, OneOcrError> {
+        let mut word_content: *const c_char = ptr::null();
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrWordContent)(word.as_ptr(), &mut word_content) },
+            "Failed to get word content"
+        );
+        check_not_null(word_content, "GetOcrWordContent")?;
+        let word_content_cstr = unsafe { CStr::from_ptr(word_content) };
+        Ok(word_content_cstr.to_string_lossy().to_string())
+    }
+
+    fn get_word_bounding_box(&self, word: Handle) -> Result<BoundingBox, OneOcrError> {
+        let mut bounding_box_ptr: *const RawBBox = ptr::null();
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrWordBoundingBox)(word.as_ptr(), &mut bounding_box_ptr) },
+            "Failed to get word bounding box"
+        );
+
+        check_not_null(bounding_box_ptr, "GetOcrWordBoundingBox")?;
+
+        let raw_bbox = unsafe { ptr::read(bounding_box_ptr) };
+        Ok(BoundingBox::new(raw_bbox))
+    }
+
+    fn get_word_confidence(&self, word: Handle) -> Result<f32, OneOcrError> {
+        let mut confidence: f32 = 0.0;
+        check_ocr_call!(
+            unsafe { (self.api.GetOcrWordConfidence)(word.as_ptr(), &mut confidence) },
+            "Failed to get word confidence"
+        );
+        Ok(confidence)
+    }
+
+    fn release_result(&self, result: Handle) {
+        unsafe { (self.api.ReleaseOcrResult)(result.as_ptr()) };
+    }
+
+    fn release_init_options(&self, init_options: Handle) {
+        unsafe { (self.api.ReleaseOcrInitOptions)(init_options.as_ptr()) };
+    }
+
+    fn release_pipeline(&self, pipeline: Handle) {
+        unsafe { (self.api.ReleaseOcrPipeline)(pipeline.as_ptr()) };
+    }
+
+    fn release_process_options(&self, process_options: Handle) {
+        unsafe { (self.api.ReleaseOcrProcessOptions)(process_options.as_ptr()) };
+    }
+}
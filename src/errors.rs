@@ -7,15 +7,93 @@ pub enum OneOcrError {
     #[error("Image format not supported: {0}")]
     ImageFormatError(String),
 
+    #[error("Failed to load OneOCR library {}: {message}", path.display())]
+    LibraryLoadError {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("OneOCR library {} is missing symbols: {}", path.display(), symbols.join(", "))]
+    MissingSymbols {
+        path: std::path::PathBuf,
+        symbols: Vec<String>,
+    },
+
+    #[error("Max recognition line count must be between 0 and 1000, got {0}")]
+    InvalidMaxRecognitionLineCount(i32),
+
+    #[error("Resize resolution must be between 1x1 and 1152x768, got {width}x{height}")]
+    InvalidResizeResolution { width: i32, height: i32 },
+
+    #[error("Engine pool size must be at least 1, got {0}")]
+    InvalidPoolSize(usize),
+
+    #[error("Async engine queue capacity must be at least 1, got {0}")]
+    InvalidQueueCapacity(usize),
+
     #[error("Failed to load model file: {0}")]
     ModelFileLoadError(String),
 
+    #[error("Model file not found, searched: {}", searched.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "))]
+    ModelFileNotFound { searched: Vec<std::path::PathBuf> },
+
     #[error("Invalid model decryption key: {0}")]
     InvalidModelKey(String),
 
+    #[error("Invalid model file format: {0}")]
+    InvalidModelFormat(String),
+
+    #[error("Failed to extract model entries to {}: {source}", path.display())]
+    ModelExtractError {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to run model inference: {0}")]
+    InferenceError(String),
+
     #[error("Failed to run OCR API (code: {result}): {message}")]
     OcrApiError { result: i32, message: String },
 
+    #[error("OCR backend returned an invalid {what} count: {count}")]
+    InvalidCount { what: &'static str, count: i64 },
+
+    #[error("OCR request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("OCR request queue is full")]
+    QueueFull,
+
+    #[error("OCR worker stopped before returning a result")]
+    WorkerStopped,
+
+    #[error("Invalid OCR result JSON: {0}")]
+    InvalidResultJson(String),
+
+    #[error("OCR result schema version {found} is not supported, the latest is {supported}")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+
+    #[error("No recorded OCR result for image {hash}: {} is missing", path.display())]
+    FixtureNotFound {
+        hash: String,
+        path: std::path::PathBuf,
+    },
+
+    #[error("Invalid coordinate transform: {0}")]
+    InvalidTransform(String),
+
+    #[error("PDF resolution must be positive, got {0} DPI")]
+    InvalidDpi(f32),
+
+    #[error("Image must not be empty, got {width}x{height}")]
+    EmptyImage { width: u32, height: u32 },
+
+    #[error("Failed to write PDF: {0}")]
+    PdfWriteError(std::io::Error),
+
+    #[error("Failed to access OCR fixture: {0}")]
+    FixtureError(String),
+
     #[error("Other error: {0}")]
     Other(String),
 }
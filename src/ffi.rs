@@ -1,40 +1,116 @@
+use crate::errors::OneOcrError;
+use libloading::Library;
 use std::ffi::{c_char, c_void};
-use windows_link::link;
+use std::path::{Path, PathBuf};
 
-link!("oneocr.dll"  "system"  fn CreateOcrInitOptions(init_option: *mut *mut c_void) -> i32);
-link!("oneocr.dll"  "system"  fn OcrInitOptionsSetUseModelDelayLoad(init_option: *mut c_void, delay_load: u8) -> i32);
-link!("oneocr.dll"  "system"  fn CreateOcrPipeline(
-    model_path: *const c_char,
-    key: *const c_char,
-    ctx: *mut c_void,
-    pipeline: *mut *mut c_void
-) -> i32);
-link!("oneocr.dll"  "system"  fn CreateOcrProcessOptions(option: *mut *mut c_void) -> i32);
-link!("oneocr.dll"  "system"  fn OcrProcessOptionsGetMaxRecognitionLineCount(option: *mut c_void, count: *mut i32) -> i32);
-link!("oneocr.dll"  "system"  fn OcrProcessOptionsSetMaxRecognitionLineCount(option: *mut c_void, count: i32) -> i32);
-link!("oneocr.dll"  "system"  fn OcrProcessOptionsGetResizeResolution(option: *mut c_void, width: *mut i64, height: *mut i64) -> i32);
-link!("oneocr.dll"  "system"  fn OcrProcessOptionsSetResizeResolution   (option: *mut c_void, width: i32, height: i32) -> i32);
-link!("oneocr.dll"  "system"  fn RunOcrPipeline(
-    pipeline: *mut c_void,
-    image: *const RawImage,
-    process_options: *mut c_void,
-    result: *mut *mut c_void
-) -> i32);
-link!("oneocr.dll"  "system"  fn GetImageAngle(pipeline: *mut c_void, angle: *mut f32) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrLineCount(result: *mut c_void, count: *mut i64) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrLine(result: *mut c_void, index: i64, line: *mut *mut c_void) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrLineContent(line: *mut c_void, content: *mut *const c_char) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrLineBoundingBox(line: *mut c_void, bbox: *mut *const RawBBox) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrLineStyle(line: *mut c_void, style: *mut i32, confidence: *mut f32) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrLineWordCount(line: *mut c_void, count: *mut i64) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrWord(line: *mut c_void, index: i64, word: *mut *mut c_void) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrWordContent(word: *mut c_void, content: *mut *const c_char) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrWordBoundingBox(word: *mut c_void, bbox: *mut *const RawBBox) -> i32);
-link!("oneocr.dll"  "system"  fn GetOcrWordConfidence(word: *mut c_void, confidence: *mut f32) -> i32);
-link!("oneocr.dll"  "system"  fn ReleaseOcrResult(result: *mut c_void));
-link!("oneocr.dll"  "system"  fn ReleaseOcrInitOptions(init_options: *mut c_void));
-link!("oneocr.dll"  "system"  fn ReleaseOcrPipeline(pipeline: *mut c_void));
-link!("oneocr.dll"  "system"  fn ReleaseOcrProcessOptions(process_options: *mut c_void));
+/// Declares the functions exported by `oneocr.dll` and generates `OneOcrApi`, a table of
+/// function pointers resolved from a library loaded at runtime.
+macro_rules! oneocr_api {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        /// The functions of a loaded OneOCR library.
+        /// The function pointers are only valid as long as `_library` is alive.
+        #[allow(non_snake_case)]
+        pub(crate) struct OneOcrApi {
+            path: PathBuf,
+            $(pub $name: unsafe extern "system" fn($($arg: $ty),*) $(-> $ret)?,)*
+            _library: Library,
+        }
+
+        impl OneOcrApi {
+            /// Loads the library at `path` and resolves every function up front.
+            #[allow(non_snake_case)]
+            pub(crate) fn load(path: &Path) -> Result<Self, OneOcrError> {
+                let library = unsafe { open_library(path) }.map_err(|e| {
+                    OneOcrError::LibraryLoadError {
+                        path: path.to_path_buf(),
+                        message: e.to_string(),
+                    }
+                })?;
+
+                let mut missing_symbols = Vec::new();
+                $(
+                    let $name = unsafe {
+                        library.get::<unsafe extern "system" fn($($ty),*) $(-> $ret)?>(
+                            concat!(stringify!($name), "\0").as_bytes(),
+                        )
+                    }
+                    .map(|symbol| *symbol)
+                    .map_err(|_| missing_symbols.push(stringify!($name).to_string()))
+                    .ok();
+                )*
+
+                if !missing_symbols.is_empty() {
+                    return Err(OneOcrError::MissingSymbols {
+                        path: path.to_path_buf(),
+                        symbols: missing_symbols,
+                    });
+                }
+
+                Ok(Self {
+                    path: path.to_path_buf(),
+                    $($name: $name.expect("symbol was resolved"),)*
+                    _library: library,
+                })
+            }
+
+            /// Returns the path the library was loaded from.
+            pub(crate) fn path(&self) -> &Path {
+                &self.path
+            }
+        }
+    };
+}
+
+oneocr_api! {
+    fn CreateOcrInitOptions(init_option: *mut *mut c_void) -> i32;
+    fn OcrInitOptionsSetUseModelDelayLoad(init_option: *mut c_void, delay_load: u8) -> i32;
+    fn CreateOcrPipeline(
+        model_path: *const c_char,
+        key: *const c_char,
+        ctx: *mut c_void,
+        pipeline: *mut *mut c_void,
+    ) -> i32;
+    fn CreateOcrProcessOptions(option: *mut *mut c_void) -> i32;
+    fn OcrProcessOptionsGetMaxRecognitionLineCount(option: *mut c_void, count: *mut i32) -> i32;
+    fn OcrProcessOptionsSetMaxRecognitionLineCount(option: *mut c_void, count: i32) -> i32;
+    fn OcrProcessOptionsGetResizeResolution(option: *mut c_void, width: *mut i64, height: *mut i64) -> i32;
+    fn OcrProcessOptionsSetResizeResolution(option: *mut c_void, width: i32, height: i32) -> i32;
+    fn RunOcrPipeline(
+        pipeline: *mut c_void,
+        image: *const RawImage,
+        process_options: *mut c_void,
+        result: *mut *mut c_void,
+    ) -> i32;
+    fn GetImageAngle(pipeline: *mut c_void, angle: *mut f32) -> i32;
+    fn GetOcrLineCount(result: *mut c_void, count: *mut i64) -> i32;
+    fn GetOcrLine(result: *mut c_void, index: i64, line: *mut *mut c_void) -> i32;
+    fn GetOcrLineContent(line: *mut c_void, content: *mut *const c_char) -> i32;
+    fn GetOcrLineBoundingBox(line: *mut c_void, bbox: *mut *const RawBBox) -> i32;
+    fn GetOcrLineStyle(line: *mut c_void, style: *mut i32, confidence: *mut f32) -> i32;
+    fn GetOcrLineWordCount(line: *mut c_void, count: *mut i64) -> i32;
+    fn GetOcrWord(line: *mut c_void, index: i64, word: *mut *mut c_void) -> i32;
+    fn GetOcrWordContent(word: *mut c_void, content: *mut *const c_char) -> i32;
+    fn GetOcrWordBoundingBox(word: *mut c_void, bbox: *mut *const RawBBox) -> i32;
+    fn GetOcrWordConfidence(word: *mut c_void, confidence: *mut f32) -> i32;
+    fn ReleaseOcrResult(result: *mut c_void);
+    fn ReleaseOcrInitOptions(init_options: *mut c_void);
+    fn ReleaseOcrPipeline(pipeline: *mut c_void);
+    fn ReleaseOcrProcessOptions(process_options: *mut c_void);
+}
+
+/// Opens a shared library.
+/// On Windows, the dependencies of a library given by an absolute path (e.g. `onnxruntime.dll`)
+/// are searched in the library's own folder first.
+unsafe fn open_library(path: &Path) -> Result<Library, libloading::Error> {
+    #[cfg(windows)]
+    if path.is_absolute() {
+        use libloading::os::windows::{LOAD_WITH_ALTERED_SEARCH_PATH, Library as WinLibrary};
+        return unsafe { WinLibrary::load_with_flags(path, LOAD_WITH_ALTERED_SEARCH_PATH) }
+            .map(Library::from);
+    }
+
+    unsafe { Library::new(path) }
+}
 
 /// This `RawImage` struct represents an image in a format suitable for OCR processing. Used for FFI.
 ///  - t: Type of the image (e.g., RGB, RGBA).
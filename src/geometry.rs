@@ -0,0 +1,463 @@
+use crate::bounding_box::{BoundingBox, Point};
+use serde::{Deserialize, Serialize};
+
+/// An axis-aligned rectangle in `(x, y, width, height)` form, with `(x, y)` its top left corner.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// Creates a rectangle from its top left corner and size.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the x coordinate of the right edge.
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    /// Returns the y coordinate of the bottom edge.
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// Returns the smallest rectangle enclosing this rectangle and `other`.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let (left, top) = (self.x.min(other.x), self.y.min(other.y));
+        let (right, bottom) = (
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        );
+        Rect::new(left, top, right - left, bottom - top)
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Rect {
+    fn from((x, y, width, height): (f32, f32, f32, f32)) -> Self {
+        Rect::new(x, y, width, height)
+    }
+}
+
+impl From<Rect> for (f32, f32, f32, f32) {
+    fn from(rect: Rect) -> Self {
+        (rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+impl From<Rect> for BoundingBox {
+    fn from(rect: Rect) -> Self {
+        BoundingBox::from_corners([
+            Point::new(rect.x, rect.y),
+            Point::new(rect.right(), rect.y),
+            Point::new(rect.right(), rect.bottom()),
+            Point::new(rect.x, rect.bottom()),
+        ])
+    }
+}
+
+/// An affine transform of image coordinates, mapping `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`.
+///
+/// Image coordinates grow to the right and down, so positive rotation angles turn clockwise
+/// on screen. Transforms are combined with [`then`](Self::then):
+///
+/// ```
+/// use oneocr_rs::{Affine, Point};
+///
+/// let transform = Affine::scale(2.0, 2.0).then(&Affine::translate(10.0, 0.0));
+/// assert_eq!(transform.apply(Point::new(1.0, 1.0)), Point::new(12.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Affine::IDENTITY
+    }
+}
+
+impl Affine {
+    /// The transform that leaves points unchanged.
+    pub const IDENTITY: Affine = Affine {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    /// Moves points by `(dx, dy)`.
+    pub fn translate(dx: f32, dy: f32) -> Self {
+        Affine {
+            e: dx,
+            f: dy,
+            ..Affine::IDENTITY
+        }
+    }
+
+    /// Scales points away from the origin by `sx` horizontally and `sy` vertically.
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Affine {
+            a: sx,
+            d: sy,
+            ..Affine::IDENTITY
+        }
+    }
+
+    /// Rotates points around the origin by `degrees`, clockwise on screen.
+    pub fn rotate(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Affine {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// Rotates points around `center` by `degrees`, clockwise on screen.
+    pub fn rotate_around(degrees: f32, center: Point) -> Self {
+        Affine::translate(-center.x, -center.y)
+            .then(&Affine::rotate(degrees))
+            .then(&Affine::translate(center.x, center.y))
+    }
+
+    /// Returns the transform applying `self`, then `next`.
+    pub fn then(&self, next: &Affine) -> Affine {
+        Affine {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// Returns the transform undoing this one, or `None` if it collapses points onto a line.
+    ///
+    /// The determinant is compared relative to its terms, so uniformly tiny or huge scales still
+    /// invert.
+    pub fn inverse(&self) -> Option<Affine> {
+        let (ad, bc) = (self.a * self.d, self.b * self.c);
+        let determinant = ad - bc;
+        if !determinant.is_finite() || determinant.abs() <= f32::EPSILON * ad.abs().max(bc.abs()) {
+            return None;
+        }
+        let (a, b) = (self.d / determinant, -self.b / determinant);
+        let (c, d) = (-self.c / determinant, self.a / determinant);
+        Some(Affine {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f),
+        })
+    }
+
+    /// Transforms a point.
+    pub fn apply(&self, point: Point) -> Point {
+        Point::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+}
+
+impl Point {
+    /// Creates a point.
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+
+    /// Returns the Euclidean distance to another point.
+    pub fn distance(&self, other: Point) -> f32 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+}
+
+impl BoundingBox {
+    /// Creates a box from its corners, clockwise on screen from the top left.
+    pub fn from_corners([top_left, top_right, bottom_right, bottom_left]: [Point; 4]) -> Self {
+        BoundingBox {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    /// Creates an axis-aligned box from its top left corner and size.
+    pub fn from_rect(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Rect::new(x, y, width, height).into()
+    }
+
+    /// Returns the corners, clockwise on screen from the top left.
+    pub fn corners(&self) -> [Point; 4] {
+        [
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+    }
+
+    /// Returns the area enclosed by the corners.
+    pub fn area(&self) -> f32 {
+        polygon_area(&self.corners()).abs()
+    }
+
+    /// Returns the center of mass of the enclosed area, or the mean of the corners if the
+    /// box has no area.
+    pub fn centroid(&self) -> Point {
+        let corners = self.corners();
+        let area = polygon_area(&corners);
+        if area.abs() < f32::EPSILON {
+            let sum = corners.iter().fold(Point::default(), |sum, p| {
+                Point::new(sum.x + p.x, sum.y + p.y)
+            });
+            return Point::new(sum.x / 4.0, sum.y / 4.0);
+        }
+        // Relative to a corner, which keeps the products small for boxes far from the origin.
+        let origin = corners[0];
+        let relative = corners.map(|p| Point::new(p.x - origin.x, p.y - origin.y));
+        let (mut x, mut y) = (0.0, 0.0);
+        for (p, q) in edges(&relative) {
+            let cross = p.x * q.y - q.x * p.y;
+            x += (p.x + q.x) * cross;
+            y += (p.y + q.y) * cross;
+        }
+        Point::new(origin.x + x / (6.0 * area), origin.y + y / (6.0 * area))
+    }
+
+    /// Returns the smallest axis-aligned rectangle enclosing the corners.
+    pub fn bounds(&self) -> Rect {
+        let corners = self.corners();
+        let left = corners.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let top = corners.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let right = corners
+            .iter()
+            .map(|p| p.x)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let bottom = corners
+            .iter()
+            .map(|p| p.y)
+            .fold(f32::NEG_INFINITY, f32::max);
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Returns the angle of the text baseline, from the bottom left to the bottom right corner,
+    /// in degrees clockwise on screen from the x axis, between -180 and 180.
+    pub fn angle(&self) -> f32 {
+        let (dx, dy) = (
+            self.bottom_right.x - self.bottom_left.x,
+            self.bottom_right.y - self.bottom_left.y,
+        );
+        dy.atan2(dx).to_degrees()
+    }
+
+    /// Returns the width along the text baseline: the mean length of the top and bottom edges.
+    pub fn width(&self) -> f32 {
+        (self.top_left.distance(self.top_right) + self.bottom_left.distance(self.bottom_right))
+            / 2.0
+    }
+
+    /// Returns the height across the text baseline: the mean length of the left and right edges.
+    pub fn height(&self) -> f32 {
+        (self.top_left.distance(self.bottom_left) + self.top_right.distance(self.bottom_right))
+            / 2.0
+    }
+
+    /// Returns whether `point` lies inside the box or on its edges.
+    pub fn contains(&self, point: Point) -> bool {
+        let corners = self.corners();
+        // With a tolerance relative to the box, so points on the edges count as inside.
+        let tolerance = 1e-4 * (1.0 + self.bounds().width.max(self.bounds().height));
+        let mut inside = false;
+        for (p, q) in edges(&corners) {
+            if distance_to_segment(point, p, q) <= tolerance {
+                return true;
+            }
+            if (p.y > point.y) != (q.y > point.y)
+                && point.x < p.x + (point.y - p.y) / (q.y - p.y) * (q.x - p.x)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Returns the polygon where this box and `other` overlap, or an empty list if they don't.
+    ///
+    /// Both boxes must be convex, as OCR boxes are.
+    pub fn intersection(&self, other: &BoundingBox) -> Vec<Point> {
+        let clip = other.corners();
+        // Which side of the clip edges is inside depends on the winding of the clip polygon.
+        let winding = polygon_area(&clip).signum();
+        let mut polygon = self.corners().to_vec();
+        for (p, q) in edges(&clip) {
+            if polygon.is_empty() {
+                break;
+            }
+            let inside = |point: Point| winding * cross(p, q, point) >= 0.0;
+            let input = std::mem::take(&mut polygon);
+            for (i, &current) in input.iter().enumerate() {
+                let previous = input[(i + input.len() - 1) % input.len()];
+                match (inside(previous), inside(current)) {
+                    (true, true) => polygon.push(current),
+                    (true, false) => polygon.push(line_intersection(previous, current, p, q)),
+                    (false, true) => {
+                        polygon.push(line_intersection(previous, current, p, q));
+                        polygon.push(current);
+                    }
+                    (false, false) => {}
+                }
+            }
+        }
+        if polygon_area(&polygon).abs() < f32::EPSILON {
+            polygon.clear();
+        }
+        polygon
+    }
+
+    /// Returns the area where this box and `other` overlap. Both boxes must be convex.
+    pub fn intersection_area(&self, other: &BoundingBox) -> f32 {
+        polygon_area(&self.intersection(other)).abs()
+    }
+
+    /// Returns the intersection over union of this box and `other`, from 0 (disjoint) to 1
+    /// (identical). Both boxes must be convex.
+    pub fn iou(&self, other: &BoundingBox) -> f32 {
+        let intersection = self.intersection_area(other);
+        let union = self.area() + other.area() - intersection;
+        if union <= 0.0 {
+            return 0.0;
+        }
+        (intersection / union).clamp(0.0, 1.0)
+    }
+
+    /// Returns the smallest axis-aligned box enclosing this box and `other`.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        self.bounds().union(&other.bounds()).into()
+    }
+
+    /// Returns the smallest box enclosing this box and `other` that keeps the baseline angle
+    /// of this box, e.g. to join the words of a rotated line.
+    pub fn merge(&self, other: &BoundingBox) -> BoundingBox {
+        let center = self.centroid();
+        let upright = Affine::rotate_around(-self.angle(), center);
+        let enclosing = self.transform(&upright).union(&other.transform(&upright));
+        enclosing.transform(&Affine::rotate_around(self.angle(), center))
+    }
+
+    /// Returns the smallest integer rectangle `[left, top, right, bottom]` enclosing all the
+    /// boxes, like [`pixel_bounds`](Self::pixel_bounds), or `None` if there are none.
+    pub(crate) fn pixel_bounds_of<'a>(
+        boxes: impl IntoIterator<Item = &'a BoundingBox>,
+    ) -> Option<[u32; 4]> {
+        boxes.into_iter().map(BoundingBox::pixel_bounds).reduce(
+            |[l0, t0, r0, b0], [l1, t1, r1, b1]| [l0.min(l1), t0.min(t1), r0.max(r1), b0.max(b1)],
+        )
+    }
+
+    /// Returns the box with every corner transformed.
+    pub fn transform(&self, transform: &Affine) -> BoundingBox {
+        BoundingBox::from_corners(self.corners().map(|p| transform.apply(p)))
+    }
+
+    /// Returns the box moved by `(dx, dy)`.
+    pub fn translate(&self, dx: f32, dy: f32) -> BoundingBox {
+        self.transform(&Affine::translate(dx, dy))
+    }
+
+    /// Returns the box scaled away from the origin by `sx` horizontally and `sy` vertically.
+    pub fn scale(&self, sx: f32, sy: f32) -> BoundingBox {
+        self.transform(&Affine::scale(sx, sy))
+    }
+
+    /// Returns the box rotated around its centroid by `degrees`, clockwise on screen.
+    pub fn rotate(&self, degrees: f32) -> BoundingBox {
+        self.transform(&Affine::rotate_around(degrees, self.centroid()))
+    }
+}
+
+/// Returns the median of the values, the upper one for an even count, or `None` if there are
+/// none.
+pub(crate) fn median(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let mut values: Vec<f32> = values.collect();
+    values.sort_by(f32::total_cmp);
+    values.get(values.len() / 2).copied()
+}
+
+/// Returns the edges of a polygon, from each point to the next.
+fn edges(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&p, &q)| (p, q))
+}
+
+/// Returns the signed area of a polygon, positive if it winds clockwise on screen.
+fn polygon_area(polygon: &[Point]) -> f32 {
+    let Some(&origin) = polygon.first() else {
+        return 0.0;
+    };
+    edges(polygon)
+        .map(|(p, q)| {
+            let (p, q) = (
+                Point::new(p.x - origin.x, p.y - origin.y),
+                Point::new(q.x - origin.x, q.y - origin.y),
+            );
+            p.x * q.y - q.x * p.y
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Returns the cross product of `q - p` and `r - p`, positive if `r` is clockwise from `p -> q`
+/// on screen.
+fn cross(p: Point, q: Point, r: Point) -> f32 {
+    (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)
+}
+
+/// Returns where the segment from `p` to `q` crosses the line through `r` and `s`.
+fn line_intersection(p: Point, q: Point, r: Point, s: Point) -> Point {
+    let (dp, dr) = ((q.x - p.x, q.y - p.y), (s.x - r.x, s.y - r.y));
+    let denominator = dp.0 * dr.1 - dp.1 * dr.0;
+    if denominator.abs() < f32::EPSILON {
+        return q;
+    }
+    let t = ((r.x - p.x) * dr.1 - (r.y - p.y) * dr.0) / denominator;
+    Point::new(p.x + t * dp.0, p.y + t * dp.1)
+}
+
+fn distance_to_segment(point: Point, p: Point, q: Point) -> f32 {
+    let (dx, dy) = (q.x - p.x, q.y - p.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return point.distance(p);
+    }
+    let t = (((point.x - p.x) * dx + (point.y - p.y) * dy) / length_squared).clamp(0.0, 1.0);
+    point.distance(Point::new(p.x + t * dx, p.y + t * dy))
+}
@@ -0,0 +1,227 @@
+/// The units per em of the font.
+pub(crate) const UNITS_PER_EM: u16 = 1000;
+/// The advance of both glyphs.
+pub(crate) const ADVANCE: u16 = 500;
+
+/// Builds a TrueType font without outlines for the invisible text layer of searchable PDFs,
+/// like the `pdf.ttf` of Tesseract. It has two empty glyphs, `.notdef` and the glyph every
+/// character of the text layer is mapped to, each [`ADVANCE`] units wide.
+pub(crate) fn glyphless_font() -> Vec<u8> {
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = vec![
+        (*b"OS/2", os2()),
+        (*b"cmap", cmap()),
+        // Both glyphs are empty, so they have no data.
+        (*b"glyf", Vec::new()),
+        (*b"head", head()),
+        (*b"hhea", hhea()),
+        (
+            *b"hmtx",
+            [ADVANCE, 0, ADVANCE, 0]
+                .iter()
+                .flat_map(|v| v.to_be_bytes())
+                .collect(),
+        ),
+        // Short offsets of the two glyphs and the end of the glyph data.
+        (*b"loca", vec![0; 6]),
+        (*b"maxp", maxp()),
+        (*b"name", name()),
+        (*b"post", post()),
+    ];
+    // The directory lists tables sorted by tag.
+    tables.sort_by_key(|(tag, _)| *tag);
+
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = 16 << entry_selector;
+    let mut font = Vec::new();
+    font.extend(0x0001_0000u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        font.extend(value.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        font.extend(tag);
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    let mut head_offset = 0;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = font.len();
+        }
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    // The checksum adjustment makes the checksum of the whole font 0xB1B0AFBA.
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
+
+/// Returns the sum of the big-endian `u32`s of a table, padded with zeros.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Appends big-endian 16-bit values.
+fn push_u16(table: &mut Vec<u8>, values: &[u16]) {
+    for value in values {
+        table.extend(value.to_be_bytes());
+    }
+}
+
+fn head() -> Vec<u8> {
+    let mut head = Vec::with_capacity(54);
+    // Version 1.0, font revision 1.0, checksum adjustment (set last), magic number.
+    for value in [0x0001_0000u32, 0x0001_0000, 0, 0x5F0F_3CF5] {
+        head.extend(value.to_be_bytes());
+    }
+    // Flags: baseline and left side bearing at 0, integer scaling.
+    push_u16(&mut head, &[0x000B, UNITS_PER_EM]);
+    // Created and modified, in seconds since 1904.
+    head.extend([0; 16]);
+    // xMin, yMin, xMax, yMax.
+    push_u16(&mut head, &[0, 0, ADVANCE, UNITS_PER_EM]);
+    // Mac style, smallest readable size, font direction hint, short offsets, glyph format.
+    push_u16(&mut head, &[0, 3, 2, 0, 0]);
+    head
+}
+
+fn hhea() -> Vec<u8> {
+    let mut hhea = Vec::with_capacity(36);
+    hhea.extend(0x0001_0000u32.to_be_bytes());
+    // Ascender, descender, line gap, widest advance, min left and right side bearings,
+    // max extent, caret slope rise and run, caret offset, 4 reserved, metric format,
+    // number of horizontal metrics.
+    push_u16(
+        &mut hhea,
+        &[
+            UNITS_PER_EM,
+            0,
+            0,
+            ADVANCE,
+            0,
+            0,
+            0,
+            1,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            2,
+        ],
+    );
+    hhea
+}
+
+fn maxp() -> Vec<u8> {
+    let mut maxp = Vec::with_capacity(32);
+    maxp.extend(0x0001_0000u32.to_be_bytes());
+    // Two glyphs without points, contours or instructions, and two zones.
+    push_u16(&mut maxp, &[2, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+    maxp
+}
+
+/// A Windows symbol `cmap` that maps no characters: the PDF selects glyphs by ID.
+fn cmap() -> Vec<u8> {
+    let mut cmap = Vec::with_capacity(36);
+    // Version, one subtable for platform 3 (Windows), encoding 0 (symbol), at offset 12.
+    push_u16(&mut cmap, &[0, 1, 3, 0]);
+    cmap.extend(12u32.to_be_bytes());
+    // Format 4 with only the final segment: format, length, language, segment count times 2,
+    // search range, entry selector, range shift, end code, padding, start code, delta,
+    // range offset.
+    push_u16(&mut cmap, &[4, 24, 0, 2, 2, 0, 0, 0xFFFF, 0, 0xFFFF, 1, 0]);
+    cmap
+}
+
+fn name() -> Vec<u8> {
+    let names: [(u16, &str); 6] = [
+        (1, "GlyphLessFont"),
+        (2, "Regular"),
+        (3, "oneocr-rs:GlyphLessFont"),
+        (4, "GlyphLessFont"),
+        (5, "Version 1.0"),
+        (6, "GlyphLessFont"),
+    ];
+    let strings: Vec<Vec<u8>> = names
+        .iter()
+        .map(|(_, text)| text.encode_utf16().flat_map(u16::to_be_bytes).collect())
+        .collect();
+
+    let mut name = Vec::new();
+    push_u16(
+        &mut name,
+        &[0, names.len() as u16, 6 + 12 * names.len() as u16],
+    );
+    let mut offset = 0;
+    for ((name_id, _), string) in names.iter().zip(&strings) {
+        // Windows, Unicode BMP, US English.
+        push_u16(
+            &mut name,
+            &[3, 1, 0x0409, *name_id, string.len() as u16, offset],
+        );
+        offset += string.len() as u16;
+    }
+    for string in strings {
+        name.extend(string);
+    }
+    name
+}
+
+fn os2() -> Vec<u8> {
+    let mut os2 = Vec::with_capacity(96);
+    // Version 4, average width, regular weight, medium width, installable embedding, sub- and
+    // superscript sizes and offsets, strikeout size and position, no family class.
+    push_u16(
+        &mut os2,
+        &[
+            4, ADVANCE, 400, 5, 0, 650, 600, 0, 75, 650, 600, 0, 350, 50, 300, 0,
+        ],
+    );
+    // PANOSE classification: any.
+    os2.extend([0; 10]);
+    // Unicode ranges.
+    os2.extend([0; 16]);
+    os2.extend(b"NONE");
+    // Regular style, first and last character, typographic ascender, descender and line gap,
+    // Windows ascent and descent.
+    push_u16(
+        &mut os2,
+        &[0x0040, 0, 0xFFFF, UNITS_PER_EM, 0, 0, UNITS_PER_EM, 0],
+    );
+    // Code page ranges: symbol character set.
+    os2.extend(0x8000_0000u32.to_be_bytes());
+    os2.extend(0u32.to_be_bytes());
+    // x-height, cap height, default character, break character, max context.
+    push_u16(&mut os2, &[500, UNITS_PER_EM, 0, 0, 1]);
+    os2
+}
+
+fn post() -> Vec<u8> {
+    let mut post = Vec::with_capacity(32);
+    // Format 3 has no glyph names. Italic angle 0.
+    post.extend(0x0003_0000u32.to_be_bytes());
+    post.extend(0u32.to_be_bytes());
+    // Underline position (-100) and thickness.
+    push_u16(&mut post, &[(-100i16) as u16, 50]);
+    // Fixed pitch: both glyphs have the same width.
+    post.extend(1u32.to_be_bytes());
+    // Memory usage hints.
+    post.extend([0; 16]);
+    post
+}
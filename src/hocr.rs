@@ -0,0 +1,100 @@
+use crate::bounding_box::BoundingBox;
+use crate::ocr_line::OcrLine;
+use crate::ocr_result::OcrResult;
+use crate::xml::escape;
+use std::fmt::Write;
+
+impl OcrResult {
+    /// Renders the result as an [hOCR](http://kba.github.io/hocr-spec/1.2/) document.
+    ///
+    /// The page is an `ocr_page` sized after the image, with the image angle as `textangle`.
+    /// Each of the [`blocks`](Self::blocks) is an `ocr_carea` of `ocr_par` paragraphs, grouped
+    /// on the fly if the result has none. Each line is an `ocr_line` and, with word-level
+    /// details, each word is an `ocrx_word` with its confidence as `x_wconf` (0-100). Boxes
+    /// are the axis-aligned bounds of the quadrilateral bounding boxes.
+    pub fn to_hocr(&self) -> String {
+        let (width, height) = self.page_size();
+        let mut hocr = String::new();
+        hocr.push_str(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" ",
+            "\"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">\n",
+            "<html xmlns=\"http://www.w3.org/1999/xhtml\" xml:lang=\"en\" lang=\"en\">\n",
+            " <head>\n",
+            "  <title></title>\n",
+            "  <meta http-equiv=\"Content-Type\" content=\"text/html;charset=utf-8\"/>\n",
+            "  <meta name=\"ocr-system\" content=\"oneocr-rs ",
+            env!("CARGO_PKG_VERSION"),
+            "\"/>\n",
+            "  <meta name=\"ocr-capabilities\" content=\"ocr_page ocr_carea ocr_par ocr_line ocrx_word\"/>\n",
+            " </head>\n",
+            " <body>\n",
+        ));
+        // Writing to a `String` never fails.
+        let _ = writeln!(
+            hocr,
+            "  <div class=\"ocr_page\" id=\"page_1\" title=\"bbox 0 0 {width} {height}; ppageno 0; textangle {}\">",
+            self.image_angle
+        );
+        let blocks = self.current_blocks();
+        let mut paragraph_id = 0;
+        for (b, block) in blocks.iter().enumerate() {
+            let _ = writeln!(
+                hocr,
+                "   <div class=\"ocr_carea\" id=\"block_1_{}\" title=\"{}\">",
+                b + 1,
+                bbox(&block.bounding_box)
+            );
+            for paragraph in &block.paragraphs {
+                paragraph_id += 1;
+                let _ = writeln!(
+                    hocr,
+                    "    <p class=\"ocr_par\" id=\"par_1_{paragraph_id}\" title=\"{}\">",
+                    bbox(&paragraph.bounding_box)
+                );
+                for &l in &paragraph.lines {
+                    write_line(&mut hocr, l, &self.lines[l]);
+                }
+                hocr.push_str("    </p>\n");
+            }
+            hocr.push_str("   </div>\n");
+        }
+        hocr.push_str("  </div>\n </body>\n</html>\n");
+        hocr
+    }
+}
+
+/// Writes the `ocr_line` of the line with index `l`, and its words.
+fn write_line(hocr: &mut String, l: usize, line: &OcrLine) {
+    let _ = write!(
+        hocr,
+        "     <span class=\"ocr_line\" id=\"line_1_{}\" title=\"{}\">",
+        l + 1,
+        bbox(&line.bounding_box)
+    );
+    match &line.words {
+        Some(words) => {
+            hocr.push('\n');
+            for (w, word) in words.iter().enumerate() {
+                let _ = writeln!(
+                    hocr,
+                    "      <span class=\"ocrx_word\" id=\"word_1_{}_{}\" title=\"{}; x_wconf {}\">{}</span>",
+                    l + 1,
+                    w + 1,
+                    bbox(&word.bounding_box),
+                    (word.confidence.clamp(0.0, 1.0) * 100.0).round(),
+                    escape(&word.text)
+                );
+            }
+            hocr.push_str("     ");
+        }
+        None => hocr.push_str(&escape(&line.text)),
+    }
+    hocr.push_str("</span>\n");
+}
+
+/// Formats the hOCR `bbox` property of a bounding box.
+fn bbox(bounding_box: &BoundingBox) -> String {
+    let [left, top, right, bottom] = bounding_box.pixel_bounds();
+    format!("bbox {left} {top} {right} {bottom}")
+}
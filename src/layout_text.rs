@@ -0,0 +1,111 @@
+use crate::geometry::median;
+use crate::ocr_line::OcrLine;
+use crate::ocr_result::OcrResult;
+
+/// A line placed on the page, in pixels.
+struct PlacedLine<'a> {
+    text: &'a str,
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl PlacedLine<'_> {
+    fn center(&self) -> f32 {
+        (self.top + self.bottom) / 2.0
+    }
+}
+
+impl OcrResult {
+    /// Renders the text as plain text laid out on a character grid, so that the columns of
+    /// tables and forms stay aligned in `.txt` output.
+    ///
+    /// Lines whose vertical centers fall within the same line of text share a row, and each
+    /// line starts at the column matching its left edge, measured in the median character
+    /// width of the page. Vertical gaps of about a line's height or more become blank rows.
+    /// Lines that would overlap are separated by a single space. Positions are taken from the
+    /// axis-aligned bounds of the bounding boxes, so the layout of strongly rotated pages is
+    /// only approximated.
+    pub fn to_layout_text(&self) -> String {
+        let mut lines: Vec<PlacedLine> = self
+            .lines
+            .iter()
+            .filter(|line| !line.text.trim().is_empty())
+            .map(place)
+            .collect();
+        if lines.is_empty() {
+            return String::new();
+        }
+        let char_width = median(
+            lines
+                .iter()
+                .map(|line| (line.right - line.left) / line.text.chars().count() as f32)
+                .filter(|width| *width > 0.0),
+        )
+        .unwrap_or(1.0);
+        let line_height = median(
+            lines
+                .iter()
+                .map(|line| line.bottom - line.top)
+                .filter(|height| *height > 0.0),
+        )
+        .unwrap_or(1.0);
+        let page_left = lines
+            .iter()
+            .map(|line| line.left)
+            .fold(f32::INFINITY, f32::min);
+
+        lines.sort_by(|a, b| a.center().total_cmp(&b.center()));
+        let mut rows: Vec<Vec<PlacedLine>> = Vec::new();
+        for line in lines {
+            match rows.last_mut() {
+                Some(row) if line.center() <= row[0].bottom => row.push(line),
+                _ => rows.push(vec![line]),
+            }
+        }
+
+        let mut text = String::new();
+        let mut previous_bottom: Option<f32> = None;
+        for mut row in rows {
+            let top = row
+                .iter()
+                .map(|line| line.top)
+                .fold(f32::INFINITY, f32::min);
+            if let Some(previous_bottom) = previous_bottom {
+                let blank_rows = ((top - previous_bottom) / line_height).round().max(0.0);
+                text.extend(std::iter::repeat_n('\n', blank_rows as usize));
+            }
+            previous_bottom = Some(row.iter().map(|line| line.bottom).fold(0.0, f32::max));
+
+            row.sort_by(|a, b| a.left.total_cmp(&b.left));
+            let mut row_text = String::new();
+            let mut row_len = 0;
+            for line in row {
+                let column = ((line.left - page_left) / char_width).round().max(0.0) as usize;
+                let column = if row_len > 0 && column <= row_len {
+                    row_len + 1
+                } else {
+                    column
+                };
+                row_text.extend(std::iter::repeat_n(' ', column - row_len));
+                row_text.push_str(line.text);
+                row_len = column + line.text.chars().count();
+            }
+            text.push_str(row_text.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn place(line: &OcrLine) -> PlacedLine<'_> {
+    let [left, top, right, bottom] = line.bounding_box.pixel_bounds();
+    PlacedLine {
+        text: line.text.trim(),
+        left: left as f32,
+        top: top as f32,
+        right: right as f32,
+        bottom: bottom as f32,
+    }
+}
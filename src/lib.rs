@@ -1,25 +1,72 @@
+mod alto;
+#[cfg(feature = "async")]
+mod async_ocr_engine;
+mod blocks;
 mod bounding_box;
+mod coordinates;
+#[cfg(feature = "cpu")]
+mod cpu_backend;
+mod dll_backend;
 mod errors;
 mod ffi;
+mod geometry;
+#[cfg(feature = "pdf")]
+mod glyphless_font;
+mod hocr;
 mod image_input;
+mod layout_text;
+mod ocr_backend;
 mod ocr_engine;
+mod ocr_engine_builder;
+mod ocr_engine_pool;
 mod ocr_line;
 mod ocr_options;
 mod ocr_result;
 mod ocr_word;
+mod onemodel;
+mod page_xml;
+mod reading_order;
+mod replay_backend;
+mod result_store;
+#[cfg(feature = "pdf")]
+mod searchable_pdf;
+mod tables;
+mod tsv;
+mod xml;
 
 // Re-export the public structs for easier access
+pub use alto::AltoOptions;
+#[cfg(feature = "async")]
+pub use async_ocr_engine::{AsyncOcrEngine, AsyncOptions};
+pub use blocks::{Block, BlockOptions, Paragraph};
 pub use bounding_box::BoundingBox;
 pub use bounding_box::Point;
+pub use coordinates::PreprocessingStep;
+#[cfg(feature = "cpu")]
+pub use cpu_backend::{CpuBackend, CpuOptions};
+pub use dll_backend::DllBackend;
 pub use errors::OneOcrError;
+pub use geometry::{Affine, Rect};
 pub use image_input::ImageInput;
+pub use ocr_backend::{Handle, OcrBackend};
 pub use ocr_engine::OcrEngine;
-pub use ocr_line::OcrLine;
-pub use ocr_options::{OcrOptions, Resolution};
+pub use ocr_engine_builder::OcrEngineBuilder;
+pub use ocr_engine_pool::{OcrEnginePool, PoolStats, PooledEngine};
+pub use ocr_line::{LineStyle, OcrLine};
+pub use ocr_options::{OcrOptions, Resolution, RunOptions};
 pub use ocr_result::OcrResult;
 pub use ocr_word::OcrWord;
+pub use onemodel::{ModelEntry, ModelEntryKind, OneModel};
+pub use page_xml::PageXmlOptions;
+pub use reading_order::{ReadingOrderOptions, TextDirection};
+pub use replay_backend::{ReplayBackend, image_hash};
+#[cfg(feature = "pdf")]
+pub use searchable_pdf::{PdfOptions, SearchablePdf};
+pub use tables::{Table, TableCell, TableOptions};
 
+pub(crate) const ONE_OCR_LIBRARY_NAME: &str = "oneocr";
 pub(crate) const ONE_OCR_MODEL_FILE_NAME: &str = "oneocr.onemodel";
+pub(crate) const ONE_OCR_MODEL_PATH_ENV: &str = "ONEOCR_MODEL_PATH";
 pub(crate) const ONE_OCR_MODEL_KEY: &str = r#"kj)TGtrK>f]b[Piow.gU+nC@s""""""4"#;
 
 /// A macro to check the result of an OCR call and return an error if it fails.
@@ -41,3 +88,15 @@ macro_rules! check_ocr_call {
 }
 
 pub(crate) use check_ocr_call;
+
+/// Formats bytes as a lowercase hex string.
+pub(crate) fn hex_string(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
@@ -0,0 +1,169 @@
+use crate::bounding_box::BoundingBox;
+use crate::errors::OneOcrError;
+use image::{ImageBuffer, Rgba};
+use std::ffi::{CStr, c_void};
+
+/// An opaque handle to an object owned by an [`OcrBackend`].
+///
+/// Handles mirror the `void*` objects of the OneOCR C API (init options, pipelines,
+/// process options, results, lines and words). A backend is free to use any value it
+/// likes, e.g. a real pointer for `oneocr.dll` or an index into a table for a fake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle(usize);
+
+impl Handle {
+    /// The null handle.
+    pub const NULL: Handle = Handle(0);
+
+    /// Creates a handle from a raw value.
+    pub const fn from_raw(value: usize) -> Self {
+        Handle(value)
+    }
+
+    /// Returns the raw value of the handle.
+    pub const fn into_raw(self) -> usize {
+        self.0
+    }
+
+    /// Creates a handle from a pointer returned by the C API.
+    pub fn from_ptr(ptr: *mut c_void) -> Self {
+        Handle(ptr as usize)
+    }
+
+    /// Returns the handle as a pointer suitable for the C API.
+    pub fn as_ptr(self) -> *mut c_void {
+        self.0 as *mut c_void
+    }
+
+    /// Returns `true` if this is the null handle.
+    pub const fn is_null(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// The set of operations `OcrEngine` needs from an OCR implementation.
+///
+/// Each method corresponds to one function exported by `oneocr.dll`. The DLL itself is
+/// available through [`DllBackend`](crate::DllBackend); other implementations (fakes,
+/// replays, ...) let `OcrEngine`, `OcrResult`, `OcrLine` and `OcrWord` run without it.
+///
+/// Handles returned by `create_*` and `run_pipeline` are released through the matching
+/// `release_*` method. Line and word handles are owned by their result.
+///
+/// Backends are shared between threads, so every method may be called concurrently, as long
+/// as no object is used by two calls at once. The only exception is the pipeline, which is
+/// shared by every `run_pipeline` call, see [`supports_concurrent_runs`](Self::supports_concurrent_runs).
+pub trait OcrBackend: std::fmt::Debug + Send + Sync {
+    /// Whether `create_pipeline` needs an existing model file.
+    /// Backends that don't (e.g. fakes) can still be created when no model file is found.
+    fn requires_model_file(&self) -> bool {
+        true
+    }
+
+    /// Whether `run_pipeline` may be called concurrently on the same pipeline, each call with
+    /// its own process options.
+    /// If not, `OcrEngine` runs the pipeline and reads the result under a lock.
+    fn supports_concurrent_runs(&self) -> bool {
+        false
+    }
+
+    /// Creates a new init options object.
+    fn create_init_options(&self) -> Result<Handle, OneOcrError>;
+
+    /// Sets whether the model should be delay loaded.
+    fn set_use_model_delay_load(
+        &self,
+        init_options: Handle,
+        delay_load: bool,
+    ) -> Result<(), OneOcrError>;
+
+    /// Creates an OCR pipeline from the model file at `model_path`, decrypted with `key`.
+    fn create_pipeline(
+        &self,
+        model_path: &CStr,
+        key: &CStr,
+        init_options: Handle,
+    ) -> Result<Handle, OneOcrError>;
+
+    /// Creates a new process options object.
+    fn create_process_options(&self) -> Result<Handle, OneOcrError>;
+
+    /// Retrieves the maximum number of lines that can be recognized.
+    fn get_max_recognition_line_count(&self, process_options: Handle) -> Result<i32, OneOcrError>;
+
+    /// Sets the maximum number of lines that can be recognized.
+    fn set_max_recognition_line_count(
+        &self,
+        process_options: Handle,
+        count: i32,
+    ) -> Result<(), OneOcrError>;
+
+    /// Retrieves the maximum internal resize resolution as `(width, height)`.
+    fn get_resize_resolution(&self, process_options: Handle) -> Result<(i64, i64), OneOcrError>;
+
+    /// Sets the maximum internal resize resolution.
+    fn set_resize_resolution(
+        &self,
+        process_options: Handle,
+        width: i32,
+        height: i32,
+    ) -> Result<(), OneOcrError>;
+
+    /// Runs the pipeline on an RGBA image and returns a result handle.
+    fn run_pipeline(
+        &self,
+        pipeline: Handle,
+        image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        process_options: Handle,
+    ) -> Result<Handle, OneOcrError>;
+
+    /// Retrieves the detected image angle of a result.
+    fn get_image_angle(&self, result: Handle) -> Result<f32, OneOcrError>;
+
+    /// Retrieves the number of lines in a result.
+    fn get_line_count(&self, result: Handle) -> Result<i64, OneOcrError>;
+
+    /// Retrieves the line at `index` of a result.
+    fn get_line(&self, result: Handle, index: i64) -> Result<Handle, OneOcrError>;
+
+    /// Retrieves the text of a line.
+    fn get_line_content(&self, line: Handle) -> Result<String, OneOcrError>;
+
+    /// Retrieves the bounding box of a line.
+    fn get_line_bounding_box(&self, line: Handle) -> Result<BoundingBox, OneOcrError>;
+
+    /// Retrieves the style of a line (0 = handwritten, 1 = printed) and its confidence.
+    fn get_line_style(&self, line: Handle) -> Result<(i32, f32), OneOcrError>;
+
+    /// Retrieves the number of words in a line.
+    fn get_line_word_count(&self, line: Handle) -> Result<i64, OneOcrError>;
+
+    /// Retrieves the word at `index` of a line.
+    fn get_word(&self, line: Handle, index: i64) -> Result<Handle, OneOcrError>;
+
+    /// Retrieves the text of a word.
+    fn get_word_content(&self, word: Handle) -> Result<String, OneOcrError>;
+
+    /// Retrieves the bounding box of a word.
+    fn get_word_bounding_box(&self, word: Handle) -> Result<BoundingBox, OneOcrError>;
+
+    /// Retrieves the recognition confidence of a word.
+    fn get_word_confidence(&self, word: Handle) -> Result<f32, OneOcrError>;
+
+    /// Releases a result and all of its lines and words.
+    fn release_result(&self, result: Handle);
+
+    /// Releases an init options object.
+    fn release_init_options(&self, init_options: Handle);
+
+    /// Releases a pipeline.
+    fn release_pipeline(&self, pipeline: Handle);
+
+    /// Releases a process options object.
+    fn release_process_options(&self, process_options: Handle);
+}
+
+/// Converts a line or word count returned by a backend, rejecting negative counts.
+pub(crate) fn checked_count(count: i64, what: &'static str) -> Result<usize, OneOcrError> {
+    usize::try_from(count).map_err(|_| OneOcrError::InvalidCount { what, count })
+}
@@ -1,100 +1,125 @@
+use crate::dll_backend::DllBackend;
 use crate::errors::OneOcrError;
-use crate::ffi::{
-    CreateOcrInitOptions, CreateOcrPipeline, CreateOcrProcessOptions,
-    OcrInitOptionsSetUseModelDelayLoad, OcrProcessOptionsGetMaxRecognitionLineCount,
-    OcrProcessOptionsGetResizeResolution, OcrProcessOptionsSetMaxRecognitionLineCount,
-    OcrProcessOptionsSetResizeResolution, RawImage, ReleaseOcrInitOptions, ReleaseOcrPipeline,
-    ReleaseOcrProcessOptions, RunOcrPipeline,
-};
+use crate::ocr_backend::{Handle, OcrBackend};
+use crate::ocr_engine_builder::OcrEngineBuilder;
+use crate::ocr_options::{validate_max_recognition_line_count, validate_resize_resolution};
 use crate::ocr_result::OcrResult;
-use crate::{ImageInput, ONE_OCR_MODEL_FILE_NAME, ONE_OCR_MODEL_KEY, OcrOptions};
+use crate::{
+    ImageInput, ONE_OCR_MODEL_FILE_NAME, ONE_OCR_MODEL_KEY, ONE_OCR_MODEL_PATH_ENV, OcrOptions,
+    RunOptions,
+};
 use image::{DynamicImage, ImageBuffer, Rgba};
-use std::ffi::{CString, c_void};
-use std::ptr;
-
-// Macros
-use crate::check_ocr_call;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 /// The `OcrEngine` struct represents the OneOcr processing engine.
+///
+/// # Threading
+///
+/// The engine is `Send` and `Sync`: it can be moved to a worker thread, or shared through an
+/// `Arc` (e.g. in the state of a web server) and used from many threads at once.
+///  - [`run`](Self::run) and the process option getters and setters share the engine's process
+///    options; setters wait for running calls and vice versa.
+///  - [`run_with`](Self::run_with) uses its own process options, so it only waits for other
+///    calls if the backend needs it.
+///  - If the backend doesn't support concurrent runs (see
+///    [`OcrBackend::supports_concurrent_runs`]), which is the case of `oneocr.dll`, pipeline
+///    runs and result reads are serialized. Load images and post-process results outside of the
+///    engine to keep the serialized part short, or use several engines to run in parallel.
 #[derive(Debug)]
 pub struct OcrEngine {
-    init_options: *mut c_void,
-    pipeline: *mut c_void,
-    process_options: *mut c_void,
+    backend: Arc<dyn OcrBackend>,
+    init_options: Handle,
+    pipeline: Handle,
+    process_options: Handle,
     ocr_options: OcrOptions,
+    /// Guards the engine's process options: setters write them, `run` reads them.
+    process_options_lock: RwLock<()>,
+    /// Serializes pipeline runs on backends that don't support concurrent runs.
+    pipeline_lock: Mutex<()>,
 }
 
 impl OcrEngine {
     /// Creates a new instance of the OCR engine with specified options.
     /// This function loads the necessary library and initializes the OCR pipeline with the provided options.
     pub fn new_with_options(ocr_options: OcrOptions) -> Result<Self, OneOcrError> {
-        let mut init_options: *mut c_void = ptr::null_mut();
-        check_ocr_call!(
-            unsafe { CreateOcrInitOptions(&mut init_options) },
-            "Failed to create init options"
-        );
-
-        // Disable model delay load
-        check_ocr_call!(
-            unsafe { OcrInitOptionsSetUseModelDelayLoad(init_options, 0) },
-            "Failed to set model delay load"
-        );
-
-        let model_path = Self::get_model_path()?;
-        let model_path_cstr = CString::new(model_path).map_err(|e| {
-            OneOcrError::ModelFileLoadError(format!("Failed to convert model path to CString: {e}"))
-        })?;
+        Self::new_with_backend(DllBackend::new()?, ocr_options)
+    }
 
-        let key_cstr = CString::new(ONE_OCR_MODEL_KEY).map_err(|e| {
-            OneOcrError::InvalidModelKey(format!("Failed to convert model key to CString: {e}"))
-        })?;
+    /// Creates a new instance of the OCR engine on top of a custom [`OcrBackend`].
+    /// This is useful to run the engine against something other than `oneocr.dll`, e.g. an in-process fake in tests.
+    pub fn new_with_backend(
+        backend: impl OcrBackend + 'static,
+        ocr_options: OcrOptions,
+    ) -> Result<Self, OneOcrError> {
+        Self::new_with_shared_backend(Arc::new(backend), ocr_options)
+    }
+
+    /// Creates a new instance of the OCR engine on a backend that may already be shared.
+    pub(crate) fn new_with_shared_backend(
+        backend: Arc<dyn OcrBackend>,
+        ocr_options: OcrOptions,
+    ) -> Result<Self, OneOcrError> {
+        ocr_options.validate()?;
 
-        let mut pipeline: *mut c_void = ptr::null_mut();
-        check_ocr_call!(
-            unsafe {
-                CreateOcrPipeline(
-                    model_path_cstr.as_ptr(),
-                    key_cstr.as_ptr(),
-                    init_options,
-                    &mut pipeline,
-                )
-            },
-            "Failed to create OCR pipeline"
-        );
-
-        let mut process_options: *mut c_void = ptr::null_mut();
-        check_ocr_call!(
-            unsafe { CreateOcrProcessOptions(&mut process_options) },
-            "Failed to create OCR process options"
-        );
-
-        check_ocr_call!(
-            unsafe {
-                OcrProcessOptionsSetMaxRecognitionLineCount(
-                    process_options,
-                    ocr_options.max_recognition_line_count,
-                )
-            },
-            "Failed to set max recognition line count"
-        );
-
-        check_ocr_call!(
-            unsafe {
-                OcrProcessOptionsSetResizeResolution(
-                    process_options,
-                    ocr_options.resize_resolution.width,
-                    ocr_options.resize_resolution.height,
-                )
-            },
-            "Failed to set resize resolution"
-        );
-
-        Ok(Self {
+        let init_options = backend.create_init_options()?;
+        // From here on, the engine owns the handles and releases them on drop, even on error.
+        let mut engine = Self {
+            backend,
             init_options,
-            pipeline,
-            process_options,
+            pipeline: Handle::NULL,
+            process_options: Handle::NULL,
             ocr_options,
-        })
+            process_options_lock: RwLock::new(()),
+            pipeline_lock: Mutex::new(()),
+        };
+
+        engine.backend.set_use_model_delay_load(
+            engine.init_options,
+            engine.ocr_options.use_model_delay_load,
+        )?;
+
+        let model_path = match Self::resolve_model_path(&engine.ocr_options) {
+            Ok(model_path) => model_path,
+            Err(_) if !engine.backend.requires_model_file() => PathBuf::new(),
+            Err(e) => return Err(e),
+        };
+        let model_path_cstr =
+            CString::new(model_path.to_string_lossy().as_bytes()).map_err(|e| {
+                OneOcrError::ModelFileLoadError(format!(
+                    "Failed to convert model path to CString: {e}"
+                ))
+            })?;
+
+        let key = engine
+            .ocr_options
+            .model_key
+            .as_deref()
+            .unwrap_or(ONE_OCR_MODEL_KEY);
+        let key_cstr = CString::new(key).map_err(|e| {
+            OneOcrError::InvalidModelKey(format!("Failed to convert model key to CString: {e}"))
+        })?;
+
+        engine.pipeline =
+            engine
+                .backend
+                .create_pipeline(&model_path_cstr, &key_cstr, engine.init_options)?;
+
+        engine.process_options = engine.backend.create_process_options()?;
+
+        engine.backend.set_max_recognition_line_count(
+            engine.process_options,
+            engine.ocr_options.max_recognition_line_count,
+        )?;
+
+        engine.backend.set_resize_resolution(
+            engine.process_options,
+            engine.ocr_options.resize_resolution.width,
+            engine.ocr_options.resize_resolution.height,
+        )?;
+
+        Ok(engine)
     }
 
     /// Creates a new instance of the OCR engine with default options.
@@ -103,27 +128,47 @@ impl OcrEngine {
         Self::new_with_options(OcrOptions::default())
     }
 
+    /// Returns a builder to configure and create an OCR engine.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oneocr_rs::OcrEngine;
+    ///
+    /// let engine = OcrEngine::builder()
+    ///     .max_recognition_line_count(500)
+    ///     .resize_resolution(800, 600)
+    ///     .include_word_level_details(true)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> OcrEngineBuilder {
+        OcrEngineBuilder::new()
+    }
+
+    /// Returns the options the engine was created with.
+    pub fn options(&self) -> &OcrOptions {
+        &self.ocr_options
+    }
+
     /// Retrieves the maximum number of lines that can be recognized.
     /// Default is 100.
     pub fn get_max_recognition_line_count(&self) -> Result<i32, OneOcrError> {
-        let mut count: i32 = 0;
-        check_ocr_call!(
-            unsafe {
-                OcrProcessOptionsGetMaxRecognitionLineCount(self.process_options, &mut count)
-            },
-            "Failed to get max recognition line count"
-        );
-        Ok(count)
+        let _process_options = self.read_process_options();
+        self.backend
+            .get_max_recognition_line_count(self.process_options)
     }
 
     /// Sets the maximum number of lines that can be recognized.
     /// Default is 100, range is 0-1000.
+    ///
+    /// This applies to every later call to [`run`](Self::run). Use [`run_with`](Self::run_with)
+    /// to change the limit for a single call only.
     pub fn set_max_recognition_line_count(&self, count: i32) -> Result<(), OneOcrError> {
-        check_ocr_call!(
-            unsafe { OcrProcessOptionsSetMaxRecognitionLineCount(self.process_options, count) },
-            "Failed to set max recognition line count"
-        );
-        Ok(())
+        validate_max_recognition_line_count(count)?;
+        let _process_options = self.write_process_options();
+        self.backend
+            .set_max_recognition_line_count(self.process_options, count)
     }
 
     /// Retrieves the maximum internal resize resolution.
@@ -133,15 +178,8 @@ impl OcrEngine {
     ///
     /// Default is 1152*768.
     pub fn get_resize_resolution(&self) -> Result<(i64, i64), OneOcrError> {
-        let mut width: i64 = 0;
-        let mut height: i64 = 0;
-        check_ocr_call!(
-            unsafe {
-                OcrProcessOptionsGetResizeResolution(self.process_options, &mut width, &mut height)
-            },
-            "Failed to get resize resolution"
-        );
-        Ok((width, height))
+        let _process_options = self.read_process_options();
+        self.backend.get_resize_resolution(self.process_options)
     }
 
     /// Sets the maximum internal resize resolution.
@@ -150,12 +188,14 @@ impl OcrEngine {
     /// It’s a performance and accuracy trade-off rather than a restriction on the original image’s resolution.
     ///
     /// The maximum resolution is 1152*768.
+    ///
+    /// This applies to every later call to [`run`](Self::run). Use [`run_with`](Self::run_with)
+    /// to change the resolution for a single call only.
     pub fn set_resize_resolution(&self, width: i32, height: i32) -> Result<(), OneOcrError> {
-        check_ocr_call!(
-            unsafe { OcrProcessOptionsSetResizeResolution(self.process_options, width, height) },
-            "Failed to set resize resolution"
-        );
-        Ok(())
+        validate_resize_resolution(width, height)?;
+        let _process_options = self.write_process_options();
+        self.backend
+            .set_resize_resolution(self.process_options, width, height)
     }
 
     /// Run OCR processing on an image.
@@ -190,7 +230,58 @@ impl OcrEngine {
     /// ```
     pub fn run(&self, input: ImageInput) -> Result<OcrResult, OneOcrError> {
         let img_rgba = self.load_image(input)?;
-        self.run_ocr_on_rgba_image(&img_rgba, self.ocr_options.include_word_level_details)
+        let _process_options = self.read_process_options();
+        self.run_ocr_on_rgba_image(
+            &img_rgba,
+            self.process_options,
+            self.ocr_options.include_word_level_details,
+        )
+    }
+
+    /// Run OCR processing on an image with options that only apply to this call.
+    ///
+    /// Each call gets its own process options object, so the options neither change the
+    /// engine's options used by [`run`](Self::run) nor affect concurrent calls.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oneocr_rs::{OcrEngine, RunOptions};
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new().unwrap();
+    /// let mut options = RunOptions::default();
+    /// options.max_recognition_line_count = 10;
+    /// options.include_word_level_details = true;
+    /// let result = engine.run_with(Path::new("image.jpg").into(), &options).unwrap();
+    /// ```
+    pub fn run_with(
+        &self,
+        input: ImageInput,
+        run_options: &RunOptions,
+    ) -> Result<OcrResult, OneOcrError> {
+        run_options.validate()?;
+        let img_rgba = self.load_image(input)?;
+
+        let process_options = ProcessOptions {
+            backend: self.backend.as_ref(),
+            handle: self.backend.create_process_options()?,
+        };
+        self.backend.set_max_recognition_line_count(
+            process_options.handle,
+            run_options.max_recognition_line_count,
+        )?;
+        self.backend.set_resize_resolution(
+            process_options.handle,
+            run_options.resize_resolution.width,
+            run_options.resize_resolution.height,
+        )?;
+
+        self.run_ocr_on_rgba_image(
+            &img_rgba,
+            process_options.handle,
+            run_options.include_word_level_details,
+        )
     }
 
     /// Loads an image from various input sources and converts it to RGBA format.
@@ -217,54 +308,134 @@ impl OcrEngine {
     fn run_ocr_on_rgba_image(
         &self,
         img_rgba: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        process_options: Handle,
         word_level_detail: bool,
     ) -> Result<OcrResult, OneOcrError> {
-        let (rows, cols) = (img_rgba.height() as i32, img_rgba.width() as i32);
-        let step = (img_rgba.sample_layout().height_stride) as i64;
-        let data_ptr = img_rgba.as_ptr() as i64;
-        let image = RawImage {
-            t: 3, // RGBA format identifier expected by the C API
-            col: cols,
-            row: rows,
-            _unk: 0,
-            step,
-            data_ptr,
+        let pipeline = self.lock_pipeline();
+        let ocr_result = self
+            .backend
+            .run_pipeline(self.pipeline, img_rgba, process_options)?;
+        let result = OcrResult::from_backend(self.backend.as_ref(), ocr_result, word_level_detail)?;
+        // Post-processing doesn't touch the backend, so it runs outside of the serialized part.
+        drop(pipeline);
+
+        Ok(result
+            .with_blocks()
+            .with_image_size(img_rgba.width(), img_rgba.height()))
+    }
+
+    // The locks guard no data, so a panic while holding them leaves nothing inconsistent.
+
+    fn read_process_options(&self) -> RwLockReadGuard<'_, ()> {
+        self.process_options_lock
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_process_options(&self) -> RwLockWriteGuard<'_, ()> {
+        self.process_options_lock
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Locks the pipeline, unless the backend supports concurrent runs.
+    fn lock_pipeline(&self) -> Option<MutexGuard<'_, ()>> {
+        (!self.backend.supports_concurrent_runs())
+            .then(|| self.pipeline_lock.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Resolves the path to the model file.
+    ///
+    /// If `model_path` is set, it is the only candidate. Otherwise `model_search_paths` is searched,
+    /// followed by the `ONEOCR_MODEL_PATH` environment variable, the directory of the current
+    /// executable, the current working directory and the user config directory.
+    fn resolve_model_path(ocr_options: &OcrOptions) -> Result<PathBuf, OneOcrError> {
+        let candidates = match &ocr_options.model_path {
+            Some(model_path) => vec![model_path.clone()],
+            None => Self::model_search_candidates(&ocr_options.model_search_paths),
         };
 
-        let mut ocr_result: *mut c_void = ptr::null_mut();
-        check_ocr_call!(
-            unsafe { RunOcrPipeline(self.pipeline, &image, self.process_options, &mut ocr_result) },
-            "Failed to run OCR pipeline"
-        );
+        candidates
+            .iter()
+            .find(|candidate| candidate.is_file())
+            .cloned()
+            .ok_or(OneOcrError::ModelFileNotFound {
+                searched: candidates,
+            })
+    }
+
+    /// Lists the model file candidates, in search order.
+    fn model_search_candidates(search_paths: &[PathBuf]) -> Vec<PathBuf> {
+        let mut candidates: Vec<PathBuf> = search_paths
+            .iter()
+            .map(|dir| dir.join(ONE_OCR_MODEL_FILE_NAME))
+            .collect();
+
+        // The environment variable may point to the model file itself or to its directory.
+        if let Some(env_path) = std::env::var_os(ONE_OCR_MODEL_PATH_ENV).map(PathBuf::from) {
+            if env_path.is_dir() {
+                candidates.push(env_path.join(ONE_OCR_MODEL_FILE_NAME));
+            } else {
+                candidates.push(env_path);
+            }
+        }
+
+        if let Some(exe_dir) = std::env::current_exe()
+            .ok()
+            .and_then(|exe_path| exe_path.parent().map(Path::to_path_buf))
+        {
+            candidates.push(exe_dir.join(ONE_OCR_MODEL_FILE_NAME));
+        }
+
+        if let Ok(cwd) = std::env::current_dir() {
+            candidates.push(cwd.join(ONE_OCR_MODEL_FILE_NAME));
+        }
 
-        OcrResult::new(ocr_result, word_level_detail)
+        if let Some(config_dir) = user_config_dir() {
+            candidates.push(config_dir.join("oneocr").join(ONE_OCR_MODEL_FILE_NAME));
+        }
+
+        candidates
     }
+}
 
-    /// Retrieves the path to the model file.
-    fn get_model_path() -> Result<String, OneOcrError> {
-        let exe_path = std::env::current_exe().map_err(|e| {
-            OneOcrError::ModelFileLoadError(format!("Failed to get current executable path: {e}"))
-        })?;
-        let model_path_buf = exe_path
-            .parent()
-            .ok_or_else(|| {
-                OneOcrError::ModelFileLoadError(
-                    "Failed to get parent directory of current executable".to_string(),
-                )
-            })?
-            .join(ONE_OCR_MODEL_FILE_NAME);
-        let model_path_string = model_path_buf.to_string_lossy().to_string();
-
-        Ok(model_path_string)
+/// Returns the platform's user config directory, e.g. `%APPDATA%` on Windows or `~/.config` on Linux.
+fn user_config_dir() -> Option<PathBuf> {
+    let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+
+    if cfg!(windows) {
+        non_empty("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        non_empty("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        non_empty("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".config")))
     }
 }
 
 impl Drop for OcrEngine {
     fn drop(&mut self) {
-        unsafe {
-            ReleaseOcrPipeline(self.pipeline);
-            ReleaseOcrInitOptions(self.init_options);
-            ReleaseOcrProcessOptions(self.process_options);
-        };
+        if !self.pipeline.is_null() {
+            self.backend.release_pipeline(self.pipeline);
+        }
+        if !self.init_options.is_null() {
+            self.backend.release_init_options(self.init_options);
+        }
+        if !self.process_options.is_null() {
+            self.backend.release_process_options(self.process_options);
+        }
+    }
+}
+
+/// A process options object created for a single call, released when dropped.
+struct ProcessOptions<'a> {
+    backend: &'a dyn OcrBackend,
+    handle: Handle,
+}
+
+impl Drop for ProcessOptions<'_> {
+    fn drop(&mut self) {
+        self.backend.release_process_options(self.handle);
     }
 }
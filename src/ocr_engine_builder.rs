@@ -0,0 +1,132 @@
+#[cfg(feature = "async")]
+use crate::async_ocr_engine::{AsyncOcrEngine, AsyncOptions};
+use crate::dll_backend::DllBackend;
+use crate::errors::OneOcrError;
+use crate::ocr_backend::OcrBackend;
+use crate::ocr_engine::OcrEngine;
+use crate::ocr_engine_pool::OcrEnginePool;
+use crate::ocr_options::{OcrOptions, Resolution};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The `OcrEngineBuilder` struct configures and creates an [`OcrEngine`].
+///
+/// Options are checked by [`build`](Self::build) before any library or model is loaded:
+/// out-of-range values are reported as [`OneOcrError::InvalidMaxRecognitionLineCount`] or
+/// [`OneOcrError::InvalidResizeResolution`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::{OcrEngine, OcrOptions};
+///
+/// // Options loaded from a config file, tweaked in code.
+/// let options: OcrOptions = serde_json::from_str(r#"{ "include_word_level_details": true }"#).unwrap();
+/// let engine = OcrEngine::builder()
+///     .options(options)
+///     .model_path(r"C:\models\oneocr.onemodel")
+///     .use_model_delay_load(true)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct OcrEngineBuilder {
+    options: OcrOptions,
+    backend: Option<Arc<dyn OcrBackend>>,
+}
+
+impl OcrEngineBuilder {
+    /// Creates a builder with the default [`OcrOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces all options at once, e.g. with options loaded from a config file.
+    pub fn options(mut self, options: OcrOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the maximum number of lines that can be recognized, from 0 to 1000.
+    pub fn max_recognition_line_count(mut self, count: i32) -> Self {
+        self.options.max_recognition_line_count = count;
+        self
+    }
+
+    /// Sets the maximum internal resize resolution, at most 1152×768.
+    pub fn resize_resolution(mut self, width: i32, height: i32) -> Self {
+        self.options.resize_resolution = Resolution { width, height };
+        self
+    }
+
+    /// Sets whether results include words with their bounding boxes and confidences.
+    pub fn include_word_level_details(mut self, include: bool) -> Self {
+        self.options.include_word_level_details = include;
+        self
+    }
+
+    /// Sets whether the model is loaded lazily, on first use.
+    pub fn use_model_delay_load(mut self, delay_load: bool) -> Self {
+        self.options.use_model_delay_load = delay_load;
+        self
+    }
+
+    /// Sets the path of the `oneocr.onemodel` file, skipping the model search.
+    pub fn model_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.model_path = Some(path.into());
+        self
+    }
+
+    /// Adds a directory to search for `oneocr.onemodel`, before the default locations.
+    pub fn model_search_path(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.options.model_search_paths.push(dir.into());
+        self
+    }
+
+    /// Sets the key used to decrypt the model file.
+    pub fn model_key(mut self, key: impl Into<String>) -> Self {
+        self.options.model_key = Some(key.into());
+        self
+    }
+
+    /// Runs the engine on a custom [`OcrBackend`] instead of `oneocr.dll`.
+    pub fn backend(mut self, backend: impl OcrBackend + 'static) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Validates the options and creates the engine.
+    /// Without a custom backend, `oneocr.dll` is loaded from the default location.
+    pub fn build(mut self) -> Result<OcrEngine, OneOcrError> {
+        self.options.validate()?;
+        let backend = self.resolve_backend()?;
+        OcrEngine::new_with_shared_backend(backend, self.options)
+    }
+
+    /// Validates the options and creates a pool of `size` engines sharing the backend,
+    /// each with its own pipeline.
+    pub fn build_pool(mut self, size: usize) -> Result<OcrEnginePool, OneOcrError> {
+        self.options.validate()?;
+        let backend = self.resolve_backend()?;
+        OcrEnginePool::new_with_shared_backend(size, backend, self.options)
+    }
+
+    /// Validates the options and creates an [`AsyncOcrEngine`] whose workers share the backend.
+    #[cfg(feature = "async")]
+    pub fn build_async(
+        mut self,
+        async_options: AsyncOptions,
+    ) -> Result<AsyncOcrEngine, OneOcrError> {
+        self.options.validate()?;
+        let backend = self.resolve_backend()?;
+        AsyncOcrEngine::new_with_shared_backend(backend, self.options, async_options)
+    }
+
+    /// Returns the custom backend, or loads `oneocr.dll` from the default location.
+    fn resolve_backend(&mut self) -> Result<Arc<dyn OcrBackend>, OneOcrError> {
+        match self.backend.take() {
+            Some(backend) => Ok(backend),
+            None => Ok(Arc::new(DllBackend::new()?)),
+        }
+    }
+}
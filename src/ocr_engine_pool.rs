@@ -0,0 +1,308 @@
+use crate::dll_backend::DllBackend;
+use crate::errors::OneOcrError;
+use crate::ocr_backend::OcrBackend;
+use crate::ocr_engine::OcrEngine;
+use crate::ocr_result::OcrResult;
+use crate::{ImageInput, OcrOptions, RunOptions};
+use std::ops::Deref;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// The `OcrEnginePool` struct keeps a fixed number of ready [`OcrEngine`]s and lends them out.
+///
+/// Every engine has its own pipeline, so the pool runs up to `size` images in parallel even on
+/// backends that serialize runs on one pipeline, such as `oneocr.dll`. Pipelines are created
+/// once, up front, instead of for every image.
+///
+/// Engines are borrowed with [`get`](Self::get), which returns a [`PooledEngine`] guard that
+/// puts the engine back when dropped. [`run`](Self::run) and [`run_batch`](Self::run_batch)
+/// borrow engines for you.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::{ImageInput, OcrEnginePool, OcrOptions};
+/// use std::path::PathBuf;
+///
+/// let pool = OcrEnginePool::new(4, OcrOptions::default()).unwrap();
+/// let inputs = (1..=16).map(|i| ImageInput::FilePath(PathBuf::from(format!("page{i}.png"))));
+/// for result in pool.run_batch(inputs) {
+///     println!("{} lines", result.unwrap().lines.len());
+/// }
+/// println!("utilization: {:.0}%", pool.stats().utilization() * 100.0);
+/// ```
+#[derive(Debug)]
+pub struct OcrEnginePool {
+    state: Mutex<PoolState>,
+    available: Condvar,
+    size: usize,
+    created_at: Instant,
+}
+
+#[derive(Debug)]
+struct PoolState {
+    idle: Vec<OcrEngine>,
+    checkouts: u64,
+    waits: u64,
+    busy_time: Duration,
+    wait_time: Duration,
+}
+
+/// A snapshot of the utilization of an [`OcrEnginePool`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolStats {
+    /// The number of engines in the pool.
+    pub size: usize,
+    /// The number of engines currently lent out.
+    pub in_use: usize,
+    /// The number of times an engine was lent out.
+    pub checkouts: u64,
+    /// The number of checkouts that had to wait for an engine.
+    pub waits: u64,
+    /// The total time engines spent lent out, over completed checkouts.
+    pub busy_time: Duration,
+    /// The total time callers spent waiting for an engine.
+    pub wait_time: Duration,
+    /// The time since the pool was created.
+    pub uptime: Duration,
+}
+
+impl PoolStats {
+    /// Returns the share of the pool's capacity that was used since it was created,
+    /// from `0.0` (always idle) to `1.0` (every engine always busy).
+    pub fn utilization(&self) -> f64 {
+        let capacity = self.uptime.as_secs_f64() * self.size as f64;
+        if capacity == 0.0 {
+            return 0.0;
+        }
+        (self.busy_time.as_secs_f64() / capacity).min(1.0)
+    }
+}
+
+impl OcrEnginePool {
+    /// Creates a pool of `size` engines on `oneocr.dll`, loaded once from the default location.
+    pub fn new(size: usize, ocr_options: OcrOptions) -> Result<Self, OneOcrError> {
+        Self::new_with_backend(size, DllBackend::new()?, ocr_options)
+    }
+
+    /// Creates a pool of `size` engines sharing a custom [`OcrBackend`], each with its own pipeline.
+    pub fn new_with_backend(
+        size: usize,
+        backend: impl OcrBackend + 'static,
+        ocr_options: OcrOptions,
+    ) -> Result<Self, OneOcrError> {
+        Self::new_with_shared_backend(size, Arc::new(backend), ocr_options)
+    }
+
+    pub(crate) fn new_with_shared_backend(
+        size: usize,
+        backend: Arc<dyn OcrBackend>,
+        ocr_options: OcrOptions,
+    ) -> Result<Self, OneOcrError> {
+        if size == 0 {
+            return Err(OneOcrError::InvalidPoolSize(size));
+        }
+        let engines = (0..size)
+            .map(|_| OcrEngine::new_with_shared_backend(backend.clone(), ocr_options.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_engines(engines)
+    }
+
+    /// Creates a pool lending out the given engines.
+    pub fn from_engines(engines: Vec<OcrEngine>) -> Result<Self, OneOcrError> {
+        if engines.is_empty() {
+            return Err(OneOcrError::InvalidPoolSize(0));
+        }
+        Ok(Self {
+            size: engines.len(),
+            state: Mutex::new(PoolState {
+                idle: engines,
+                checkouts: 0,
+                waits: 0,
+                busy_time: Duration::ZERO,
+                wait_time: Duration::ZERO,
+            }),
+            available: Condvar::new(),
+            created_at: Instant::now(),
+        })
+    }
+
+    /// Returns the number of engines in the pool.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn state(&self) -> MutexGuard<'_, PoolState> {
+        // The state stays consistent even if another thread panicked while holding the lock.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Borrows an engine, waiting until one is available.
+    pub fn get(&self) -> PooledEngine<'_> {
+        self.checkout(None)
+            .expect("waiting without a timeout always yields an engine")
+    }
+
+    /// Borrows an engine, waiting at most `timeout`. Returns `None` if none became available.
+    pub fn get_timeout(&self, timeout: Duration) -> Option<PooledEngine<'_>> {
+        self.checkout(Some(timeout))
+    }
+
+    /// Borrows an engine if one is available right away.
+    pub fn try_get(&self) -> Option<PooledEngine<'_>> {
+        self.checkout(Some(Duration::ZERO))
+    }
+
+    fn checkout(&self, timeout: Option<Duration>) -> Option<PooledEngine<'_>> {
+        let started = Instant::now();
+        let mut state = self.state();
+        let waited = state.idle.is_empty();
+        while state.idle.is_empty() {
+            state = match timeout {
+                None => self
+                    .available
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner()),
+                Some(timeout) => {
+                    let remaining = timeout.checked_sub(started.elapsed())?;
+                    if remaining.is_zero() {
+                        return None;
+                    }
+                    self.available
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+            };
+        }
+
+        let engine = state.idle.pop().expect("an engine is idle");
+        state.checkouts += 1;
+        if waited {
+            state.waits += 1;
+            state.wait_time += started.elapsed();
+        }
+        Some(PooledEngine {
+            pool: self,
+            engine: Some(engine),
+            checked_out_at: Instant::now(),
+        })
+    }
+
+    fn checkin(&self, engine: OcrEngine, busy_time: Duration) {
+        let mut state = self.state();
+        state.idle.push(engine);
+        state.busy_time += busy_time;
+        drop(state);
+        self.available.notify_one();
+    }
+
+    /// Returns a snapshot of the pool's utilization.
+    pub fn stats(&self) -> PoolStats {
+        let state = self.state();
+        PoolStats {
+            size: self.size,
+            in_use: self.size - state.idle.len(),
+            checkouts: state.checkouts,
+            waits: state.waits,
+            busy_time: state.busy_time,
+            wait_time: state.wait_time,
+            uptime: self.created_at.elapsed(),
+        }
+    }
+
+    /// Runs OCR on an image with the first available engine, waiting for one if needed.
+    pub fn run(&self, input: ImageInput) -> Result<OcrResult, OneOcrError> {
+        self.get().run(input)
+    }
+
+    /// Runs OCR on an image with options that only apply to this call,
+    /// see [`OcrEngine::run_with`].
+    pub fn run_with(
+        &self,
+        input: ImageInput,
+        run_options: &RunOptions,
+    ) -> Result<OcrResult, OneOcrError> {
+        self.get().run_with(input, run_options)
+    }
+
+    /// Runs OCR on many images in parallel, one thread per engine, and returns the results in
+    /// the order of the inputs.
+    pub fn run_batch(
+        &self,
+        inputs: impl IntoIterator<Item = ImageInput>,
+    ) -> Vec<Result<OcrResult, OneOcrError>> {
+        self.fan_out(inputs, |engine, input| engine.run(input))
+    }
+
+    /// Runs OCR on many images in parallel with the same per-call options,
+    /// see [`run_batch`](Self::run_batch).
+    pub fn run_batch_with(
+        &self,
+        inputs: impl IntoIterator<Item = ImageInput>,
+        run_options: &RunOptions,
+    ) -> Vec<Result<OcrResult, OneOcrError>> {
+        self.fan_out(inputs, |engine, input| engine.run_with(input, run_options))
+    }
+
+    fn fan_out(
+        &self,
+        inputs: impl IntoIterator<Item = ImageInput>,
+        run: impl Fn(&OcrEngine, ImageInput) -> Result<OcrResult, OneOcrError> + Sync,
+    ) -> Vec<Result<OcrResult, OneOcrError>> {
+        let inputs: Vec<ImageInput> = inputs.into_iter().collect();
+        let count = inputs.len();
+        let queue = Mutex::new(inputs.into_iter().enumerate());
+        let results = Mutex::new(Vec::with_capacity(count));
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.size.min(count) {
+                scope.spawn(|| {
+                    loop {
+                        // Take the next input without holding the queue while it runs.
+                        let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                        let Some((index, input)) = next else {
+                            break;
+                        };
+                        // Engines are borrowed per image so other callers can interleave.
+                        let result = run(&self.get(), input);
+                        results
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+/// An [`OcrEngine`] borrowed from an [`OcrEnginePool`], put back when dropped.
+#[derive(Debug)]
+pub struct PooledEngine<'a> {
+    pool: &'a OcrEnginePool,
+    engine: Option<OcrEngine>,
+    checked_out_at: Instant,
+}
+
+impl Deref for PooledEngine<'_> {
+    type Target = OcrEngine;
+
+    fn deref(&self) -> &OcrEngine {
+        self.engine
+            .as_ref()
+            .expect("the engine is only taken on drop")
+    }
+}
+
+impl Drop for PooledEngine<'_> {
+    fn drop(&mut self) {
+        if let Some(engine) = self.engine.take() {
+            self.pool.checkin(engine, self.checked_out_at.elapsed());
+        }
+    }
+}
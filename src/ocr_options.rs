@@ -1,21 +1,54 @@
+use crate::errors::OneOcrError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
 /// A simple width×height pair.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Resolution {
     pub width: i32,
     pub height: i32,
 }
 
+impl Resolution {
+    /// The largest resize resolution supported by OneOCR.
+    pub const MAX: Resolution = Resolution {
+        width: 1152,
+        height: 768,
+    };
+}
+
 impl Default for Resolution {
     fn default() -> Self {
-        Resolution {
-            width: 1152,
-            height: 768,
-        }
+        Resolution::MAX
     }
 }
 
 /// Configuration for OCR processing behavior.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// The options can be loaded from any serde format, e.g. a TOML or JSON config file.
+/// Missing fields take their default value.
+///
+/// New options may be added in minor releases, so the struct can't be built with a struct
+/// expression outside this crate: start from [`OcrOptions::default`] and set the fields, or
+/// configure the engine with [`OcrEngine::builder`](crate::OcrEngine::builder).
+///
+/// # Examples
+///
+/// ```
+/// use oneocr_rs::OcrOptions;
+///
+/// let options: OcrOptions = serde_json::from_str(
+///     r#"{ "max_recognition_line_count": 500, "resize_resolution": { "width": 800, "height": 600 } }"#,
+/// )
+/// .unwrap();
+/// options.validate().unwrap();
+///
+/// let mut options = OcrOptions::default();
+/// options.include_word_level_details = true;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
 pub struct OcrOptions {
     /// The maximum number of lines that can be recognized.
     /// Default is 100, range is 0-1000.
@@ -33,6 +66,25 @@ pub struct OcrOptions {
     /// If `true`, the result will contain bounding boxes and confidence scores for individual words.
     /// If `false`, only line-level information will be available.
     pub include_word_level_details: bool,
+
+    /// Whether the model is loaded lazily, on first use, instead of when the engine is created.
+    pub use_model_delay_load: bool,
+
+    /// An explicit path to the `oneocr.onemodel` file.
+    /// If set, this is the only location that is tried.
+    pub model_path: Option<PathBuf>,
+
+    /// Additional directories to search for `oneocr.onemodel`.
+    ///
+    /// These are searched first, followed by the default locations:
+    /// the `ONEOCR_MODEL_PATH` environment variable (a model file or a directory containing it),
+    /// the directory of the current executable, the current working directory
+    /// and the `oneocr` folder in the user config directory.
+    pub model_search_paths: Vec<PathBuf>,
+
+    /// The key used to decrypt the model file.
+    /// If `None`, the key of the model shipped with the Snipping Tool is used.
+    pub model_key: Option<String>,
 }
 
 impl Default for OcrOptions {
@@ -41,6 +93,80 @@ impl Default for OcrOptions {
             max_recognition_line_count: 100,
             resize_resolution: Resolution::default(),
             include_word_level_details: false,
+            use_model_delay_load: false,
+            model_path: None,
+            model_search_paths: Vec::new(),
+            model_key: None,
+        }
+    }
+}
+
+impl OcrOptions {
+    /// The largest `max_recognition_line_count` supported by OneOCR.
+    pub const MAX_RECOGNITION_LINE_COUNT: i32 = 1000;
+
+    /// Checks that the options are within the ranges supported by OneOCR.
+    pub fn validate(&self) -> Result<(), OneOcrError> {
+        validate_max_recognition_line_count(self.max_recognition_line_count)?;
+        validate_resize_resolution(self.resize_resolution.width, self.resize_resolution.height)
+    }
+}
+
+/// Options of a single OCR call, see [`OcrEngine::run_with`](crate::OcrEngine::run_with).
+///
+/// The defaults match the defaults of [`OcrOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct RunOptions {
+    /// The maximum number of lines that can be recognized, from 0 to 1000.
+    pub max_recognition_line_count: i32,
+
+    /// The maximum internal resize resolution, at most 1152×768.
+    pub resize_resolution: Resolution,
+
+    /// Whether to include word-level details in the result.
+    pub include_word_level_details: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions::from(&OcrOptions::default())
+    }
+}
+
+impl From<&OcrOptions> for RunOptions {
+    fn from(options: &OcrOptions) -> Self {
+        RunOptions {
+            max_recognition_line_count: options.max_recognition_line_count,
+            resize_resolution: options.resize_resolution,
+            include_word_level_details: options.include_word_level_details,
         }
     }
 }
+
+impl RunOptions {
+    /// Checks that the options are within the ranges supported by OneOCR.
+    pub fn validate(&self) -> Result<(), OneOcrError> {
+        validate_max_recognition_line_count(self.max_recognition_line_count)?;
+        validate_resize_resolution(self.resize_resolution.width, self.resize_resolution.height)
+    }
+}
+
+/// Checks that `count` is within `0..=1000`.
+pub(crate) fn validate_max_recognition_line_count(count: i32) -> Result<(), OneOcrError> {
+    if !(0..=OcrOptions::MAX_RECOGNITION_LINE_COUNT).contains(&count) {
+        return Err(OneOcrError::InvalidMaxRecognitionLineCount(count));
+    }
+    Ok(())
+}
+
+/// Checks that `width`×`height` is at least 1×1 and at most 1152×768.
+pub(crate) fn validate_resize_resolution(width: i32, height: i32) -> Result<(), OneOcrError> {
+    if !(1..=Resolution::MAX.width).contains(&width)
+        || !(1..=Resolution::MAX.height).contains(&height)
+    {
+        return Err(OneOcrError::InvalidResizeResolution { width, height });
+    }
+    Ok(())
+}
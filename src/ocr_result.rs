@@ -1,60 +1,196 @@
+use crate::blocks::{Block, GroupedLines};
+use crate::bounding_box::BoundingBox;
+use crate::coordinates::PreprocessingStep;
 use crate::errors::OneOcrError;
-use crate::ocr_line::OcrLine;
-use serde::Serialize;
-use std::ffi::c_void;
-use std::ptr;
-
-// FFI types
-use crate::ffi::{GetImageAngle, GetOcrLine, GetOcrLineCount, ReleaseOcrResult};
-// Macros
-use crate::check_ocr_call;
+use crate::ocr_backend::{Handle, OcrBackend, checked_count};
+use crate::ocr_line::{LineStyle, OcrLine};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
 
 /// The `OcrResult` struct represents the result of an OCR operation.
 /// It contains the recognized text lines, their bounding boxes, and the image angle.
-#[derive(Debug, Serialize)]
+///
+/// The result is plain data: everything is read from the backend when the result is created
+/// and the backend's result object is released right away. Results can be cloned, sent to other
+/// threads, and outlive the engine that produced them.
+///
+/// Results serialize to the JSON format described by [`OcrResult::JSON_SCHEMA`], and can be
+/// loaded back with [`from_json`](Self::from_json) or any serde format.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct OcrResult {
-    #[serde(skip_serializing)]
-    result_handle: *mut c_void,
+    /// The version of the serialized format, see [`OcrResult::SCHEMA_VERSION`].
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
     pub lines: Vec<OcrLine>,
     pub image_angle: f32,
+    /// The width of the recognized image in pixels, or 0 if unknown.
+    #[serde(default)]
+    pub image_width: u32,
+    /// The height of the recognized image in pixels, or 0 if unknown.
+    #[serde(default)]
+    pub image_height: u32,
+    /// The steps that turned the original image into the coordinate space of the boxes,
+    /// including transforms of the boxes by [`transform`](Self::transform), see
+    /// [`to_original_coordinates`](Self::to_original_coordinates).
+    #[serde(default)]
+    pub preprocessing: Vec<PreprocessingStep>,
+    /// The lines grouped into blocks and paragraphs, in reading order. Filled in by the engine,
+    /// or by [`with_blocks`](Self::with_blocks) for results created with [`new`](Self::new).
+    ///
+    /// Paragraphs refer to lines by index, so call [`with_blocks`](Self::with_blocks) again
+    /// after adding, removing, reordering or replacing lines. Serialization and the hOCR
+    /// export group the lines afresh if the blocks don't hold every line exactly once, or if
+    /// the lines changed since the blocks were grouped by [`with_blocks`](Self::with_blocks)
+    /// or loaded by [`from_json`](Self::from_json).
+    #[serde(default)]
+    pub blocks: Vec<Block>,
+    #[serde(skip)]
+    pub(crate) grouped_lines: GroupedLines,
+}
+
+/// Results serialized before the format was versioned are version 1.
+fn first_schema_version() -> u32 {
+    1
 }
 
 impl OcrResult {
-    pub(crate) fn new(
-        result_handle: *mut c_void,
+    /// The version of the serialized format written by this crate.
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// The JSON Schema (draft 2020-12) of the serialized format.
+    pub const JSON_SCHEMA: &'static str = include_str!("../schema/ocr_result.schema.json");
+
+    /// Creates a result from already recognized lines, e.g. loaded from a database.
+    pub fn new(lines: Vec<OcrLine>, image_angle: f32) -> Self {
+        Self {
+            schema_version: Self::SCHEMA_VERSION,
+            lines,
+            image_angle,
+            image_width: 0,
+            image_height: 0,
+            preprocessing: Vec::new(),
+            blocks: Vec::new(),
+            grouped_lines: GroupedLines::default(),
+        }
+    }
+
+    /// Sets the size of the recognized image, used as the page size by the exporters.
+    pub fn with_image_size(mut self, width: u32, height: u32) -> Self {
+        self.image_width = width;
+        self.image_height = height;
+        self
+    }
+
+    /// Returns the size of the recognized image, or the extent of the recognized lines
+    /// if it is unknown.
+    pub(crate) fn page_size(&self) -> (u32, u32) {
+        if self.image_width > 0 && self.image_height > 0 {
+            return (self.image_width, self.image_height);
+        }
+        BoundingBox::pixel_bounds_of(self.lines.iter().map(|line| &line.bounding_box))
+            .map_or((0, 0), |[_, _, right, bottom]| (right, bottom))
+    }
+
+    /// Serializes the result to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("OCR results always serialize to JSON")
+    }
+
+    /// Deserializes a result from JSON, rejecting formats newer than this crate supports.
+    pub fn from_json(json: &str) -> Result<Self, OneOcrError> {
+        let mut result: Self = serde_json::from_str(json)
+            .map_err(|e| OneOcrError::InvalidResultJson(e.to_string()))?;
+        if result.schema_version > Self::SCHEMA_VERSION {
+            return Err(OneOcrError::UnsupportedSchemaVersion {
+                found: result.schema_version,
+                supported: Self::SCHEMA_VERSION,
+            });
+        }
+        result.grouped_lines = GroupedLines::of(&result);
+        Ok(result)
+    }
+
+    pub(crate) fn from_backend(
+        backend: &dyn OcrBackend,
+        result_handle: Handle,
         word_level_detail: bool,
     ) -> Result<Self, OneOcrError> {
-        let mut line_count: i64 = 0;
-        check_ocr_call!(
-            unsafe { GetOcrLineCount(result_handle, &mut line_count) },
-            "Failed to get line count"
-        );
-        let mut lines = Vec::with_capacity(line_count as usize);
+        // Take ownership of the handle first, so it is released even if reading the lines fails.
+        let result = ResultHandle {
+            backend,
+            handle: result_handle,
+        };
+
+        let line_count = backend.get_line_count(result.handle)?;
+        let mut lines = Vec::with_capacity(checked_count(line_count, "line")?);
         for i in 0..line_count {
-            let mut line: *mut c_void = ptr::null_mut();
-            check_ocr_call!(
-                unsafe { GetOcrLine(result_handle, i, &mut line) },
-                "Failed to get line"
-            );
-            let ocr_line = OcrLine::new(line, word_level_detail)?;
+            let line = backend.get_line(result.handle, i)?;
+            let ocr_line = OcrLine::from_backend(backend, line, word_level_detail)?;
             lines.push(ocr_line);
         }
-        let mut angle: f32 = 0.0;
-        check_ocr_call!(
-            unsafe { GetImageAngle(result_handle, &mut angle) },
-            "Failed to get image angle"
-        );
-
-        Ok(Self {
-            result_handle,
-            lines,
-            image_angle: angle,
-        })
+        let image_angle = backend.get_image_angle(result.handle)?;
+
+        Ok(Self::new(lines, image_angle))
+    }
+
+    /// Returns the lines of the given style.
+    pub fn lines_with_style(&self, style: LineStyle) -> impl Iterator<Item = &OcrLine> {
+        self.lines.iter().filter(move |line| line.style == style)
+    }
+
+    /// Returns the handwritten lines.
+    pub fn handwritten_lines(&self) -> impl Iterator<Item = &OcrLine> {
+        self.lines_with_style(LineStyle::Handwritten)
+    }
+
+    /// Returns the printed lines.
+    pub fn printed_lines(&self) -> impl Iterator<Item = &OcrLine> {
+        self.lines_with_style(LineStyle::Printed)
     }
+
+    /// Returns the lines whose style was classified with at least `min_confidence`,
+    /// e.g. to send the other lines to human review.
+    pub fn lines_with_style_confidence(
+        &self,
+        min_confidence: f32,
+    ) -> impl Iterator<Item = &OcrLine> {
+        self.lines
+            .iter()
+            .filter(move |line| line.style_confidence >= min_confidence)
+    }
+}
+
+impl Serialize for OcrResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("OcrResult", 7)?;
+        state.serialize_field("schema_version", &self.schema_version)?;
+        state.serialize_field("lines", &self.lines)?;
+        state.serialize_field("image_angle", &self.image_angle)?;
+        state.serialize_field("image_width", &self.image_width)?;
+        state.serialize_field("image_height", &self.image_height)?;
+        if self.preprocessing.is_empty() {
+            state.skip_field("preprocessing")?;
+        } else {
+            state.serialize_field("preprocessing", &self.preprocessing)?;
+        }
+        if self.blocks.is_empty() {
+            state.skip_field("blocks")?;
+        } else {
+            // Stale blocks are grouped afresh, like in the hOCR export.
+            state.serialize_field("blocks", &self.current_blocks())?;
+        }
+        state.end()
+    }
+}
+
+/// Releases a backend result object, and with it its line and word handles, when dropped.
+struct ResultHandle<'a> {
+    backend: &'a dyn OcrBackend,
+    handle: Handle,
 }
 
-impl Drop for OcrResult {
+impl Drop for ResultHandle<'_> {
     fn drop(&mut self) {
-        unsafe { ReleaseOcrResult(self.result_handle) };
+        self.backend.release_result(self.handle);
     }
 }
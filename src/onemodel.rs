@@ -0,0 +1,284 @@
+use crate::errors::OneOcrError;
+use crate::{ONE_OCR_MODEL_KEY, hex_string};
+use cfb_mode::Decryptor;
+use cfb_mode::cipher::{AsyncStreamCipher, KeyIvInit};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// The IV used for every encrypted block of a `.onemodel` container.
+const ONE_MODEL_IV: &[u8; 16] = b"Copyright @ OneO";
+
+/// The magic number every decrypted block starts with.
+const ONE_MODEL_MAGIC: [u8; 8] = [0x4a, 0x1a, 0x08, 0x2b, 0x25, 0x00, 0x00, 0x00];
+
+/// The kind of an entry embedded in a `.onemodel` container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelEntryKind {
+    /// The ONNX text detection graph. This is the first ONNX graph of the container.
+    Detector,
+    /// An ONNX text recognition graph (or another auxiliary graph following the detector).
+    Recognizer,
+    /// A character map decoding recognizer outputs to text, stored as UTF-8 lines.
+    CharacterMap,
+    /// Any other blob, such as pipeline configuration.
+    Config,
+}
+
+impl ModelEntryKind {
+    /// Returns the file extension used when extracting an entry of this kind.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ModelEntryKind::Detector | ModelEntryKind::Recognizer => "onnx",
+            ModelEntryKind::CharacterMap => "txt",
+            ModelEntryKind::Config => "bin",
+        }
+    }
+}
+
+impl std::fmt::Display for ModelEntryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ModelEntryKind::Detector => "detector",
+            ModelEntryKind::Recognizer => "recognizer",
+            ModelEntryKind::CharacterMap => "charmap",
+            ModelEntryKind::Config => "config",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A decrypted entry of a `.onemodel` container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelEntry {
+    /// The position of the entry in the container.
+    pub index: usize,
+    /// The kind of the entry, guessed from its content.
+    pub kind: ModelEntryKind,
+    /// The offset of the entry's block in the container file.
+    pub offset: u64,
+    /// The decrypted content.
+    pub data: Vec<u8>,
+}
+
+impl ModelEntry {
+    /// Returns the size of the decrypted content in bytes.
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the lowercase hex SHA-256 of the decrypted content.
+    pub fn sha256(&self) -> String {
+        hex_string(&Sha256::digest(&self.data))
+    }
+
+    /// Returns the file name used when extracting this entry, e.g. `003_recognizer.onnx`.
+    pub fn file_name(&self) -> String {
+        format!("{:03}_{}.{}", self.index, self.kind, self.kind.extension())
+    }
+}
+
+/// The `OneModel` struct represents a decrypted `.onemodel` container.
+///
+/// The container is laid out as follows (all integers little-endian):
+///  - `u64` header length, followed by the encrypted header.
+///  - Any number of entries, each a `u64` length, a 16-byte salt and the encrypted entry.
+///
+/// Blocks are encrypted with AES-256-CFB128 and the IV `"Copyright @ OneO"`. The header uses the
+/// model key directly, entries use `SHA-256(key || salt)`. Every decrypted block starts with an
+/// 8-byte magic number and a `u64` payload length, which is how a wrong key is detected.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::OneModel;
+///
+/// let model = OneModel::open("oneocr.onemodel").unwrap();
+/// for entry in model.entries() {
+///     println!("{} {} bytes {}", entry.file_name(), entry.size(), entry.sha256());
+/// }
+/// model.extract_all("./extracted").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct OneModel {
+    header: Vec<u8>,
+    entries: Vec<ModelEntry>,
+}
+
+impl OneModel {
+    /// Opens and decrypts a `.onemodel` file with the key of the model shipped with the Snipping Tool.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, OneOcrError> {
+        Self::open_with_key(path, ONE_OCR_MODEL_KEY.as_bytes())
+    }
+
+    /// Opens and decrypts a `.onemodel` file with a custom 32-byte key.
+    pub fn open_with_key(path: impl AsRef<Path>, key: &[u8]) -> Result<Self, OneOcrError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            OneOcrError::ModelFileLoadError(format!("Failed to read {}: {e}", path.display()))
+        })?;
+        Self::parse(&bytes, key)
+    }
+
+    /// Decrypts a `.onemodel` container held in memory.
+    pub fn parse(bytes: &[u8], key: &[u8]) -> Result<Self, OneOcrError> {
+        let key: &[u8; 32] = key.try_into().map_err(|_| {
+            OneOcrError::InvalidModelKey(format!("Expected a 32-byte key, got {} bytes", key.len()))
+        })?;
+
+        let mut reader = Reader { bytes, offset: 0 };
+        let header_len = reader.read_len()?;
+        let header = decrypt_block(reader.read(header_len)?, key, 0)?;
+
+        let mut entries = Vec::new();
+        while !reader.is_empty() {
+            let offset = reader.offset as u64;
+            let len = reader.read_len()?;
+            let salt = reader.read(16)?;
+            let entry_key: [u8; 32] = Sha256::new()
+                .chain_update(key)
+                .chain_update(salt)
+                .finalize()
+                .into();
+            let data = decrypt_block(reader.read(len)?, &entry_key, offset)?;
+
+            let is_first_graph = !entries
+                .iter()
+                .any(|entry: &ModelEntry| entry.kind == ModelEntryKind::Detector);
+            let kind = classify(&data, is_first_graph);
+            entries.push(ModelEntry {
+                index: entries.len(),
+                kind,
+                offset,
+                data,
+            });
+        }
+
+        Ok(Self { header, entries })
+    }
+
+    /// Returns the decrypted container header.
+    pub fn header(&self) -> &[u8] {
+        &self.header
+    }
+
+    /// Returns the entries of the container, in order.
+    pub fn entries(&self) -> &[ModelEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries of a given kind.
+    pub fn entries_of_kind(&self, kind: ModelEntryKind) -> impl Iterator<Item = &ModelEntry> {
+        self.entries.iter().filter(move |entry| entry.kind == kind)
+    }
+
+    /// Writes every entry to `dir`, named by [`ModelEntry::file_name`], and returns the written paths.
+    pub fn extract_all(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, OneOcrError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(|source| OneOcrError::ModelExtractError {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        self.entries
+            .iter()
+            .map(|entry| {
+                let path = dir.join(entry.file_name());
+                match std::fs::write(&path, &entry.data) {
+                    Ok(()) => Ok(path),
+                    Err(source) => Err(OneOcrError::ModelExtractError { path, source }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A cursor over the container bytes.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
+    fn read(&mut self, len: usize) -> Result<&'a [u8], OneOcrError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| {
+                OneOcrError::InvalidModelFormat(format!(
+                    "Unexpected end of data: {len} bytes needed at offset {}",
+                    self.offset
+                ))
+            })?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn read_len(&mut self) -> Result<usize, OneOcrError> {
+        let bytes = self.read(8)?;
+        let len = u64::from_le_bytes(bytes.try_into().expect("read 8 bytes"));
+        usize::try_from(len).map_err(|_| {
+            OneOcrError::InvalidModelFormat(format!("Block length {len} is too large"))
+        })
+    }
+}
+
+/// Decrypts a block and strips its magic number and payload length.
+fn decrypt_block(ciphertext: &[u8], key: &[u8; 32], offset: u64) -> Result<Vec<u8>, OneOcrError> {
+    let mut plaintext = ciphertext.to_vec();
+    Decryptor::<aes::Aes256>::new(key.into(), ONE_MODEL_IV.into()).decrypt(&mut plaintext);
+
+    if plaintext.len() < 16 || plaintext[..8] != ONE_MODEL_MAGIC {
+        return Err(OneOcrError::InvalidModelKey(format!(
+            "Block at offset {offset} did not decrypt to a valid block"
+        )));
+    }
+    let payload_len = u64::from_le_bytes(plaintext[8..16].try_into().expect("8 bytes"));
+    if payload_len > (plaintext.len() - 16) as u64 {
+        return Err(OneOcrError::InvalidModelFormat(format!(
+            "Block at offset {offset} declares {payload_len} bytes but holds {}",
+            plaintext.len() - 16
+        )));
+    }
+    plaintext.truncate(16 + payload_len as usize);
+    plaintext.drain(..16);
+    Ok(plaintext)
+}
+
+/// Guesses the kind of an entry from its content.
+fn classify(data: &[u8], is_first_graph: bool) -> ModelEntryKind {
+    if is_onnx_model(data) {
+        if is_first_graph {
+            ModelEntryKind::Detector
+        } else {
+            ModelEntryKind::Recognizer
+        }
+    } else if is_character_map(data) {
+        ModelEntryKind::CharacterMap
+    } else {
+        ModelEntryKind::Config
+    }
+}
+
+/// ONNX models are `ModelProto` messages, which start with the `ir_version` field (tag `0x08`)
+/// and reference the `ai.onnx` domain or name their producer.
+fn is_onnx_model(data: &[u8]) -> bool {
+    let head = &data[..data.len().min(4096)];
+    data.first() == Some(&0x08)
+        && [&b"ai.onnx"[..], b"onnx", b"pytorch"]
+            .iter()
+            .any(|needle| head.windows(needle.len()).any(|window| window == *needle))
+}
+
+/// Character maps are UTF-8 text with one short entry per line.
+fn is_character_map(data: &[u8]) -> bool {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return false;
+    };
+    let mut lines = text.lines().filter(|line| !line.is_empty()).peekable();
+    lines.peek().is_some() && lines.all(|line| line.chars().count() <= 16)
+}
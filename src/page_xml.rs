@@ -0,0 +1,152 @@
+use crate::bounding_box::{BoundingBox, Point};
+use crate::ocr_result::OcrResult;
+use crate::xml::{date_time, escape};
+use std::fmt::Write;
+use std::time::SystemTime;
+
+/// Metadata written to a PAGE XML document, see [`OcrResult::to_page_xml_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PageXmlOptions {
+    /// The file name of the recognized image, written as the page's `imageFilename`.
+    /// Default is empty.
+    pub image_file_name: String,
+    /// The creation time written to the metadata. Default is the current time.
+    pub created: SystemTime,
+}
+
+impl Default for PageXmlOptions {
+    fn default() -> Self {
+        PageXmlOptions {
+            image_file_name: String::new(),
+            created: SystemTime::now(),
+        }
+    }
+}
+
+impl OcrResult {
+    /// Renders the result as a [PAGE XML](https://github.com/PRImA-Research-Lab/PAGE-XML)
+    /// document (2019-07-15 schema) with the default [`PageXmlOptions`].
+    pub fn to_page_xml(&self) -> String {
+        self.to_page_xml_with(&PageXmlOptions::default())
+    }
+
+    /// Renders the result as a PAGE XML document (2019-07-15 schema).
+    ///
+    /// Unlike hOCR and ALTO, coordinates keep the exact geometry: every `TextLine` and `Word`
+    /// has the four corners of its bounding box as `Coords` points, rounded to whole pixels.
+    /// Each of the [`blocks`](Self::blocks), grouped on the fly if the result has none, is a
+    /// `TextRegion` with the corners of the block as `Coords`. A `TextRegion` holds lines rather
+    /// than paragraphs, so the lines of a block's paragraphs follow each other in its region.
+    /// The image angle is written as the page's `orientation`. Each word has its text and
+    /// confidence as a `TextEquiv`; each line has its text, with the mean confidence of its
+    /// words if known.
+    pub fn to_page_xml_with(&self, options: &PageXmlOptions) -> String {
+        let (width, height) = self.page_size();
+        let created = date_time(options.created);
+        let mut page = String::new();
+        // Writing to a `String` never fails.
+        let _ = write!(
+            page,
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<PcGts xmlns=\"http://schema.primaresearch.org/PAGE/gts/pagecontent/2019-07-15\" ",
+                "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+                "xsi:schemaLocation=\"http://schema.primaresearch.org/PAGE/gts/pagecontent/2019-07-15 ",
+                "http://schema.primaresearch.org/PAGE/gts/pagecontent/2019-07-15/pagecontent.xsd\">\n",
+                "  <Metadata>\n",
+                "    <Creator>oneocr-rs {}</Creator>\n",
+                "    <Created>{created}</Created>\n",
+                "    <LastChange>{created}</LastChange>\n",
+                "  </Metadata>\n",
+                "  <Page imageFilename=\"{}\" imageWidth=\"{}\" imageHeight=\"{}\" orientation=\"{}\">\n",
+            ),
+            env!("CARGO_PKG_VERSION"),
+            escape(&options.image_file_name),
+            width,
+            height,
+            orientation(self.image_angle),
+            created = created,
+        );
+
+        for (b, block) in self.current_blocks().iter().enumerate() {
+            let region_id = format!("r{}", b + 1);
+            let _ = writeln!(
+                page,
+                "    <TextRegion id=\"{region_id}\">\n      <Coords points=\"{}\"/>",
+                points(&block.bounding_box)
+            );
+            for &l in block
+                .paragraphs
+                .iter()
+                .flat_map(|paragraph| &paragraph.lines)
+            {
+                let line = &self.lines[l];
+                let line_id = format!("{region_id}_l{}", l + 1);
+                let _ = writeln!(
+                    page,
+                    "      <TextLine id=\"{line_id}\">\n        <Coords points=\"{}\"/>",
+                    points(&line.bounding_box)
+                );
+                let words = line.words.as_deref().unwrap_or_default();
+                for (w, word) in words.iter().enumerate() {
+                    let _ = writeln!(
+                        page,
+                        concat!(
+                            "        <Word id=\"{}_w{}\">\n",
+                            "          <Coords points=\"{}\"/>\n",
+                            "          <TextEquiv conf=\"{:.3}\">\n",
+                            "            <Unicode>{}</Unicode>\n",
+                            "          </TextEquiv>\n",
+                            "        </Word>",
+                        ),
+                        line_id,
+                        w + 1,
+                        points(&word.bounding_box),
+                        word.confidence.clamp(0.0, 1.0),
+                        escape(&word.text)
+                    );
+                }
+                page.push_str("        <TextEquiv");
+                if !words.is_empty() {
+                    let confidence =
+                        words.iter().map(|word| word.confidence).sum::<f32>() / words.len() as f32;
+                    let _ = write!(page, " conf=\"{:.3}\"", confidence.clamp(0.0, 1.0));
+                }
+                let _ = writeln!(
+                    page,
+                    ">\n          <Unicode>{}</Unicode>\n        </TextEquiv>\n      </TextLine>",
+                    escape(&line.text)
+                );
+            }
+            page.push_str("    </TextRegion>\n");
+        }
+        page.push_str("  </Page>\n</PcGts>\n");
+        page
+    }
+}
+
+/// Formats the corners of a box as PAGE `points`, clockwise from the top left, rounded to
+/// non-negative whole pixels.
+fn points(bounding_box: &BoundingBox) -> String {
+    let point = |p: Point| format!("{},{}", p.x.round() as u32, p.y.round() as u32);
+    format!(
+        "{} {} {} {}",
+        point(bounding_box.top_left),
+        point(bounding_box.top_right),
+        point(bounding_box.bottom_right),
+        point(bounding_box.bottom_left)
+    )
+}
+
+/// Normalizes an angle in degrees to PAGE's `orientation` range, -180 (exclusive) to 180.
+fn orientation(angle: f32) -> f32 {
+    let angle = angle % 360.0;
+    if angle > 180.0 {
+        angle - 360.0
+    } else if angle <= -180.0 {
+        angle + 360.0
+    } else {
+        angle
+    }
+}
@@ -0,0 +1,271 @@
+use crate::geometry::{Affine, Rect, median};
+use crate::ocr_line::OcrLine;
+use crate::ocr_result::OcrResult;
+use serde::{Deserialize, Serialize};
+
+/// The direction in which the text of a page is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDirection {
+    /// Latin, Cyrillic, CJK and most other scripts.
+    #[default]
+    LeftToRight,
+    /// Hebrew, Arabic and other right-to-left scripts.
+    RightToLeft,
+}
+
+/// Options for [`OcrResult::reading_order_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct ReadingOrderOptions {
+    /// The direction of the text, or `None` to detect it from the recognized text,
+    /// see [`OcrResult::text_direction`].
+    pub direction: Option<TextDirection>,
+    /// The narrowest vertical strip of whitespace that separates two columns,
+    /// in median line heights.
+    pub min_column_gap: f32,
+    /// The narrowest horizontal strip of whitespace that separates two sections of the page,
+    /// such as a title spanning the columns below it, in median line heights.
+    ///
+    /// It must be wider than the gap between two lines of a paragraph, or columns whose
+    /// lines share a baseline are cut into rows and read across.
+    pub min_section_gap: f32,
+}
+
+impl Default for ReadingOrderOptions {
+    fn default() -> Self {
+        Self {
+            direction: None,
+            min_column_gap: 1.0,
+            min_section_gap: 0.8,
+        }
+    }
+}
+
+/// A line to order, by index, with its deskewed bounds.
+#[derive(Clone, Copy)]
+struct Item {
+    index: usize,
+    bounds: Rect,
+}
+
+impl Item {
+    fn center(&self) -> f32 {
+        self.bounds.y + self.bounds.height / 2.0
+    }
+}
+
+/// The thresholds of one ordering, in pixels.
+struct Cutter {
+    direction: TextDirection,
+    min_column_gap: f32,
+    min_section_gap: f32,
+}
+
+impl OcrResult {
+    /// Returns the direction of the text: right to left if most of its letters are in a
+    /// right-to-left script.
+    pub fn text_direction(&self) -> TextDirection {
+        let (mut rtl, mut ltr) = (0usize, 0usize);
+        for c in self.lines.iter().flat_map(|line| line.text.chars()) {
+            if is_rtl(c) {
+                rtl += 1;
+            } else if c.is_alphabetic() {
+                ltr += 1;
+            }
+        }
+        if rtl > ltr {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+
+    /// Returns the indices of [`lines`](Self::lines) in reading order, with the default
+    /// [`ReadingOrderOptions`].
+    pub fn reading_order(&self) -> Vec<usize> {
+        self.reading_order_with(&ReadingOrderOptions::default())
+    }
+
+    /// Returns the indices of [`lines`](Self::lines) in reading order.
+    ///
+    /// The page is split recursively along strips of whitespace (an XY-cut): first into
+    /// columns, read left to right or right to left depending on the text direction, and
+    /// where no column gap runs across the whole region, into sections read top to bottom.
+    /// Lines of a region that cannot be split further are read row by row. The boxes are
+    /// first deskewed by the median angle of the lines, so slightly rotated scans keep their
+    /// columns.
+    ///
+    /// Cells of a table are separated by whitespace too, so tables are read column by column.
+    pub fn reading_order_with(&self, options: &ReadingOrderOptions) -> Vec<usize> {
+        let items: Vec<Item> = self
+            .deskewed_bounds()
+            .into_iter()
+            .enumerate()
+            .map(|(index, bounds)| Item { index, bounds })
+            .collect();
+        let line_height = median(
+            items
+                .iter()
+                .map(|item| item.bounds.height)
+                .filter(|height| *height > 0.0),
+        )
+        .unwrap_or(0.0)
+        .max(1.0);
+
+        let cutter = Cutter {
+            direction: options.direction.unwrap_or_else(|| self.text_direction()),
+            min_column_gap: options.min_column_gap * line_height,
+            min_section_gap: options.min_section_gap * line_height,
+        };
+        let mut order = Vec::with_capacity(items.len());
+        cutter.cut(items, &mut order);
+        order
+    }
+
+    /// Returns the lines in reading order, see [`reading_order`](Self::reading_order).
+    pub fn lines_in_reading_order(&self) -> Vec<&OcrLine> {
+        self.reading_order()
+            .into_iter()
+            .map(|index| &self.lines[index])
+            .collect()
+    }
+
+    /// Sorts [`lines`](Self::lines) into reading order, e.g. before exporting the result.
+    /// The line indices of [`blocks`](Self::blocks) are updated to match.
+    pub fn sort_by_reading_order(&mut self) {
+        let order = self.reading_order();
+        let mut positions = vec![0; order.len()];
+        for (position, index) in order.iter().enumerate() {
+            positions[*index] = position;
+        }
+        for index in self
+            .blocks
+            .iter_mut()
+            .flat_map(|block| &mut block.paragraphs)
+            .flat_map(|paragraph| &mut paragraph.lines)
+        {
+            *index = positions.get(*index).copied().unwrap_or(*index);
+        }
+
+        let mut lines: Vec<Option<OcrLine>> = self.lines.drain(..).map(Some).collect();
+        self.lines = order
+            .into_iter()
+            .filter_map(|index| lines[index].take())
+            .collect();
+    }
+
+    /// Returns the rotation undoing the skew of the page, measured as the median angle of
+    /// the lines.
+    pub(crate) fn deskew(&self) -> Affine {
+        Affine::rotate(
+            -median(self.lines.iter().map(|line| {
+                let angle = line.bounding_box.angle();
+                // Vertical and upside-down lines don't tell the skew of the page.
+                if angle.abs() < 45.0 { angle } else { 0.0 }
+            }))
+            .unwrap_or(0.0),
+        )
+    }
+
+    /// Returns the axis-aligned bounds of the lines after undoing the skew of the page.
+    pub(crate) fn deskewed_bounds(&self) -> Vec<Rect> {
+        let deskew = self.deskew();
+        self.lines
+            .iter()
+            .map(|line| line.bounding_box.transform(&deskew).bounds())
+            .collect()
+    }
+
+    /// Returns the text of the page, one line per line of text, in reading order.
+    pub fn text(&self) -> String {
+        self.lines_in_reading_order()
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Cutter {
+    fn cut(&self, items: Vec<Item>, order: &mut Vec<usize>) {
+        if items.len() <= 1 {
+            order.extend(items.iter().map(|item| item.index));
+            return;
+        }
+
+        let mut columns = split(items, self.min_column_gap, |r| (r.x, r.right()));
+        if columns.len() > 1 {
+            if self.direction == TextDirection::RightToLeft {
+                columns.reverse();
+            }
+            for column in columns {
+                self.cut(column, order);
+            }
+            return;
+        }
+
+        let items = columns.pop().unwrap_or_default();
+        let sections = split(items, self.min_section_gap, |r| (r.y, r.bottom()));
+        if sections.len() > 1 {
+            for section in sections {
+                self.cut(section, order);
+            }
+            return;
+        }
+
+        for mut row in rows(sections.into_iter().flatten().collect()) {
+            match self.direction {
+                TextDirection::LeftToRight => row.sort_by(|a, b| a.bounds.x.total_cmp(&b.bounds.x)),
+                TextDirection::RightToLeft => {
+                    row.sort_by(|a, b| b.bounds.right().total_cmp(&a.bounds.right()))
+                }
+            }
+            order.extend(row.iter().map(|item| item.index));
+        }
+    }
+}
+
+/// Splits items into groups separated by gaps of at least `min_gap` along the axis given by
+/// `extent`, in order along that axis.
+fn split(
+    mut items: Vec<Item>,
+    min_gap: f32,
+    extent: impl Fn(&Rect) -> (f32, f32),
+) -> Vec<Vec<Item>> {
+    items.sort_by(|a, b| extent(&a.bounds).0.total_cmp(&extent(&b.bounds).0));
+    let mut groups: Vec<Vec<Item>> = Vec::new();
+    let mut end = f32::NEG_INFINITY;
+    for item in items {
+        let (start, item_end) = extent(&item.bounds);
+        let gap = start - end;
+        match groups.last_mut() {
+            Some(group) if gap <= 0.0 || gap < min_gap => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+        end = end.max(item_end);
+    }
+    groups
+}
+
+/// Groups items whose vertical centers fall within the same line of text, top to bottom.
+fn rows(mut items: Vec<Item>) -> Vec<Vec<Item>> {
+    items.sort_by(|a, b| a.center().total_cmp(&b.center()));
+    let mut rows: Vec<Vec<Item>> = Vec::new();
+    for item in items {
+        match rows.last_mut() {
+            Some(row) if item.center() <= row[0].bounds.bottom() => row.push(item),
+            _ => rows.push(vec![item]),
+        }
+    }
+    rows
+}
+
+/// Whether `c` is a letter of a right-to-left script.
+fn is_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{0590}'..='\u{08FF}'     // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic
+        | '\u{FB1D}'..='\u{FDFF}'   // Hebrew and Arabic presentation forms A
+        | '\u{FE70}'..='\u{FEFF}'   // Arabic presentation forms B
+    ) && c.is_alphabetic()
+}
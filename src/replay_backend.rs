@@ -0,0 +1,365 @@
+use crate::bounding_box::BoundingBox;
+use crate::errors::OneOcrError;
+use crate::hex_string;
+use crate::ocr_backend::{Handle, OcrBackend, checked_count};
+use crate::result_store::{ResultStore, StoredLine, StoredResult, StoredWord};
+use image::{ImageBuffer, Rgba};
+use sha2::{Digest, Sha256};
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Numbers the temporary files of fixtures being saved, so concurrent saves never share one.
+static SAVE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// The `ReplayBackend` struct is an [`OcrBackend`] that serves recorded OCR results.
+///
+/// Results are looked up in a fixture directory by the SHA-256 hash of the input image
+/// (see [`image_hash`]) and the process options that change the result, so the same image
+/// run with the same options always yields exactly the recorded lines, words, bounding boxes,
+/// confidences, styles and image angle, without `oneocr.dll`. Fixtures are named
+/// `{image_hash}_{max_recognition_line_count}_{width}x{height}.json` after the image hash, the
+/// max recognition line count and the resize resolution.
+///
+/// Fixtures are written by a backend created with [`ReplayBackend::record`], which runs
+/// another backend (usually [`DllBackend`](crate::DllBackend)) and stores every result it
+/// produces before serving it. A fixture is written to a temporary file first and then
+/// renamed, so an interrupted recording never leaves a truncated fixture behind.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::{OcrEngine, OcrOptions, ReplayBackend};
+/// use std::path::Path;
+///
+/// let engine = OcrEngine::new_with_backend(
+///     ReplayBackend::new("tests/fixtures"),
+///     OcrOptions::default(),
+/// )
+/// .unwrap();
+/// let result = engine.run(Path::new("image.jpg").into()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ReplayBackend {
+    fixture_dir: PathBuf,
+    recorder: Option<Box<dyn OcrBackend>>,
+    store: Mutex<ResultStore>,
+}
+
+impl ReplayBackend {
+    /// Creates a backend that replays the fixtures found in `fixture_dir`.
+    pub fn new(fixture_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            fixture_dir: fixture_dir.into(),
+            recorder: None,
+            store: Mutex::new(ResultStore::default()),
+        }
+    }
+
+    /// Creates a backend that runs `inner` and records every result into `fixture_dir`.
+    /// Word-level details are always recorded, regardless of the engine options.
+    pub fn record(inner: impl OcrBackend + 'static, fixture_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            fixture_dir: fixture_dir.into(),
+            recorder: Some(Box::new(inner)),
+            store: Mutex::new(ResultStore::default()),
+        }
+    }
+
+    /// Returns the fixture directory of this backend.
+    pub fn fixture_dir(&self) -> &Path {
+        &self.fixture_dir
+    }
+
+    /// Returns the path of the fixture recorded for an image hash and process options.
+    fn fixture_path(&self, hash: &str, process_options: Handle) -> Result<PathBuf, OneOcrError> {
+        let line_count = self.get_max_recognition_line_count(process_options)?;
+        let (width, height) = self.get_resize_resolution(process_options)?;
+        Ok(self
+            .fixture_dir
+            .join(format!("{hash}_{line_count}_{width}x{height}.json")))
+    }
+
+    fn store(&self) -> MutexGuard<'_, ResultStore> {
+        // The table stays consistent even if another thread panicked while holding the lock.
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn load_fixture(&self, hash: &str, path: &Path) -> Result<StoredResult, OneOcrError> {
+        if !path.exists() {
+            return Err(OneOcrError::FixtureNotFound {
+                hash: hash.to_string(),
+                path: path.to_path_buf(),
+            });
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            OneOcrError::FixtureError(format!("Failed to read {}: {e}", path.display()))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            OneOcrError::FixtureError(format!("Failed to parse {}: {e}", path.display()))
+        })
+    }
+
+    fn save_fixture(&self, path: &Path, fixture: &StoredResult) -> Result<(), OneOcrError> {
+        std::fs::create_dir_all(&self.fixture_dir).map_err(|e| {
+            OneOcrError::FixtureError(format!(
+                "Failed to create {}: {e}",
+                self.fixture_dir.display()
+            ))
+        })?;
+        let content = serde_json::to_string_pretty(fixture)
+            .map_err(|e| OneOcrError::FixtureError(format!("Failed to serialize fixture: {e}")))?;
+        let temporary = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            SAVE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&temporary, content)
+            .and_then(|()| std::fs::rename(&temporary, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temporary);
+                OneOcrError::FixtureError(format!("Failed to write {}: {e}", path.display()))
+            })
+    }
+}
+
+/// Runs `inner` on an image and reads back the complete result as a fixture.
+fn capture(
+    inner: &dyn OcrBackend,
+    pipeline: Handle,
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    process_options: Handle,
+) -> Result<StoredResult, OneOcrError> {
+    let result = inner.run_pipeline(pipeline, image, process_options)?;
+    let fixture = capture_result(inner, result);
+    inner.release_result(result);
+    fixture
+}
+
+fn capture_result(inner: &dyn OcrBackend, result: Handle) -> Result<StoredResult, OneOcrError> {
+    let line_count = inner.get_line_count(result)?;
+    let mut lines = Vec::with_capacity(checked_count(line_count, "line")?);
+    for i in 0..line_count {
+        let line = inner.get_line(result, i)?;
+        let (style, style_confidence) = inner.get_line_style(line)?;
+        let word_count = inner.get_line_word_count(line)?;
+        let mut words = Vec::with_capacity(checked_count(word_count, "word")?);
+        for j in 0..word_count {
+            let word = inner.get_word(line, j)?;
+            words.push(StoredWord {
+                text: inner.get_word_content(word)?,
+                confidence: inner.get_word_confidence(word)?,
+                bounding_box: inner.get_word_bounding_box(word)?,
+            });
+        }
+        lines.push(StoredLine {
+            text: inner.get_line_content(line)?,
+            bounding_box: inner.get_line_bounding_box(line)?,
+            style,
+            style_confidence,
+            words,
+        });
+    }
+
+    Ok(StoredResult {
+        image_angle: inner.get_image_angle(result)?,
+        lines,
+    })
+}
+
+/// Computes the hash used to name the fixture of an image.
+///
+/// This is the lowercase hex SHA-256 of the width and height (little-endian `u32`)
+/// followed by the raw RGBA pixels.
+pub fn image_hash(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(image.width().to_le_bytes());
+    hasher.update(image.height().to_le_bytes());
+    hasher.update(image.as_raw());
+    hex_string(&hasher.finalize())
+}
+
+impl OcrBackend for ReplayBackend {
+    fn requires_model_file(&self) -> bool {
+        self.recorder
+            .as_ref()
+            .is_some_and(|inner| inner.requires_model_file())
+    }
+
+    fn supports_concurrent_runs(&self) -> bool {
+        self.recorder
+            .as_ref()
+            .is_none_or(|inner| inner.supports_concurrent_runs())
+    }
+
+    fn create_init_options(&self) -> Result<Handle, OneOcrError> {
+        match &self.recorder {
+            Some(inner) => inner.create_init_options(),
+            None => Ok(self.store().create_init_options()),
+        }
+    }
+
+    fn set_use_model_delay_load(
+        &self,
+        init_options: Handle,
+        delay_load: bool,
+    ) -> Result<(), OneOcrError> {
+        match &self.recorder {
+            Some(inner) => inner.set_use_model_delay_load(init_options, delay_load),
+            None => self.store().check_init_options(init_options),
+        }
+    }
+
+    fn create_pipeline(
+        &self,
+        model_path: &CStr,
+        key: &CStr,
+        init_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        match &self.recorder {
+            Some(inner) => inner.create_pipeline(model_path, key, init_options),
+            None => Ok(self.store().create_pipeline()),
+        }
+    }
+
+    fn create_process_options(&self) -> Result<Handle, OneOcrError> {
+        match &self.recorder {
+            Some(inner) => inner.create_process_options(),
+            None => Ok(self.store().create_process_options()),
+        }
+    }
+
+    fn get_max_recognition_line_count(&self, process_options: Handle) -> Result<i32, OneOcrError> {
+        if let Some(inner) = &self.recorder {
+            return inner.get_max_recognition_line_count(process_options);
+        }
+        let mut store = self.store();
+        let (count, _) = store.process_options(process_options)?;
+        Ok(*count)
+    }
+
+    fn set_max_recognition_line_count(
+        &self,
+        process_options: Handle,
+        count: i32,
+    ) -> Result<(), OneOcrError> {
+        if let Some(inner) = &self.recorder {
+            return inner.set_max_recognition_line_count(process_options, count);
+        }
+        *self.store().process_options(process_options)?.0 = count;
+        Ok(())
+    }
+
+    fn get_resize_resolution(&self, process_options: Handle) -> Result<(i64, i64), OneOcrError> {
+        if let Some(inner) = &self.recorder {
+            return inner.get_resize_resolution(process_options);
+        }
+        let mut store = self.store();
+        let (_, (width, height)) = store.process_options(process_options)?;
+        Ok((*width as i64, *height as i64))
+    }
+
+    fn set_resize_resolution(
+        &self,
+        process_options: Handle,
+        width: i32,
+        height: i32,
+    ) -> Result<(), OneOcrError> {
+        if let Some(inner) = &self.recorder {
+            return inner.set_resize_resolution(process_options, width, height);
+        }
+        *self.store().process_options(process_options)?.1 = (width, height);
+        Ok(())
+    }
+
+    fn run_pipeline(
+        &self,
+        pipeline: Handle,
+        image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        process_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        let hash = image_hash(image);
+        let path = self.fixture_path(&hash, process_options)?;
+        let fixture = match &self.recorder {
+            Some(inner) => {
+                let fixture = capture(inner.as_ref(), pipeline, image, process_options)?;
+                self.save_fixture(&path, &fixture)?;
+                fixture
+            }
+            None => self.load_fixture(&hash, &path)?,
+        };
+        Ok(self.store().insert_result(fixture))
+    }
+
+    fn get_image_angle(&self, result: Handle) -> Result<f32, OneOcrError> {
+        Ok(self.store().result(result)?.image_angle)
+    }
+
+    fn get_line_count(&self, result: Handle) -> Result<i64, OneOcrError> {
+        Ok(self.store().result(result)?.lines.len() as i64)
+    }
+
+    fn get_line(&self, result: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        self.store().get_line(result, index)
+    }
+
+    fn get_line_content(&self, line: Handle) -> Result<String, OneOcrError> {
+        Ok(self.store().line(line)?.text.clone())
+    }
+
+    fn get_line_bounding_box(&self, line: Handle) -> Result<BoundingBox, OneOcrError> {
+        Ok(self.store().line(line)?.bounding_box)
+    }
+
+    fn get_line_style(&self, line: Handle) -> Result<(i32, f32), OneOcrError> {
+        let store = self.store();
+        let line = store.line(line)?;
+        Ok((line.style, line.style_confidence))
+    }
+
+    fn get_line_word_count(&self, line: Handle) -> Result<i64, OneOcrError> {
+        Ok(self.store().line(line)?.words.len() as i64)
+    }
+
+    fn get_word(&self, line: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        self.store().get_word(line, index)
+    }
+
+    fn get_word_content(&self, word: Handle) -> Result<String, OneOcrError> {
+        Ok(self.store().word(word)?.text.clone())
+    }
+
+    fn get_word_bounding_box(&self, word: Handle) -> Result<BoundingBox, OneOcrError> {
+        Ok(self.store().word(word)?.bounding_box)
+    }
+
+    fn get_word_confidence(&self, word: Handle) -> Result<f32, OneOcrError> {
+        Ok(self.store().word(word)?.confidence)
+    }
+
+    fn release_result(&self, result: Handle) {
+        self.store().release_result(result);
+    }
+
+    fn release_init_options(&self, init_options: Handle) {
+        match &self.recorder {
+            Some(inner) => inner.release_init_options(init_options),
+            None => self.store().release(init_options),
+        }
+    }
+
+    fn release_pipeline(&self, pipeline: Handle) {
+        match &self.recorder {
+            Some(inner) => inner.release_pipeline(pipeline),
+            None => self.store().release(pipeline),
+        }
+    }
+
+    fn release_process_options(&self, process_options: Handle) {
+        match &self.recorder {
+            Some(inner) => inner.release_process_options(process_options),
+            None => self.store().release(process_options),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::bounding_box::BoundingBox;
+use crate::errors::OneOcrError;
+use crate::ocr_backend::Handle;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A complete OCR result held in memory, e.g. a recorded fixture or a result computed on the CPU.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct StoredResult {
+    pub image_angle: f32,
+    pub lines: Vec<StoredLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct StoredLine {
+    pub text: String,
+    pub bounding_box: BoundingBox,
+    /// 0 = Handwritten, 1 = Printed
+    pub style: i32,
+    pub style_confidence: f32,
+    pub words: Vec<StoredWord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct StoredWord {
+    pub text: String,
+    pub confidence: f32,
+    pub bounding_box: BoundingBox,
+}
+
+/// An object handed out through a [`Handle`].
+#[derive(Debug)]
+enum Object {
+    InitOptions,
+    Pipeline,
+    ProcessOptions {
+        max_recognition_line_count: i32,
+        resize_resolution: (i32, i32),
+    },
+    Result(Arc<StoredResult>),
+    Line {
+        result: Handle,
+        stored: Arc<StoredResult>,
+        line: usize,
+    },
+    Word {
+        result: Handle,
+        stored: Arc<StoredResult>,
+        line: usize,
+        word: usize,
+    },
+}
+
+/// The handle table of backends serving [`StoredResult`]s instead of calling `oneocr.dll`.
+///
+/// It implements the object model of the C API: options, pipelines, results, and the
+/// line and word handles owned by a result.
+#[derive(Debug, Default)]
+pub(crate) struct ResultStore {
+    next_handle: usize,
+    objects: HashMap<Handle, Object>,
+}
+
+impl ResultStore {
+    fn insert(&mut self, object: Object) -> Handle {
+        self.next_handle += 1;
+        let handle = Handle::from_raw(self.next_handle);
+        self.objects.insert(handle, object);
+        handle
+    }
+
+    fn get(&self, handle: Handle) -> Result<&Object, OneOcrError> {
+        self.objects
+            .get(&handle)
+            .ok_or_else(|| invalid_handle(handle))
+    }
+
+    pub fn create_init_options(&mut self) -> Handle {
+        self.insert(Object::InitOptions)
+    }
+
+    /// Returns an error unless `handle` is an init options object.
+    pub fn check_init_options(&self, handle: Handle) -> Result<(), OneOcrError> {
+        match self.get(handle)? {
+            Object::InitOptions => Ok(()),
+            _ => Err(invalid_handle(handle)),
+        }
+    }
+
+    pub fn create_pipeline(&mut self) -> Handle {
+        self.insert(Object::Pipeline)
+    }
+
+    /// Creates a process options object with the defaults of `oneocr.dll`.
+    pub fn create_process_options(&mut self) -> Handle {
+        self.insert(Object::ProcessOptions {
+            max_recognition_line_count: 100,
+            resize_resolution: (1152, 768),
+        })
+    }
+
+    /// Returns the max recognition line count and resize resolution of a process options object.
+    pub fn process_options(
+        &mut self,
+        handle: Handle,
+    ) -> Result<(&mut i32, &mut (i32, i32)), OneOcrError> {
+        match self.objects.get_mut(&handle) {
+            Some(Object::ProcessOptions {
+                max_recognition_line_count,
+                resize_resolution,
+            }) => Ok((max_recognition_line_count, resize_resolution)),
+            _ => Err(invalid_handle(handle)),
+        }
+    }
+
+    pub fn insert_result(&mut self, result: StoredResult) -> Handle {
+        self.insert(Object::Result(Arc::new(result)))
+    }
+
+    pub fn result(&self, result: Handle) -> Result<Arc<StoredResult>, OneOcrError> {
+        match self.get(result)? {
+            Object::Result(stored) => Ok(stored.clone()),
+            _ => Err(invalid_handle(result)),
+        }
+    }
+
+    /// Hands out a handle to the line at `index` of a result.
+    pub fn get_line(&mut self, result: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        let stored = self.result(result)?;
+        if index < 0 || index as usize >= stored.lines.len() {
+            return Err(OneOcrError::OcrApiError {
+                result: -1,
+                message: format!("Line index {index} out of range"),
+            });
+        }
+        Ok(self.insert(Object::Line {
+            result,
+            stored,
+            line: index as usize,
+        }))
+    }
+
+    pub fn line(&self, line: Handle) -> Result<&StoredLine, OneOcrError> {
+        match self.get(line)? {
+            Object::Line { stored, line, .. } => Ok(&stored.lines[*line]),
+            _ => Err(invalid_handle(line)),
+        }
+    }
+
+    /// Hands out a handle to the word at `index` of a line.
+    pub fn get_word(&mut self, line: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        let (result, stored, line) = match self.get(line)? {
+            Object::Line {
+                result,
+                stored,
+                line: index,
+            } => (*result, stored.clone(), *index),
+            _ => return Err(invalid_handle(line)),
+        };
+        if index < 0 || index as usize >= stored.lines[line].words.len() {
+            return Err(OneOcrError::OcrApiError {
+                result: -1,
+                message: format!("Word index {index} out of range"),
+            });
+        }
+        Ok(self.insert(Object::Word {
+            result,
+            stored,
+            line,
+            word: index as usize,
+        }))
+    }
+
+    pub fn word(&self, word: Handle) -> Result<&StoredWord, OneOcrError> {
+        match self.get(word)? {
+            Object::Word {
+                stored,
+                line,
+                word: index,
+                ..
+            } => Ok(&stored.lines[*line].words[*index]),
+            _ => Err(invalid_handle(word)),
+        }
+    }
+
+    /// Releases a result together with the line and word handles it owns.
+    pub fn release_result(&mut self, result: Handle) {
+        self.objects.retain(|handle, object| match object {
+            Object::Line { result: owner, .. } | Object::Word { result: owner, .. } => {
+                *owner != result
+            }
+            _ => *handle != result,
+        });
+    }
+
+    /// Releases an options object or a pipeline.
+    pub fn release(&mut self, handle: Handle) {
+        self.objects.remove(&handle);
+    }
+}
+
+fn invalid_handle(handle: Handle) -> OneOcrError {
+    OneOcrError::OcrApiError {
+        result: -1,
+        message: format!("Invalid handle: {:#x}", handle.into_raw()),
+    }
+}
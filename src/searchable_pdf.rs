@@ -0,0 +1,455 @@
+use crate::bounding_box::BoundingBox;
+use crate::errors::OneOcrError;
+use crate::geometry::Affine;
+use crate::glyphless_font::{self, glyphless_font};
+use crate::image_input::ImageInput;
+use crate::ocr_result::OcrResult;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, TextRenderingMode};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::Cursor;
+use std::path::Path;
+
+const FONT_NAME: Name<'static> = Name(b"GlyphLessFont");
+const FONT_RESOURCE: Name<'static> = Name(b"F1");
+const IMAGE_RESOURCE: Name<'static> = Name(b"Im1");
+/// The advance of every glyph of the text layer's font, in thousandths of the font size.
+const GLYPH_WIDTH: f32 =
+    glyphless_font::ADVANCE as f32 * 1000.0 / glyphless_font::UNITS_PER_EM as f32;
+
+/// Options of a [`SearchablePdf`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct PdfOptions {
+    /// The resolution of the images, which sets the size of the pages. Default is 300 DPI.
+    pub dpi: f32,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions { dpi: 300.0 }
+    }
+}
+
+/// A page waiting to be written.
+#[derive(Debug)]
+struct PdfPage {
+    width: f32,
+    height: f32,
+    image: PageImage,
+    content: Vec<u8>,
+}
+
+/// The image of a page, encoded for its image XObject.
+#[derive(Debug)]
+struct PageImage {
+    data: Vec<u8>,
+    filter: Filter,
+    width: u32,
+    height: u32,
+    gray: bool,
+}
+
+impl PageImage {
+    /// Loads an image, keeping JPEG files as they are and compressing other images as
+    /// gray or RGB pixels.
+    fn load(image: ImageInput) -> Result<Self, OneOcrError> {
+        let image = match image {
+            ImageInput::FilePath(path) => {
+                let data = std::fs::read(&path).map_err(image::ImageError::IoError)?;
+                if let Some((width, height, gray)) = jpeg_info(&data) {
+                    return Ok(Self {
+                        data,
+                        filter: Filter::DctDecode,
+                        width,
+                        height,
+                        gray,
+                    });
+                }
+                let mut reader = ImageReader::new(Cursor::new(data));
+                if let Ok(format) = ImageFormat::from_path(&path) {
+                    reader.set_format(format);
+                }
+                reader.decode()?
+            }
+            ImageInput::Buffer(buffer) => DynamicImage::ImageRgba8(buffer),
+            ImageInput::Dynamic(image) => image,
+        };
+        let (width, height) = (image.width(), image.height());
+        let (pixels, gray) = match image {
+            DynamicImage::ImageLuma8(gray) => (gray.into_raw(), true),
+            image => (image.into_rgb8().into_raw(), false),
+        };
+        Ok(Self {
+            data: compress_to_vec_zlib(&pixels, 6),
+            filter: Filter::FlateDecode,
+            width,
+            height,
+            gray,
+        })
+    }
+}
+
+/// Reads the size of a JPEG image and whether it is gray from its frame header. Returns `None`
+/// unless the image is an 8-bit baseline or progressive JPEG with one or three components,
+/// which PDF viewers decode as gray or RGB.
+fn jpeg_info(data: &[u8]) -> Option<(u32, u32, bool)> {
+    let u16_at = |i: usize| Some(u16::from_be_bytes([*data.get(i)?, *data.get(i + 1)?]));
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut i = 2;
+    loop {
+        if *data.get(i)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(i + 1)?;
+        match marker {
+            // Fill bytes before a marker.
+            0xFF => i += 1,
+            // Baseline, extended and progressive frames with Huffman coding.
+            0xC0..=0xC2 => {
+                let precision = *data.get(i + 4)?;
+                let height = u16_at(i + 5)?;
+                let width = u16_at(i + 7)?;
+                let components = *data.get(i + 9)?;
+                return (precision == 8 && width > 0 && height > 0 && matches!(components, 1 | 3))
+                    .then_some((width.into(), height.into(), components == 1));
+            }
+            // Other frame types, or the image data or its end before any frame header.
+            0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF | 0xD9 | 0xDA => return None,
+            _ => i += 2 + usize::from(u16_at(i + 2)?),
+        }
+    }
+}
+
+/// The `SearchablePdf` struct writes scanned images as a searchable PDF.
+///
+/// Each page shows an image with invisible text from its [`OcrResult`] on top: every word, or
+/// every line for results without word-level details, is placed over its bounding box, scaled
+/// and rotated to match. Viewers show the image, while the text can be searched, selected and
+/// copied.
+///
+/// # Examples
+///
+/// ```no_run
+/// use oneocr_rs::{OcrEngine, PdfOptions, SearchablePdf};
+/// use std::path::Path;
+///
+/// let engine = OcrEngine::new().unwrap();
+/// let mut pdf = SearchablePdf::new(PdfOptions::default()).unwrap();
+/// for page in ["page1.png", "page2.png"] {
+///     let result = engine.run(Path::new(page).into()).unwrap();
+///     pdf.add_page(Path::new(page), &result).unwrap();
+/// }
+/// pdf.save("scan.pdf").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SearchablePdf {
+    options: PdfOptions,
+    pages: Vec<PdfPage>,
+    /// The character shown by each glyph of the text layer, by glyph ID minus one.
+    chars: Vec<char>,
+    glyphs: HashMap<char, u16>,
+}
+
+impl SearchablePdf {
+    /// Creates an empty document.
+    pub fn new(options: PdfOptions) -> Result<Self, OneOcrError> {
+        if !(options.dpi > 0.0 && options.dpi.is_finite()) {
+            return Err(OneOcrError::InvalidDpi(options.dpi));
+        }
+        Ok(Self {
+            options,
+            pages: Vec::new(),
+            chars: Vec::new(),
+            glyphs: HashMap::new(),
+        })
+    }
+
+    /// Returns the number of pages added so far.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Adds a page showing `image` with the text of `result`, which was recognized on it.
+    ///
+    /// If the result knows the size of its image, its coordinates are scaled to `image`,
+    /// so a page can show a higher resolution version of the recognized image. Otherwise they
+    /// are taken as pixels of `image`.
+    ///
+    /// JPEG files passed by path are embedded as they are. Other images, including decoded
+    /// JPEGs passed as buffers, are losslessly compressed as gray or RGB pixels, dropping any
+    /// transparency, which can make pages of photos much larger than their files. Returns
+    /// [`OneOcrError::EmptyImage`] for images without pixels.
+    pub fn add_page(
+        &mut self,
+        image: impl Into<ImageInput>,
+        result: &OcrResult,
+    ) -> Result<(), OneOcrError> {
+        let image = PageImage::load(image.into())?;
+        let (image_width, image_height) = (image.width, image.height);
+        if image_width == 0 || image_height == 0 {
+            return Err(OneOcrError::EmptyImage {
+                width: image_width,
+                height: image_height,
+            });
+        }
+
+        let scale = 72.0 / self.options.dpi;
+        let (width, height) = (image_width as f32 * scale, image_height as f32 * scale);
+        let (result_width, result_height) = match (result.image_width, result.image_height) {
+            (0, _) | (_, 0) => (image_width, image_height),
+            size => size,
+        };
+        // Maps result coordinates, y down, to page coordinates, y up.
+        let to_page = Affine::scale(width / result_width as f32, -height / result_height as f32)
+            .then(&Affine::translate(0.0, height));
+
+        let mut content = Content::new();
+        content
+            .save_state()
+            .transform([width, 0.0, 0.0, height, 0.0, 0.0])
+            .x_object(IMAGE_RESOURCE)
+            .restore_state();
+        content
+            .begin_text()
+            .set_text_rendering_mode(TextRenderingMode::Invisible);
+        for line in &result.lines {
+            match &line.words {
+                Some(words) => {
+                    for (i, word) in words.iter().enumerate() {
+                        // Separates words when the text is extracted.
+                        let trailing_space = i + 1 < words.len();
+                        self.place_text(
+                            &mut content,
+                            &word.text,
+                            &word.bounding_box,
+                            trailing_space,
+                            &to_page,
+                        );
+                    }
+                }
+                None => self.place_text(
+                    &mut content,
+                    &line.text,
+                    &line.bounding_box,
+                    false,
+                    &to_page,
+                ),
+            }
+        }
+        content.end_text();
+
+        self.pages.push(PdfPage {
+            width,
+            height,
+            image,
+            content: compress_to_vec_zlib(&content.finish(), 6),
+        });
+        Ok(())
+    }
+
+    /// Shows `text` stretched over the baseline of `bounding_box`, as high as the box.
+    fn place_text(
+        &mut self,
+        content: &mut Content,
+        text: &str,
+        bounding_box: &BoundingBox,
+        trailing_space: bool,
+        to_page: &Affine,
+    ) {
+        let page_box = bounding_box.transform(to_page);
+        let (origin, end) = (page_box.bottom_left, page_box.bottom_right);
+        let (dx, dy) = (end.x - origin.x, end.y - origin.y);
+        let length = origin.distance(end);
+        let size = origin.distance(page_box.top_left);
+        let glyph_count = text.chars().count();
+        if glyph_count == 0 || length < f32::EPSILON || size < f32::EPSILON {
+            return;
+        }
+
+        let (cos, sin) = (dx / length, dy / length);
+        let natural_length = glyph_count as f32 * GLYPH_WIDTH / 1000.0 * size;
+        let mut encoded = Vec::with_capacity((glyph_count + 1) * 2);
+        for c in text.chars().chain(trailing_space.then_some(' ')) {
+            encoded.extend_from_slice(&self.glyph(c).to_be_bytes());
+        }
+        content
+            .set_font(FONT_RESOURCE, size)
+            .set_text_matrix([cos, sin, -sin, cos, origin.x, origin.y])
+            .set_horizontal_scaling(100.0 * length / natural_length)
+            .show(Str(&encoded));
+    }
+
+    /// Returns the glyph showing `c`, adding one if needed. Glyph 0 stands for characters
+    /// beyond the 65535 glyphs a font can have.
+    fn glyph(&mut self, c: char) -> u16 {
+        if let Some(&glyph) = self.glyphs.get(&c) {
+            return glyph;
+        }
+        let Ok(glyph) = u16::try_from(self.chars.len() + 1) else {
+            return 0;
+        };
+        self.chars.push(c);
+        self.glyphs.insert(c, glyph);
+        glyph
+    }
+
+    /// Writes the document and returns its bytes.
+    pub fn finish(self) -> Vec<u8> {
+        let mut pdf = Pdf::new();
+        let mut next_id = Ref::new(1);
+        let mut alloc = || next_id.bump();
+        let catalog_id = alloc();
+        let page_tree_id = alloc();
+        let font_id = alloc();
+        let cid_font_id = alloc();
+        let descriptor_id = alloc();
+        let font_file_id = alloc();
+        let cid_to_gid_id = alloc();
+        let cmap_id = alloc();
+        let info_id = alloc();
+        let page_ids: Vec<[Ref; 3]> = self
+            .pages
+            .iter()
+            .map(|_| [alloc(), alloc(), alloc()])
+            .collect();
+
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().map(|[page_id, ..]| *page_id))
+            .count(self.pages.len() as i32);
+        pdf.document_info(info_id)
+            .producer(TextStr(concat!("oneocr-rs ", env!("CARGO_PKG_VERSION"))));
+
+        // The text layer is never drawn, so its font has no glyph outlines: each character is
+        // its own CID, all CIDs show the same empty glyph, and the ToUnicode map makes the
+        // text extractable.
+        let system_info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+        pdf.type0_font(font_id)
+            .base_font(FONT_NAME)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+        pdf.cid_font(cid_font_id)
+            .subtype(CidFontType::Type2)
+            .base_font(FONT_NAME)
+            .system_info(system_info)
+            .font_descriptor(descriptor_id)
+            .default_width(GLYPH_WIDTH)
+            .cid_to_gid_map_stream(cid_to_gid_id);
+        pdf.font_descriptor(descriptor_id)
+            .name(FONT_NAME)
+            .flags(FontFlags::SYMBOLIC)
+            .bbox(Rect::new(0.0, 0.0, GLYPH_WIDTH, 1000.0))
+            .italic_angle(0.0)
+            .ascent(1000.0)
+            .descent(0.0)
+            .cap_height(1000.0)
+            .stem_v(80.0)
+            .font_file2(font_file_id);
+        let font_file = glyphless_font();
+        pdf.stream(font_file_id, &compress_to_vec_zlib(&font_file, 6))
+            .filter(Filter::FlateDecode)
+            .pair(Name(b"Length1"), font_file.len() as i32);
+        // Two bytes per CID: CID 0 shows `.notdef`, every other CID glyph 1.
+        let cid_to_gid: Vec<u8> = std::iter::once([0, 0])
+            .chain(self.chars.iter().map(|_| [0, 1]))
+            .flatten()
+            .collect();
+        pdf.stream(cid_to_gid_id, &compress_to_vec_zlib(&cid_to_gid, 6))
+            .filter(Filter::FlateDecode);
+        pdf.cmap(cmap_id, &to_unicode_cmap(&self.chars));
+
+        for (page, [page_id, image_id, content_id]) in self.pages.iter().zip(page_ids) {
+            let mut page_writer = pdf.page(page_id);
+            page_writer
+                .parent(page_tree_id)
+                .media_box(Rect::new(0.0, 0.0, page.width, page.height))
+                .contents(content_id);
+            let mut resources = page_writer.resources();
+            resources.x_objects().pair(IMAGE_RESOURCE, image_id);
+            resources.fonts().pair(FONT_RESOURCE, font_id);
+            resources.finish();
+            page_writer.finish();
+
+            let mut image = pdf.image_xobject(image_id, &page.image.data);
+            image.filter(page.image.filter);
+            image
+                .width(page.image.width as i32)
+                .height(page.image.height as i32)
+                .bits_per_component(8);
+            if page.image.gray {
+                image.color_space().device_gray();
+            } else {
+                image.color_space().device_rgb();
+            }
+            image.finish();
+
+            pdf.stream(content_id, &page.content)
+                .filter(Filter::FlateDecode);
+        }
+        pdf.finish()
+    }
+
+    /// Writes the document to a file.
+    pub fn save(self, path: impl AsRef<Path>) -> Result<(), OneOcrError> {
+        std::fs::write(path, self.finish()).map_err(OneOcrError::PdfWriteError)
+    }
+}
+
+/// Writes the ToUnicode map of the text layer's font, mapping glyph `i + 1` to `chars[i]`.
+fn to_unicode_cmap(chars: &[char]) -> Vec<u8> {
+    let mut cmap = String::from(concat!(
+        "/CIDInit /ProcSet findresource begin\n",
+        "12 dict begin\n",
+        "begincmap\n",
+        "/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n",
+        "/CMapName /Adobe-Identity-UCS def\n",
+        "/CMapType 2 def\n",
+        "1 begincodespacerange\n",
+        "<0000> <FFFF>\n",
+        "endcodespacerange\n",
+    ));
+    // Writing to a `String` never fails.
+    for (block, block_chars) in chars.chunks(100).enumerate() {
+        let _ = writeln!(cmap, "{} beginbfchar", block_chars.len());
+        for (i, c) in block_chars.iter().enumerate() {
+            let _ = write!(cmap, "<{:04X}> <", block * 100 + i + 1);
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                let _ = write!(cmap, "{unit:04X}");
+            }
+            cmap.push_str(">\n");
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str(concat!(
+        "endcmap\n",
+        "CMapName currentdict /CMap defineresource pop\n",
+        "end\n",
+        "end\n",
+    ));
+    cmap.into_bytes()
+}
+
+impl OcrResult {
+    /// Writes a single-page searchable PDF of `image` with the text of this result,
+    /// see [`SearchablePdf`].
+    pub fn to_searchable_pdf(
+        &self,
+        image: impl Into<ImageInput>,
+        options: PdfOptions,
+    ) -> Result<Vec<u8>, OneOcrError> {
+        let mut pdf = SearchablePdf::new(options)?;
+        pdf.add_page(image, self)?;
+        Ok(pdf.finish())
+    }
+}
@@ -0,0 +1,491 @@
+use crate::bounding_box::BoundingBox;
+use crate::geometry::{Rect, median};
+use crate::ocr_result::OcrResult;
+use serde::{Deserialize, Serialize};
+
+/// Cells with more words than this read like prose rather than table data.
+const MAX_CELL_WORDS: usize = 5;
+
+/// Options for [`OcrResult::detect_tables_with`]. Distances are in median word heights.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct TableOptions {
+    /// The fewest rows of a table.
+    pub min_rows: usize,
+    /// The fewest columns of a table.
+    pub min_columns: usize,
+    /// The narrowest gap between two columns. Words closer than this are read as one cell.
+    pub min_column_gap: f32,
+    /// The widest vertical gap between two rows of a table.
+    pub max_row_gap: f32,
+    /// The lowest [`Table::confidence`] of a reported table.
+    pub min_confidence: f32,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            min_rows: 2,
+            min_columns: 2,
+            min_column_gap: 1.0,
+            max_row_gap: 2.0,
+            min_confidence: 0.5,
+        }
+    }
+}
+
+/// A cell of a [`Table`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableCell {
+    /// The first row of the cell, from 0.
+    pub row: usize,
+    /// The first column of the cell, from 0.
+    pub column: usize,
+    /// The number of rows the cell covers, at least 1.
+    pub row_span: usize,
+    /// The number of columns the cell covers, at least 1.
+    pub column_span: usize,
+    pub text: String,
+    pub bounding_box: BoundingBox,
+}
+
+/// A table found in the words of an [`OcrResult`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    /// The box enclosing the cells, oriented like the first cell.
+    pub bounding_box: BoundingBox,
+    pub row_count: usize,
+    pub column_count: usize,
+    /// The cells, row by row and left to right. Empty positions of the grid have no cell.
+    pub cells: Vec<TableCell>,
+    /// How much the words look like a table, from 0 to 1: the share of the grid filled with
+    /// cells, times the share of cells aligned with their column, discounted for cells long
+    /// enough to be prose.
+    pub confidence: f32,
+}
+
+impl Table {
+    /// Returns the cell covering the given position of the grid.
+    pub fn cell(&self, row: usize, column: usize) -> Option<&TableCell> {
+        self.cells.iter().find(|cell| {
+            (cell.row..cell.row + cell.row_span).contains(&row)
+                && (cell.column..cell.column + cell.column_span).contains(&column)
+        })
+    }
+
+    /// Returns the text of every position of the grid. The text of a spanning cell is in its
+    /// first row and column, and the other positions it covers are empty.
+    pub fn grid(&self) -> Vec<Vec<&str>> {
+        let mut grid = vec![vec![""; self.column_count]; self.row_count];
+        for cell in &self.cells {
+            if let Some(position) = grid
+                .get_mut(cell.row)
+                .and_then(|row| row.get_mut(cell.column))
+            {
+                *position = &cell.text;
+            }
+        }
+        grid
+    }
+
+    /// Renders the table as CSV ([RFC 4180](https://www.rfc-editor.org/rfc/rfc4180)), one
+    /// record per row ending in `\n`. Fields containing commas, quotes or line breaks are
+    /// quoted.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for row in self.grid() {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|text| {
+                    if text.contains([',', '"', '\n', '\r']) {
+                        format!("\"{}\"", text.replace('"', "\"\""))
+                    } else {
+                        text.to_string()
+                    }
+                })
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Renders the table as a GitHub-flavored Markdown table, with the first row as header.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for (r, row) in self.grid().iter().enumerate() {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|text| text.replace('|', "\\|").replace(['\r', '\n'], " "))
+                .collect();
+            markdown.push_str(&format!("| {} |\n", fields.join(" | ")));
+            if r == 0 {
+                markdown.push_str(&format!("|{}\n", " --- |".repeat(self.column_count)));
+            }
+        }
+        markdown
+    }
+
+    /// Serializes the table to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("tables always serialize to JSON")
+    }
+}
+
+/// Words of one line close enough to be read as one cell.
+struct Segment {
+    text: String,
+    /// The bounds after undoing the skew of the page.
+    bounds: Rect,
+    bounding_box: BoundingBox,
+}
+
+impl Segment {
+    fn center(&self) -> f32 {
+        self.bounds.y + self.bounds.height / 2.0
+    }
+
+    fn append(&mut self, other: Segment) {
+        self.text.push(' ');
+        self.text.push_str(&other.text);
+        self.bounds = self.bounds.union(&other.bounds);
+        self.bounding_box = self.bounding_box.merge(&other.bounding_box);
+    }
+}
+
+/// A row band: segments whose vertical centers fall within the same line of text.
+struct Row {
+    segments: Vec<Segment>,
+    top: f32,
+    bottom: f32,
+}
+
+impl Row {
+    fn center(&self) -> f32 {
+        (self.top + self.bottom) / 2.0
+    }
+}
+
+impl OcrResult {
+    /// Finds tables with the default [`TableOptions`].
+    pub fn detect_tables(&self) -> Vec<Table> {
+        self.detect_tables_with(&TableOptions::default())
+    }
+
+    /// Finds tables in the geometry of the words.
+    ///
+    /// Words of a line closer than the column gap are joined into cells, and cells are
+    /// grouped into rows by their vertical centers. Consecutive rows of at least two cells
+    /// form a table. Its columns are the ranges covered by the cells of more than a quarter of
+    /// its rows, so a header spanning several columns doesn't merge them. A cell spans the
+    /// columns it overlaps, and the rows whose centers its height covers. Lines without
+    /// word-level details are read as single cells.
+    pub fn detect_tables_with(&self, options: &TableOptions) -> Vec<Table> {
+        let deskew = self.deskew();
+        let mut units: Vec<Vec<Segment>> = self
+            .lines
+            .iter()
+            .map(|line| {
+                let words: Vec<(&str, BoundingBox)> = match &line.words {
+                    Some(words) if !words.is_empty() => words
+                        .iter()
+                        .map(|word| (word.text.as_str(), word.bounding_box))
+                        .collect(),
+                    _ => vec![(line.text.as_str(), line.bounding_box)],
+                };
+                words
+                    .into_iter()
+                    .filter(|(text, _)| !text.trim().is_empty())
+                    .map(|(text, bounding_box)| Segment {
+                        text: text.trim().to_string(),
+                        bounds: bounding_box.transform(&deskew).bounds(),
+                        bounding_box,
+                    })
+                    .collect()
+            })
+            .collect();
+        let word_height = median(
+            units
+                .iter()
+                .flatten()
+                .map(|segment| segment.bounds.height)
+                .filter(|height| *height > 0.0),
+        )
+        .unwrap_or(1.0);
+        let column_gap = options.min_column_gap * word_height;
+
+        let mut segments = Vec::new();
+        for words in &mut units {
+            words.sort_by(|a, b| a.bounds.x.total_cmp(&b.bounds.x));
+            let mut line_segments: Vec<Segment> = Vec::new();
+            for word in words.drain(..) {
+                match line_segments.last_mut() {
+                    Some(segment) if word.bounds.x - segment.bounds.right() < column_gap => {
+                        segment.append(word)
+                    }
+                    _ => line_segments.push(word),
+                }
+            }
+            segments.extend(line_segments);
+        }
+
+        let mut tables = Vec::new();
+        let mut run: Vec<Row> = Vec::new();
+        for row in rows(segments, word_height) {
+            let continues = run
+                .last()
+                .is_some_and(|last| row.top - last.bottom <= options.max_row_gap * word_height);
+            if row.segments.len() < 2 || !continues {
+                tables.extend(build_table(std::mem::take(&mut run), word_height, options));
+            }
+            if row.segments.len() >= 2 {
+                run.push(row);
+            }
+        }
+        tables.extend(build_table(run, word_height, options));
+        tables
+    }
+}
+
+/// Groups segments into row bands, top to bottom. Rows are formed by segments of about the
+/// height of a word; taller segments, which may span rows, join the first row they reach.
+fn rows(segments: Vec<Segment>, word_height: f32) -> Vec<Row> {
+    let (mut regular, tall): (Vec<Segment>, Vec<Segment>) = segments
+        .into_iter()
+        .partition(|segment| segment.bounds.height <= 1.5 * word_height);
+    regular.sort_by(|a, b| a.center().total_cmp(&b.center()));
+
+    let mut rows: Vec<Row> = Vec::new();
+    for segment in regular {
+        match rows.last_mut() {
+            Some(row) if segment.center() <= row.segments[0].bounds.bottom() => {
+                row.bottom = row.bottom.max(segment.bounds.bottom());
+                row.segments.push(segment);
+            }
+            _ => rows.push(Row {
+                top: segment.bounds.y,
+                bottom: segment.bounds.bottom(),
+                segments: vec![segment],
+            }),
+        }
+    }
+    for segment in tall {
+        let position = rows.iter().position(|row| row.center() >= segment.bounds.y);
+        match position {
+            Some(r) if rows[r].top <= segment.bounds.bottom() => {
+                rows[r].top = rows[r].top.min(segment.bounds.y);
+                rows[r].segments.push(segment);
+            }
+            _ => {
+                let r = position.unwrap_or(rows.len());
+                rows.insert(
+                    r,
+                    Row {
+                        top: segment.bounds.y,
+                        bottom: segment.bounds.bottom(),
+                        segments: vec![segment],
+                    },
+                );
+            }
+        }
+    }
+    for row in &mut rows {
+        row.segments
+            .sort_by(|a, b| a.bounds.x.total_cmp(&b.bounds.x));
+    }
+    rows
+}
+
+/// Builds a table from consecutive rows, or returns `None` if they don't make one.
+fn build_table(rows: Vec<Row>, word_height: f32, options: &TableOptions) -> Option<Table> {
+    if rows.len() < options.min_rows.max(1) {
+        return None;
+    }
+    let columns = columns(&rows, options.min_column_gap * word_height / 2.0);
+    if columns.len() < options.min_columns.max(1) {
+        return None;
+    }
+
+    // Cells with their deskewed bounds, by row.
+    let row_centers: Vec<f32> = rows.iter().map(Row::center).collect();
+    let mut cells: Vec<(TableCell, Rect)> = Vec::new();
+    for (r, row) in rows.into_iter().enumerate() {
+        let row_start = cells.len();
+        for segment in row.segments {
+            let overlapping: Vec<usize> = (0..columns.len())
+                .filter(|c| {
+                    let (start, end) = columns[*c];
+                    segment.bounds.right().min(end) - segment.bounds.x.max(start) > 0.0
+                })
+                .collect();
+            let (first, last) = match (overlapping.first(), overlapping.last()) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => {
+                    let center = segment.bounds.x + segment.bounds.width / 2.0;
+                    let nearest = (0..columns.len())
+                        .min_by(|a, b| {
+                            let distance =
+                                |c: usize| (center - (columns[c].0 + columns[c].1) / 2.0).abs();
+                            distance(*a).total_cmp(&distance(*b))
+                        })
+                        .unwrap_or(0);
+                    (nearest, nearest)
+                }
+            };
+            let row_span = 1 + row_centers[r + 1..]
+                .iter()
+                .take_while(|center| **center <= segment.bounds.bottom())
+                .count();
+
+            match cells[row_start..].last_mut() {
+                // Segments of one row in the same column form one cell.
+                Some((cell, bounds)) if first < cell.column + cell.column_span => {
+                    cell.text.push(' ');
+                    cell.text.push_str(&segment.text);
+                    cell.bounding_box = cell.bounding_box.merge(&segment.bounding_box);
+                    cell.column_span = cell.column_span.max(last + 1 - cell.column);
+                    cell.row_span = cell.row_span.max(row_span);
+                    *bounds = bounds.union(&segment.bounds);
+                }
+                _ => cells.push((
+                    TableCell {
+                        row: r,
+                        column: first,
+                        row_span,
+                        column_span: last + 1 - first,
+                        text: segment.text,
+                        bounding_box: segment.bounding_box,
+                    },
+                    segment.bounds,
+                )),
+            }
+        }
+    }
+
+    // Spans stop at the next cell in their way.
+    let row_count = row_centers.len();
+    let mut occupied = vec![vec![false; columns.len()]; row_count];
+    for (cell, _) in &mut cells {
+        let free = |row: usize, occupied: &[Vec<bool>]| {
+            (cell.column..cell.column + cell.column_span).all(|c| !occupied[row][c])
+        };
+        cell.row_span = (cell.row..cell.row + cell.row_span)
+            .take_while(|row| *row == cell.row || free(*row, &occupied))
+            .count();
+        for row in &mut occupied[cell.row..cell.row + cell.row_span] {
+            for position in &mut row[cell.column..cell.column + cell.column_span] {
+                *position = true;
+            }
+        }
+    }
+    let filled = occupied.iter().flatten().filter(|cell| **cell).count();
+    let fill = filled as f32 / (row_count * columns.len()) as f32;
+
+    let tolerance = word_height / 2.0;
+    let mut aligned = 0;
+    let mut single = 0;
+    for c in 0..columns.len() {
+        let bounds: Vec<Rect> = cells
+            .iter()
+            .filter(|(cell, _)| cell.column == c && cell.column_span == 1)
+            .map(|(_, bounds)| *bounds)
+            .collect();
+        let left = median(bounds.iter().map(|b| b.x)).unwrap_or_default();
+        let right = median(bounds.iter().map(|b| b.right())).unwrap_or_default();
+        let center = median(bounds.iter().map(|b| b.x + b.width / 2.0)).unwrap_or_default();
+        single += bounds.len();
+        aligned += bounds
+            .iter()
+            .filter(|b| {
+                (b.x - left).abs() <= tolerance
+                    || (b.right() - right).abs() <= tolerance
+                    || (b.x + b.width / 2.0 - center).abs() <= tolerance
+            })
+            .count();
+    }
+    let alignment = if single == 0 {
+        1.0
+    } else {
+        aligned as f32 / single as f32
+    };
+    let brief = cells
+        .iter()
+        .filter(|(cell, _)| cell.text.split_whitespace().count() <= MAX_CELL_WORDS)
+        .count() as f32
+        / cells.len() as f32;
+    let confidence = fill * alignment * (0.25 + 0.75 * brief);
+    if confidence < options.min_confidence {
+        return None;
+    }
+
+    let cells: Vec<TableCell> = cells.into_iter().map(|(cell, _)| cell).collect();
+    let bounding_box = cells
+        .iter()
+        .skip(1)
+        .fold(cells[0].bounding_box, |bounding_box, cell| {
+            bounding_box.merge(&cell.bounding_box)
+        });
+    Some(Table {
+        bounding_box,
+        row_count,
+        column_count: columns.len(),
+        cells,
+        confidence,
+    })
+}
+
+/// Returns the horizontal ranges covered by the segments of more than a quarter of the rows,
+/// left to right. Ranges closer than `min_gap` are joined.
+fn columns(rows: &[Row], min_gap: f32) -> Vec<(f32, f32)> {
+    // Where the number of rows covering the page changes, by one per overlapping run of the
+    // segments of a row.
+    let mut changes: Vec<(f32, isize)> = Vec::new();
+    for row in rows {
+        let mut ranges: Vec<(f32, f32)> = row
+            .segments
+            .iter()
+            .map(|segment| (segment.bounds.x, segment.bounds.right()))
+            // Also skips NaN coordinates.
+            .filter(|(start, end)| start < end)
+            .collect();
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut covered: Option<(f32, f32)> = None;
+        for (start, end) in ranges {
+            match &mut covered {
+                Some(run) if start <= run.1 => run.1 = run.1.max(end),
+                _ => {
+                    if let Some((start, end)) = covered.replace((start, end)) {
+                        changes.extend([(start, 1), (end, -1)]);
+                    }
+                }
+            }
+        }
+        if let Some((start, end)) = covered {
+            changes.extend([(start, 1), (end, -1)]);
+        }
+    }
+    changes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let threshold = (rows.len() / 4) as isize;
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+    let (mut count, mut start) = (0, None);
+    for (i, (x, change)) in changes.iter().enumerate() {
+        count += change;
+        // Only the coverage after all changes at the same position counts.
+        if changes.get(i + 1).is_some_and(|next| next.0 == *x) {
+            continue;
+        }
+        match (start, count > threshold) {
+            (None, true) => start = Some(*x),
+            (Some(first), false) => {
+                match columns.last_mut() {
+                    Some(column) if first - column.1 < min_gap => column.1 = *x,
+                    _ => columns.push((first, *x)),
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    columns
+}
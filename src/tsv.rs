@@ -0,0 +1,91 @@
+use crate::ocr_result::OcrResult;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// The header of Tesseract's TSV output.
+const HEADER: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n";
+
+impl OcrResult {
+    /// Renders the result in the TSV format of Tesseract (`tesseract image out tsv`).
+    ///
+    /// The columns are `level page_num block_num par_num line_num word_num left top width
+    /// height conf text`, with one row for the page (level 1), then each of the
+    /// [`blocks`](Self::blocks) (2) followed by its paragraphs (3), each followed by its lines
+    /// (4) and their words (5), grouped on the fly if the result has none. As in Tesseract,
+    /// paragraphs are numbered within their block, lines within their paragraph and words
+    /// within their line. Words have their confidence scaled to 0-100; the other levels have a
+    /// confidence of -1 and no text, as in Tesseract. Lines without word-level details get a
+    /// single word row with the text of the line and a confidence of -1. Boxes are the
+    /// axis-aligned bounds of the quadrilateral bounding boxes.
+    pub fn to_tsv(&self) -> String {
+        let (width, height) = self.page_size();
+        let mut tsv = String::from(HEADER);
+        // Writing to a `String` never fails.
+        let _ = writeln!(tsv, "1\t1\t0\t0\t0\t0\t0\t0\t{width}\t{height}\t-1\t");
+        for (b, block) in self.current_blocks().iter().enumerate() {
+            let block_num = b + 1;
+            let _ = writeln!(
+                tsv,
+                "2\t1\t{block_num}\t0\t0\t0\t{}\t-1\t",
+                columns(block.bounding_box.pixel_bounds())
+            );
+            for (p, paragraph) in block.paragraphs.iter().enumerate() {
+                let ids = format!("1\t{block_num}\t{}", p + 1);
+                let _ = writeln!(
+                    tsv,
+                    "3\t{ids}\t0\t0\t{}\t-1\t",
+                    columns(paragraph.bounding_box.pixel_bounds())
+                );
+                for (l, line) in paragraph.ocr_lines(self).enumerate() {
+                    let line_num = l + 1;
+                    let _ = writeln!(
+                        tsv,
+                        "4\t{ids}\t{line_num}\t0\t{}\t-1\t",
+                        columns(line.bounding_box.pixel_bounds())
+                    );
+                    match &line.words {
+                        Some(words) => {
+                            for (w, word) in words.iter().enumerate() {
+                                let _ = writeln!(
+                                    tsv,
+                                    "5\t{ids}\t{line_num}\t{}\t{}\t{}\t{}",
+                                    w + 1,
+                                    columns(word.bounding_box.pixel_bounds()),
+                                    (word.confidence * 100.0).clamp(0.0, 100.0),
+                                    field(&word.text)
+                                );
+                            }
+                        }
+                        None => {
+                            let _ = writeln!(
+                                tsv,
+                                "5\t{ids}\t{line_num}\t1\t{}\t-1\t{}",
+                                columns(line.bounding_box.pixel_bounds()),
+                                field(&line.text)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        tsv
+    }
+}
+
+/// Formats the `left top width height` columns of `[left, top, right, bottom]`.
+fn columns([left, top, right, bottom]: [u32; 4]) -> String {
+    format!(
+        "{left}\t{top}\t{}\t{}",
+        right.saturating_sub(left),
+        bottom.saturating_sub(top)
+    )
+}
+
+/// Replaces the tabs and line breaks that would split a text field.
+fn field(text: &str) -> Cow<'_, str> {
+    if text.contains(['\t', '\n', '\r']) {
+        Cow::Owned(text.replace(['\t', '\n', '\r'], " "))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
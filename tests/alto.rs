@@ -0,0 +1,108 @@
+mod common;
+
+use common::{assert_valid_xml, exported_result, two_block_result};
+use oneocr_rs::{AltoOptions, OcrResult};
+
+const ALTO_NS: &str = "http://www.loc.gov/standards/alto/ns-v4#";
+
+fn position(node: roxmltree::Node) -> [String; 4] {
+    ["HPOS", "VPOS", "WIDTH", "HEIGHT"].map(|name| node.attribute(name).unwrap().to_string())
+}
+
+#[test]
+fn alto_has_page_lines_strings_and_spaces() {
+    let alto = exported_result().to_alto();
+
+    let document = roxmltree::Document::parse(&alto).unwrap();
+    let elements = |name: &str| {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name((ALTO_NS, name)))
+            .collect::<Vec<_>>()
+    };
+
+    let page = &elements("Page")[0];
+    assert_eq!(page.attribute("WIDTH"), Some("200"));
+    assert_eq!(page.attribute("HEIGHT"), Some("100"));
+    assert_eq!(elements("TextBlock")[0].attribute("ROTATION"), Some("1.5"));
+
+    let lines = elements("TextLine");
+    assert_eq!(lines.len(), 2);
+    assert_eq!(position(lines[0]), ["10", "10", "150", "20"]);
+    // The box of the second line is skewed: its bounds enclose every corner.
+    assert_eq!(position(lines[1]), ["10", "58", "91", "23"]);
+
+    let strings = elements("String");
+    let contents: Vec<_> = strings
+        .iter()
+        .map(|string| string.attribute("CONTENT").unwrap())
+        .collect();
+    assert_eq!(contents, ["Fish", "&", "<Chips>", "Total 42"]);
+    assert_eq!(strings[0].attribute("WC"), Some("0.987"));
+    assert_eq!(strings[3].attribute("WC"), None);
+    assert!(alto.contains("CONTENT=\"&lt;Chips&gt;\""));
+
+    let spaces = elements("SP");
+    assert_eq!(spaces.len(), 2);
+    assert_eq!(spaces[0].attribute("HPOS"), Some("50"));
+    assert_eq!(spaces[0].attribute("WIDTH"), Some("5"));
+
+    assert_eq!(elements("softwareName")[0].text(), Some("oneocr-rs"),);
+}
+
+#[test]
+fn alto_options_fill_in_the_description() {
+    let mut options = AltoOptions::default();
+    options.source_image_file_name = Some("scans/receipt & co.png".to_string());
+    options.model_name = "custom model".to_string();
+
+    let alto = exported_result().to_alto_with(&options);
+
+    assert!(alto.contains("<fileName>scans/receipt &amp; co.png</fileName>"));
+    assert!(alto.contains("<applicationDescription>custom model</applicationDescription>"));
+    assert!(AltoOptions::default().model_name.contains("OneOCR"));
+}
+
+#[test]
+fn blocks_are_text_blocks() {
+    let alto = two_block_result().to_alto();
+
+    let document = roxmltree::Document::parse(&alto).unwrap();
+    let blocks: Vec<_> = document
+        .descendants()
+        .filter(|node| node.has_tag_name((ALTO_NS, "TextBlock")))
+        .collect();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[1].attribute("ID"), Some("block_2"));
+    assert_eq!(position(blocks[1]), ["10", "300", "90", "20"]);
+    let lines: Vec<_> = blocks[1]
+        .children()
+        .filter(|node| node.has_tag_name((ALTO_NS, "TextLine")))
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].attribute("ID"), Some("line_2"));
+}
+
+#[test]
+fn empty_results_have_no_blocks() {
+    let alto = OcrResult::new(Vec::new(), 0.0).to_alto();
+
+    assert!(!alto.contains("TextBlock"));
+    assert!(!alto.contains("ROTATION"));
+}
+
+#[test]
+#[ignore = "needs xmllint and the schemas of tests/fixtures/fetch-schemas.sh"]
+fn exports_are_valid_alto_4_4() {
+    let mut options = AltoOptions::default();
+    options.source_image_file_name = Some("scans/receipt & co.png".to_string());
+
+    for alto in [
+        exported_result().to_alto(),
+        exported_result().to_alto_with(&options),
+        two_block_result().to_alto(),
+        OcrResult::new(Vec::new(), 0.0).to_alto(),
+    ] {
+        assert_valid_xml(&alto, "alto-4-4.xsd");
+    }
+}
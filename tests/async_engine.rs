@@ -0,0 +1,131 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use common::{FakeBackend, blank_image, sample_lines};
+use oneocr_rs::{AsyncOcrEngine, AsyncOptions, OcrOptions, OneOcrError, RunOptions};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn slow_backend(run_delay: Duration) -> FakeBackend {
+    let mut backend = FakeBackend::new(sample_lines(), 0.0);
+    backend.run_delay = run_delay;
+    backend
+}
+
+#[tokio::test]
+async fn results_are_returned_as_futures() {
+    let mut async_options = AsyncOptions::default();
+    async_options.workers = 2;
+    let engine = AsyncOcrEngine::new_with_backend(
+        FakeBackend::new(sample_lines(), 0.0),
+        OcrOptions::default(),
+        async_options,
+    )
+    .unwrap();
+
+    let result = engine.run(blank_image().into()).await.unwrap();
+    assert_eq!(result.lines.len(), 2);
+    assert_eq!(result.lines[0].text, "Hello world");
+
+    let mut run_options = RunOptions::default();
+    run_options.max_recognition_line_count = 1;
+    let result = engine
+        .run_with(blank_image().into(), &run_options)
+        .await
+        .unwrap();
+    assert_eq!(result.lines.len(), 1);
+}
+
+#[tokio::test]
+async fn dropped_future_skips_queued_job() {
+    let backend = slow_backend(Duration::from_millis(100));
+    let runs = backend.runs.clone();
+    let engine = Arc::new(
+        AsyncOcrEngine::new_with_backend(backend, OcrOptions::default(), AsyncOptions::default())
+            .unwrap(),
+    );
+
+    // Keep the only worker busy, then give up on a request stuck behind it.
+    let busy = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.run(blank_image().into()).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let queued =
+        tokio::time::timeout(Duration::from_millis(10), engine.run(blank_image().into())).await;
+    assert!(queued.is_err());
+
+    busy.await.unwrap().unwrap();
+    engine.run(blank_image().into()).await.unwrap();
+
+    assert_eq!(runs.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn slow_requests_time_out() {
+    let mut async_options = AsyncOptions::default();
+    async_options.timeout = Some(Duration::from_millis(10));
+    let engine = AsyncOcrEngine::new_with_backend(
+        slow_backend(Duration::from_millis(100)),
+        OcrOptions::default(),
+        async_options,
+    )
+    .unwrap();
+
+    let err = engine.run(blank_image().into()).await.unwrap_err();
+    assert!(matches!(err, OneOcrError::Timeout(_)), "{err}");
+
+    let result = engine
+        .run_with_timeout(blank_image().into(), Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(result.lines.len(), 2);
+}
+
+#[tokio::test]
+async fn full_queue_applies_backpressure() {
+    let mut async_options = AsyncOptions::default();
+    async_options.queue_capacity = 1;
+    let engine = Arc::new(
+        AsyncOcrEngine::new_with_backend(
+            slow_backend(Duration::from_millis(100)),
+            OcrOptions::default(),
+            async_options,
+        )
+        .unwrap(),
+    );
+
+    // One request running on the worker, one waiting in the queue.
+    let mut requests = Vec::new();
+    for _ in 0..2 {
+        let engine = engine.clone();
+        requests.push(tokio::spawn(async move {
+            engine.run(blank_image().into()).await
+        }));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(engine.queue_len(), 1);
+
+    let err = engine.try_run(blank_image().into()).await.unwrap_err();
+    assert!(matches!(err, OneOcrError::QueueFull), "{err}");
+
+    for request in requests {
+        request.await.unwrap().unwrap();
+    }
+    engine.try_run(blank_image().into()).await.unwrap();
+}
+
+#[test]
+fn invalid_async_options_are_rejected() {
+    let mut async_options = AsyncOptions::default();
+    async_options.queue_capacity = 0;
+    let err = AsyncOcrEngine::new_with_backend(
+        FakeBackend::new(sample_lines(), 0.0),
+        OcrOptions::default(),
+        async_options,
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, OneOcrError::InvalidQueueCapacity(0)), "{err}");
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use common::ProcessOptions;
+use common::{FakeBackend, blank_image, sample_lines};
+use oneocr_rs::{LineStyle, OcrEngine, OcrLine, OcrOptions, OneOcrError, Resolution, RunOptions};
+use std::sync::atomic::Ordering;
+
+#[test]
+fn run_walks_lines_and_words_through_backend() {
+    let mut options = OcrOptions::default();
+    options.include_word_level_details = true;
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(sample_lines(), 1.5), options).unwrap();
+
+    let result = engine.run(blank_image().into()).unwrap();
+
+    assert_eq!(result.image_angle, 1.5);
+    assert_eq!(result.lines.len(), 2);
+    assert_eq!(result.lines[0].text, "Hello world");
+    let words = result.lines[1].words.as_ref().unwrap();
+    assert_eq!(words[0].text, "Second");
+    assert_eq!(words[1].confidence, 0.9);
+    assert_eq!(result.lines[1].style, LineStyle::Handwritten);
+    assert!((result.lines[1].style_confidence - 0.8).abs() < 1e-6);
+}
+
+#[test]
+fn results_are_owned_data_released_by_the_backend_right_away() {
+    let backend = FakeBackend::new(sample_lines(), 0.0);
+    let live_objects = backend.live_objects.clone();
+    let engine = OcrEngine::new_with_backend(backend, OcrOptions::default()).unwrap();
+    let live_before = live_objects.load(Ordering::SeqCst);
+
+    let result = engine.run(blank_image().into()).unwrap();
+    assert_eq!(live_objects.load(Ordering::SeqCst), live_before);
+
+    let copy = result.clone();
+    let lines = result.lines;
+    drop(engine);
+    assert_eq!(lines, copy.lines);
+    assert_eq!(lines[0].style, LineStyle::Printed);
+    assert_eq!(lines[1].style, LineStyle::Handwritten);
+}
+
+#[test]
+fn lines_are_filtered_and_serialized_by_style() {
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(sample_lines(), 0.0), OcrOptions::default())
+            .unwrap();
+
+    let result = engine.run(blank_image().into()).unwrap();
+
+    let texts = |lines: Vec<&OcrLine>| lines.iter().map(|l| l.text.clone()).collect::<Vec<_>>();
+    assert_eq!(texts(result.handwritten_lines().collect()), ["Second line"]);
+    assert_eq!(texts(result.printed_lines().collect()), ["Hello world"]);
+    assert_eq!(
+        texts(result.lines_with_style_confidence(0.9).collect()),
+        ["Hello world"]
+    );
+    assert_eq!(result.lines_with_style_confidence(0.5).count(), 2);
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["lines"][0]["style"], "printed");
+    assert_eq!(json["lines"][1]["style"], "handwritten");
+    assert!(json["lines"][1]["style_confidence"].as_f64().unwrap() > 0.79);
+}
+
+#[test]
+fn run_skips_words_without_word_level_details() {
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(sample_lines(), 0.0), OcrOptions::default())
+            .unwrap();
+
+    let result = engine.run(blank_image().into()).unwrap();
+
+    assert!(result.lines.iter().all(|line| line.words.is_none()));
+}
+
+#[test]
+fn process_options_are_forwarded_to_backend() {
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(Vec::new(), 0.0), OcrOptions::default())
+            .unwrap();
+    assert_eq!(engine.get_max_recognition_line_count().unwrap(), 100);
+
+    engine.set_max_recognition_line_count(1000).unwrap();
+    assert_eq!(engine.get_max_recognition_line_count().unwrap(), 1000);
+}
+
+#[test]
+fn run_with_applies_options_to_a_single_call() {
+    let backend = FakeBackend::new(sample_lines(), 0.0);
+    let runs = backend.runs.clone();
+    let engine = OcrEngine::new_with_backend(backend, OcrOptions::default()).unwrap();
+    let mut run_options = RunOptions::default();
+    run_options.max_recognition_line_count = 1;
+    run_options.resize_resolution = Resolution {
+        width: 640,
+        height: 480,
+    };
+    run_options.include_word_level_details = true;
+
+    let result = engine.run_with(blank_image().into(), &run_options).unwrap();
+    assert_eq!(result.lines.len(), 1);
+    assert!(result.lines[0].words.is_some());
+
+    let result = engine.run(blank_image().into()).unwrap();
+    assert_eq!(result.lines.len(), 2);
+    assert!(result.lines[0].words.is_none());
+    assert_eq!(engine.get_max_recognition_line_count().unwrap(), 100);
+
+    assert_eq!(
+        *runs.lock().unwrap(),
+        [
+            ProcessOptions {
+                max_line_count: 1,
+                resize_resolution: (640, 480),
+            },
+            ProcessOptions::default(),
+        ]
+    );
+}
+
+#[test]
+fn run_with_releases_its_process_options() {
+    let backend = FakeBackend::new(sample_lines(), 0.0);
+    let live_objects = backend.live_objects.clone();
+    let engine = OcrEngine::new_with_backend(backend, OcrOptions::default()).unwrap();
+    let live_before = live_objects.load(Ordering::SeqCst);
+
+    let result = engine
+        .run_with(blank_image().into(), &RunOptions::default())
+        .unwrap();
+    drop(result);
+
+    assert_eq!(live_objects.load(Ordering::SeqCst), live_before);
+}
+
+#[test]
+fn run_with_rejects_out_of_range_options() {
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(sample_lines(), 0.0), OcrOptions::default())
+            .unwrap();
+    let mut run_options = RunOptions::default();
+    run_options.max_recognition_line_count = 1001;
+
+    let err = engine
+        .run_with(blank_image().into(), &run_options)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        OneOcrError::InvalidMaxRecognitionLineCount(1001)
+    ));
+}
+
+#[test]
+fn negative_line_counts_are_rejected() {
+    let mut backend = FakeBackend::new(sample_lines(), 0.0);
+    backend.reported_line_count = Some(-1);
+    let engine = OcrEngine::new_with_backend(backend, OcrOptions::default()).unwrap();
+
+    let err = engine.run(blank_image().into()).unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            OneOcrError::InvalidCount {
+                what: "line",
+                count: -1
+            }
+        ),
+        "{err}"
+    );
+}
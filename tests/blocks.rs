@@ -0,0 +1,164 @@
+mod common;
+
+use common::{line, rect};
+use oneocr_rs::{Affine, BlockOptions, LineStyle, OcrResult};
+
+/// A heading over three paragraphs, the first ending in a short line and the third indented,
+/// and a footnote far below.
+fn report() -> OcrResult {
+    let mut heading = line("Annual report", 50.0, 20.0, 200.0);
+    heading.bounding_box = rect(50.0, 20.0, 200.0, 30.0);
+    OcrResult::new(
+        vec![
+            heading,
+            line("The results of the year were ex-", 50.0, 70.0, 400.0),
+            line("ceptional across all regions and", 50.0, 95.0, 400.0),
+            line("teams.", 50.0, 120.0, 150.0),
+            line("Revenue grew in the wake of COVID-", 50.0, 145.0, 400.0),
+            line("19 by twelve percent.", 50.0, 170.0, 400.0),
+            line("Costs fell.", 80.0, 195.0, 370.0),
+            line("Margins rose.", 50.0, 220.0, 200.0),
+            line("1) Unaudited.", 50.0, 300.0, 100.0),
+        ],
+        0.0,
+    )
+}
+
+#[test]
+fn lines_are_grouped_into_paragraphs_and_blocks() {
+    let result = report().with_blocks();
+
+    let paragraphs: Vec<Vec<usize>> = result
+        .blocks
+        .iter()
+        .map(|block| block.paragraphs.iter().map(|p| p.lines.len()).collect())
+        .collect();
+    assert_eq!(paragraphs, [vec![1], vec![3, 2, 2], vec![1]]);
+
+    let body = &result.blocks[1];
+    assert_eq!(
+        body.text(&result),
+        concat!(
+            "The results of the year were exceptional across all regions and teams.\n",
+            "Revenue grew in the wake of COVID-19 by twelve percent.\n",
+            "Costs fell. Margins rose."
+        )
+    );
+    assert_eq!(
+        body.bounding_box.bounds(),
+        rect(50.0, 70.0, 400.0, 170.0).bounds()
+    );
+    assert_eq!(
+        body.paragraphs[2].bounding_box.bounds(),
+        rect(50.0, 195.0, 400.0, 45.0).bounds()
+    );
+    assert_eq!(body.ocr_lines(&result).count(), 7);
+    assert_eq!(body.words(&result).next().unwrap().text, "The");
+    assert_eq!(body.paragraphs[2].words(&result).count(), 4);
+    assert_eq!(result.paragraphs().count(), 5);
+
+    // Without the height and short-line rules, the heading runs into the first two paragraphs.
+    let mut options = BlockOptions::default();
+    options.max_height_ratio = 2.0;
+    options.min_short_line = 100.0;
+    let blocks = result.group_blocks_with(&options);
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].paragraphs.len(), 2);
+    assert_eq!(blocks[0].paragraphs[0].lines, [0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn columns_and_scripts_without_spaces() {
+    let result = OcrResult::new(
+        vec![
+            line("今日は良い", 300.0, 20.0, 100.0),
+            line("First column", 50.0, 20.0, 200.0),
+            line("天気です。", 300.0, 45.0, 100.0),
+            line("continues here", 50.0, 45.0, 200.0),
+        ],
+        0.0,
+    )
+    .with_blocks();
+
+    let texts: Vec<String> = result
+        .blocks
+        .iter()
+        .map(|block| block.text(&result))
+        .collect();
+    assert_eq!(
+        texts,
+        ["First column continues here", "今日は良い天気です。"]
+    );
+}
+
+#[test]
+fn blocks_follow_the_lines() {
+    let result = report().with_blocks();
+
+    let json = result.to_json();
+    assert!(json.contains("\"paragraphs\""));
+    assert_eq!(OcrResult::from_json(&json).unwrap(), result);
+
+    let moved = result.transform(&Affine::translate(10.0, 5.0));
+    assert_eq!(
+        moved.blocks[1].bounding_box.bounds(),
+        rect(60.0, 75.0, 400.0, 170.0).bounds()
+    );
+
+    let mut shuffled = result.clone();
+    shuffled.lines.swap(1, 4);
+    shuffled.blocks = shuffled.group_blocks();
+    let texts = shuffled.blocks[1].text(&shuffled);
+    shuffled.sort_by_reading_order();
+    assert_eq!(shuffled.lines, result.lines);
+    assert_eq!(shuffled.blocks, result.blocks);
+    assert_eq!(shuffled.blocks[1].text(&shuffled), texts);
+}
+
+#[test]
+fn stale_blocks_are_grouped_afresh() {
+    let mut result = report().with_blocks();
+    result.lines[1].style = LineStyle::Handwritten;
+    result.lines[4].style = LineStyle::Handwritten;
+
+    // Filtering lines leaves the blocks pointing at the old indices.
+    result.lines.retain(|line| line.style == LineStyle::Printed);
+
+    let regrouped = result.clone().with_blocks();
+    assert_ne!(result.blocks, regrouped.blocks);
+    let hocr = result.to_hocr();
+    assert_eq!(hocr, regrouped.to_hocr());
+    assert_eq!(hocr.matches("class=\"ocr_line\"").count(), 7);
+    assert!(hocr.contains(">ceptional<"));
+    assert!(!hocr.contains(">Revenue<"));
+    let json = OcrResult::from_json(&result.to_json()).unwrap();
+    assert_eq!(json.blocks, regrouped.blocks);
+}
+
+#[test]
+fn reordered_and_replaced_lines_are_grouped_afresh() {
+    let result = report().with_blocks();
+
+    // Every line is still grouped once, but the indices point at other lines.
+    let mut reordered = result.clone();
+    reordered.lines.swap(0, 8);
+    let mut replaced = OcrResult::from_json(&result.to_json()).unwrap();
+    replaced.lines[7] = line("Margins rose.", 50.0, 400.0, 200.0);
+
+    for changed in [reordered, replaced] {
+        let regrouped = changed.clone().with_blocks();
+        assert_ne!(changed.blocks, regrouped.blocks);
+        let value = serde_json::to_value(&changed).unwrap();
+        assert_eq!(value, serde_json::to_value(&regrouped).unwrap());
+        assert_eq!(changed.to_hocr(), regrouped.to_hocr());
+    }
+
+    // Blocks grouped by hand are kept.
+    let mut options = BlockOptions::default();
+    options.max_paragraph_gap = 0.0;
+    let mut custom = result.clone();
+    custom.blocks = custom.group_blocks_with(&options);
+    assert_ne!(custom.blocks, result.blocks);
+    let json = OcrResult::from_json(&custom.to_json()).unwrap();
+    assert_eq!(json.blocks, custom.blocks);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{FakeBackend, blank_image, sample_lines};
+use oneocr_rs::{OcrEngine, OcrOptions, OneOcrError, Resolution};
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+#[test]
+fn builder_forwards_options_to_backend() {
+    let backend = FakeBackend::new(sample_lines(), 0.0);
+    let delay_load = backend.delay_load.clone();
+
+    let engine = OcrEngine::builder()
+        .max_recognition_line_count(1000)
+        .resize_resolution(800, 600)
+        .include_word_level_details(true)
+        .use_model_delay_load(true)
+        .backend(backend)
+        .build()
+        .unwrap();
+
+    assert!(delay_load.load(Ordering::SeqCst));
+    assert_eq!(engine.get_max_recognition_line_count().unwrap(), 1000);
+    assert_eq!(
+        engine.options().resize_resolution,
+        Resolution {
+            width: 800,
+            height: 600
+        }
+    );
+    let result = engine.run(blank_image().into()).unwrap();
+    assert!(result.lines[0].words.is_some());
+}
+
+#[test]
+fn out_of_range_options_are_rejected() {
+    let build = |builder: oneocr_rs::OcrEngineBuilder| {
+        builder
+            .backend(FakeBackend::new(Vec::new(), 0.0))
+            .build()
+            .unwrap_err()
+    };
+
+    let err = build(OcrEngine::builder().max_recognition_line_count(1001));
+    assert!(matches!(
+        err,
+        OneOcrError::InvalidMaxRecognitionLineCount(1001)
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Max recognition line count must be between 0 and 1000, got 1001"
+    );
+
+    let err = build(OcrEngine::builder().max_recognition_line_count(-1));
+    assert!(matches!(
+        err,
+        OneOcrError::InvalidMaxRecognitionLineCount(-1)
+    ));
+
+    let err = build(OcrEngine::builder().resize_resolution(1920, 1080));
+    assert!(matches!(
+        err,
+        OneOcrError::InvalidResizeResolution {
+            width: 1920,
+            height: 1080
+        }
+    ));
+
+    let err = build(OcrEngine::builder().resize_resolution(640, 0));
+    assert!(matches!(err, OneOcrError::InvalidResizeResolution { .. }));
+}
+
+#[test]
+fn setters_validate_ranges() {
+    let engine = OcrEngine::builder()
+        .backend(FakeBackend::new(Vec::new(), 0.0))
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        engine.set_max_recognition_line_count(5000),
+        Err(OneOcrError::InvalidMaxRecognitionLineCount(5000))
+    ));
+    assert!(matches!(
+        engine.set_resize_resolution(1153, 768),
+        Err(OneOcrError::InvalidResizeResolution { .. })
+    ));
+    assert_eq!(engine.get_max_recognition_line_count().unwrap(), 100);
+}
+
+#[test]
+fn options_round_trip_through_config_files() {
+    let options: OcrOptions = serde_json::from_str(
+        r#"{
+            "max_recognition_line_count": 250,
+            "use_model_delay_load": true,
+            "model_path": "/opt/oneocr/oneocr.onemodel"
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(options.max_recognition_line_count, 250);
+    assert!(options.use_model_delay_load);
+    assert_eq!(
+        options.model_path,
+        Some(PathBuf::from("/opt/oneocr/oneocr.onemodel"))
+    );
+    // Missing fields take their default value.
+    assert_eq!(options.resize_resolution, Resolution::default());
+    assert!(!options.include_word_level_details);
+
+    let json = serde_json::to_string(&options).unwrap();
+    assert_eq!(serde_json::from_str::<OcrOptions>(&json).unwrap(), options);
+}
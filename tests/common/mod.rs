@@ -0,0 +1,441 @@
+#![allow(dead_code)]
+
+use cfb_mode::Encryptor;
+use cfb_mode::cipher::{AsyncStreamCipher, KeyIvInit};
+use image::{ImageBuffer, Rgba};
+use oneocr_rs::{
+    BoundingBox, Handle, LineStyle, OcrBackend, OcrLine, OcrResult, OcrWord, OneOcrError, Point,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::io::Write;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A scripted word served by [`FakeBackend`].
+#[derive(Debug, Clone)]
+pub struct FakeWord {
+    pub text: String,
+    pub confidence: f32,
+    pub bounding_box: BoundingBox,
+}
+
+/// A scripted line served by [`FakeBackend`].
+#[derive(Debug, Clone)]
+pub struct FakeLine {
+    pub text: String,
+    pub bounding_box: BoundingBox,
+    pub style: (i32, f32),
+    pub words: Vec<FakeWord>,
+}
+
+/// The max recognition line count and resize resolution of a process options object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessOptions {
+    pub max_line_count: i32,
+    pub resize_resolution: (i32, i32),
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        Self {
+            max_line_count: 100,
+            resize_resolution: (1152, 768),
+        }
+    }
+}
+
+/// An in-process backend that returns the same scripted lines for every image,
+/// truncated to the max recognition line count of the call.
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    pub lines: Vec<FakeLine>,
+    pub image_angle: f32,
+    next_handle: AtomicUsize,
+    /// Number of objects created and not yet released.
+    pub live_objects: Arc<AtomicIsize>,
+    /// The max recognition line count and resize resolution of each process options object.
+    process_options: Mutex<HashMap<Handle, ProcessOptions>>,
+    /// The number of lines of each result.
+    results: Mutex<HashMap<Handle, usize>>,
+    /// The process options of every `run_pipeline` call, in order.
+    pub runs: Arc<Mutex<Vec<ProcessOptions>>>,
+    /// What `supports_concurrent_runs` reports.
+    pub concurrent_runs: bool,
+    in_flight: AtomicUsize,
+    /// The largest number of `run_pipeline` calls seen running at once.
+    pub max_in_flight: Arc<AtomicUsize>,
+    /// How long each `run_pipeline` call takes.
+    pub run_delay: std::time::Duration,
+    /// What `get_line_count` reports instead of the number of lines, e.g. a broken count.
+    pub reported_line_count: Option<i64>,
+    /// The last value passed to `set_use_model_delay_load`, shared so tests can read it
+    /// after the backend was moved into an engine.
+    pub delay_load: Arc<AtomicBool>,
+}
+
+impl FakeBackend {
+    pub fn new(lines: Vec<FakeLine>, image_angle: f32) -> Self {
+        Self {
+            lines,
+            image_angle,
+            next_handle: AtomicUsize::new(1),
+            run_delay: std::time::Duration::from_micros(200),
+            ..Default::default()
+        }
+    }
+
+    fn create(&self) -> Handle {
+        self.live_objects.fetch_add(1, Ordering::SeqCst);
+        Handle::from_raw(self.next_handle.fetch_add(1, Ordering::SeqCst))
+    }
+
+    fn release(&self) {
+        self.live_objects.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn with_process_options<T>(
+        &self,
+        handle: Handle,
+        f: impl FnOnce(&mut ProcessOptions) -> T,
+    ) -> Result<T, OneOcrError> {
+        let mut process_options = self.process_options.lock().unwrap();
+        let options = process_options
+            .get_mut(&handle)
+            .ok_or_else(|| OneOcrError::Other(format!("Invalid process options {handle:?}")))?;
+        Ok(f(options))
+    }
+
+    fn line(&self, line: Handle) -> &FakeLine {
+        &self.lines[line.into_raw() - 1]
+    }
+
+    fn word(&self, word: Handle) -> &FakeWord {
+        let raw = word.into_raw();
+        &self.lines[(raw >> 32) - 1].words[(raw & 0xffff_ffff) - 1]
+    }
+}
+
+impl OcrBackend for FakeBackend {
+    fn requires_model_file(&self) -> bool {
+        false
+    }
+
+    fn supports_concurrent_runs(&self) -> bool {
+        self.concurrent_runs
+    }
+
+    fn create_init_options(&self) -> Result<Handle, OneOcrError> {
+        Ok(self.create())
+    }
+
+    fn set_use_model_delay_load(&self, _: Handle, delay_load: bool) -> Result<(), OneOcrError> {
+        self.delay_load.store(delay_load, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn create_pipeline(&self, _: &CStr, _: &CStr, _: Handle) -> Result<Handle, OneOcrError> {
+        Ok(self.create())
+    }
+
+    fn create_process_options(&self) -> Result<Handle, OneOcrError> {
+        let handle = self.create();
+        self.process_options
+            .lock()
+            .unwrap()
+            .insert(handle, ProcessOptions::default());
+        Ok(handle)
+    }
+
+    fn get_max_recognition_line_count(&self, handle: Handle) -> Result<i32, OneOcrError> {
+        self.with_process_options(handle, |options| options.max_line_count)
+    }
+
+    fn set_max_recognition_line_count(
+        &self,
+        handle: Handle,
+        count: i32,
+    ) -> Result<(), OneOcrError> {
+        self.with_process_options(handle, |options| options.max_line_count = count)
+    }
+
+    fn get_resize_resolution(&self, handle: Handle) -> Result<(i64, i64), OneOcrError> {
+        self.with_process_options(handle, |options| {
+            let (width, height) = options.resize_resolution;
+            (width as i64, height as i64)
+        })
+    }
+
+    fn set_resize_resolution(
+        &self,
+        handle: Handle,
+        width: i32,
+        height: i32,
+    ) -> Result<(), OneOcrError> {
+        self.with_process_options(handle, |options| {
+            options.resize_resolution = (width, height)
+        })
+    }
+
+    fn run_pipeline(
+        &self,
+        _: Handle,
+        _: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        process_options: Handle,
+    ) -> Result<Handle, OneOcrError> {
+        let options = self.with_process_options(process_options, |options| *options)?;
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        // Widen the window in which overlapping runs can be observed.
+        std::thread::sleep(self.run_delay);
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.runs.lock().unwrap().push(options);
+        let result = self.create();
+        let line_count = self.lines.len().min(options.max_line_count.max(0) as usize);
+        self.results.lock().unwrap().insert(result, line_count);
+        Ok(result)
+    }
+
+    fn get_image_angle(&self, _: Handle) -> Result<f32, OneOcrError> {
+        Ok(self.image_angle)
+    }
+
+    fn get_line_count(&self, result: Handle) -> Result<i64, OneOcrError> {
+        let line_count = self.results.lock().unwrap()[&result] as i64;
+        Ok(self.reported_line_count.unwrap_or(line_count))
+    }
+
+    fn get_line(&self, _: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        Ok(Handle::from_raw(index as usize + 1))
+    }
+
+    fn get_line_content(&self, line: Handle) -> Result<String, OneOcrError> {
+        Ok(self.line(line).text.clone())
+    }
+
+    fn get_line_bounding_box(&self, line: Handle) -> Result<BoundingBox, OneOcrError> {
+        Ok(self.line(line).bounding_box)
+    }
+
+    fn get_line_style(&self, line: Handle) -> Result<(i32, f32), OneOcrError> {
+        Ok(self.line(line).style)
+    }
+
+    fn get_line_word_count(&self, line: Handle) -> Result<i64, OneOcrError> {
+        Ok(self.line(line).words.len() as i64)
+    }
+
+    fn get_word(&self, line: Handle, index: i64) -> Result<Handle, OneOcrError> {
+        Ok(Handle::from_raw(
+            (line.into_raw() << 32) | (index as usize + 1),
+        ))
+    }
+
+    fn get_word_content(&self, word: Handle) -> Result<String, OneOcrError> {
+        Ok(self.word(word).text.clone())
+    }
+
+    fn get_word_bounding_box(&self, word: Handle) -> Result<BoundingBox, OneOcrError> {
+        Ok(self.word(word).bounding_box)
+    }
+
+    fn get_word_confidence(&self, word: Handle) -> Result<f32, OneOcrError> {
+        Ok(self.word(word).confidence)
+    }
+
+    fn release_result(&self, result: Handle) {
+        self.results.lock().unwrap().remove(&result);
+        self.release();
+    }
+
+    fn release_init_options(&self, _: Handle) {
+        self.release();
+    }
+
+    fn release_pipeline(&self, _: Handle) {
+        self.release();
+    }
+
+    fn release_process_options(&self, process_options: Handle) {
+        self.process_options
+            .lock()
+            .unwrap()
+            .remove(&process_options);
+        self.release();
+    }
+}
+
+/// Creates an axis-aligned bounding box.
+pub fn rect(x: f32, y: f32, width: f32, height: f32) -> BoundingBox {
+    BoundingBox {
+        top_left: Point { x, y },
+        top_right: Point { x: x + width, y },
+        bottom_right: Point {
+            x: x + width,
+            y: y + height,
+        },
+        bottom_left: Point { x, y: y + height },
+    }
+}
+
+/// A line of text 20 pixels high, with one word per space-separated part.
+pub fn line(text: &str, left: f32, top: f32, width: f32) -> OcrLine {
+    let parts: Vec<&str> = text.split(' ').collect();
+    let word_width = width / parts.len() as f32;
+    OcrLine {
+        text: text.to_string(),
+        bounding_box: rect(left, top, width, 20.0),
+        words: Some(
+            parts
+                .iter()
+                .enumerate()
+                .map(|(i, part)| OcrWord {
+                    text: part.to_string(),
+                    confidence: 0.9,
+                    bounding_box: rect(left + i as f32 * word_width, top, word_width, 20.0),
+                })
+                .collect(),
+        ),
+        style: Default::default(),
+        style_confidence: 1.0,
+    }
+}
+
+/// A small two-line script with word-level details.
+pub fn sample_lines() -> Vec<FakeLine> {
+    let word = |text: &str, x: f32, y: f32| FakeWord {
+        text: text.to_string(),
+        confidence: 0.9,
+        bounding_box: rect(x, y, 40.0, 20.0),
+    };
+    vec![
+        FakeLine {
+            text: "Hello world".to_string(),
+            bounding_box: rect(10.0, 10.0, 90.0, 20.0),
+            style: (1, 0.95),
+            words: vec![word("Hello", 10.0, 10.0), word("world", 60.0, 10.0)],
+        },
+        FakeLine {
+            text: "Second line".to_string(),
+            bounding_box: rect(10.0, 40.0, 90.0, 20.0),
+            style: (0, 0.2),
+            words: vec![word("Second", 10.0, 40.0), word("line", 60.0, 40.0)],
+        },
+    ]
+}
+
+/// A blank RGBA image to feed the engine.
+pub fn blank_image() -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    ImageBuffer::from_pixel(64, 32, Rgba([255, 255, 255, 255]))
+}
+
+/// The key of the model shipped with the Snipping Tool.
+pub const MODEL_KEY: &[u8; 32] = br#"kj)TGtrK>f]b[Piow.gU+nC@s""""""4"#;
+
+fn encrypt_block(payload: &[u8], key: &[u8; 32]) -> Vec<u8> {
+    const IV: &[u8; 16] = b"Copyright @ OneO";
+    const MAGIC: [u8; 8] = [0x4a, 0x1a, 0x08, 0x2b, 0x25, 0x00, 0x00, 0x00];
+
+    let mut block = MAGIC.to_vec();
+    block.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    block.extend_from_slice(payload);
+    Encryptor::<aes::Aes256>::new(key.into(), IV.into()).encrypt(&mut block);
+    block
+}
+
+/// Builds a `.onemodel` container with the documented layout, encrypted with [`MODEL_KEY`].
+pub fn onemodel_container(header: &[u8], entries: &[&[u8]]) -> Vec<u8> {
+    let header = encrypt_block(header, MODEL_KEY);
+    let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+    bytes.extend_from_slice(&header);
+    for (i, entry) in entries.iter().enumerate() {
+        let salt = [i as u8; 16];
+        let key: [u8; 32] = Sha256::new()
+            .chain_update(MODEL_KEY)
+            .chain_update(salt)
+            .finalize()
+            .into();
+        let block = encrypt_block(entry, &key);
+        bytes.extend_from_slice(&(block.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&salt);
+        bytes.extend_from_slice(&block);
+    }
+    bytes
+}
+
+/// A result with a worded line containing markup characters and a line without words,
+/// on a 200×100 image.
+pub fn exported_result() -> OcrResult {
+    let mut handwritten = OcrLine {
+        text: "Total 42".to_string(),
+        bounding_box: rect(10.4, 60.0, 90.0, 20.5),
+        words: None,
+        style: LineStyle::Handwritten,
+        style_confidence: 0.8,
+    };
+    handwritten.bounding_box.top_right.y -= 2.0;
+    OcrResult::new(
+        vec![
+            OcrLine {
+                text: "Fish & <Chips>".to_string(),
+                bounding_box: rect(10.0, 10.0, 150.0, 20.0),
+                words: Some(vec![
+                    OcrWord {
+                        text: "Fish".to_string(),
+                        confidence: 0.987,
+                        bounding_box: rect(10.0, 10.0, 40.0, 20.0),
+                    },
+                    OcrWord {
+                        text: "&".to_string(),
+                        confidence: 0.5,
+                        bounding_box: rect(55.0, 10.0, 10.0, 20.0),
+                    },
+                    OcrWord {
+                        text: "<Chips>".to_string(),
+                        confidence: 0.9,
+                        bounding_box: rect(70.0, 10.0, 90.0, 20.0),
+                    },
+                ]),
+                style: LineStyle::Printed,
+                style_confidence: 0.95,
+            },
+            handwritten,
+        ],
+        1.5,
+    )
+    .with_image_size(200, 100)
+}
+
+/// [`exported_result`] with the handwritten line moved far below, into a block of its own.
+pub fn two_block_result() -> OcrResult {
+    let mut result = exported_result().with_image_size(200, 400);
+    result.lines[1].bounding_box = rect(10.0, 300.0, 90.0, 20.0);
+    result
+}
+
+/// Validates `xml` with `xmllint` against an official schema in `tests/fixtures`, downloaded
+/// by `tests/fixtures/fetch-schemas.sh`. Fails if the schema or `xmllint` is missing.
+pub fn assert_valid_xml(xml: &str, schema: &str) {
+    let fixtures = format!("{}/tests/fixtures", env!("CARGO_MANIFEST_DIR"));
+    let schema = format!("{fixtures}/{schema}");
+    assert!(
+        std::path::Path::new(&schema).exists(),
+        "{schema} is missing, run tests/fixtures/fetch-schemas.sh"
+    );
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(xml.as_bytes()).unwrap();
+
+    let output = Command::new("xmllint")
+        .args(["--noout", "--nonet", "--schema", &schema])
+        .arg(file.path())
+        .env("XML_CATALOG_FILES", format!("{fixtures}/catalog.xml"))
+        .output()
+        .unwrap_or_else(|e| panic!("xmllint is needed to validate XML: {e}"));
+    assert!(
+        output.status.success(),
+        "{}\n{xml}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
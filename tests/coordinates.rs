@@ -0,0 +1,156 @@
+mod common;
+
+use common::{exported_result, rect};
+use oneocr_rs::{Affine, BoundingBox, OcrResult, OneOcrError, Point, PreprocessingStep};
+
+fn assert_boxes_close(actual: &OcrResult, expected: &OcrResult) {
+    let boxes = |result: &OcrResult| -> Vec<BoundingBox> {
+        result
+            .lines
+            .iter()
+            .flat_map(|line| {
+                std::iter::once(line.bounding_box)
+                    .chain(line.words.iter().flatten().map(|word| word.bounding_box))
+            })
+            .collect()
+    };
+    let (actual_boxes, expected_boxes) = (boxes(actual), boxes(expected));
+    assert_eq!(actual_boxes.len(), expected_boxes.len());
+    for (a, e) in actual_boxes.iter().zip(&expected_boxes) {
+        for (p, q) in a.corners().into_iter().zip(e.corners()) {
+            assert!(p.distance(q) < 1e-3, "{a} != {e}");
+        }
+    }
+}
+
+#[test]
+fn preprocessing_steps_map_back_to_the_original_image() {
+    // A 400x200 tile at (1000, 600) of a 2000x1000 scan, recognized at half size.
+    let mut result = exported_result()
+        .with_preprocessing(PreprocessingStep::crop(1000, 600, 2000, 1000))
+        .with_preprocessing(PreprocessingStep::resize(400, 200, 200, 100).unwrap());
+    result.lines.truncate(1);
+
+    let original = result.to_original_coordinates().unwrap();
+
+    assert_eq!((original.image_width, original.image_height), (2000, 1000));
+    assert!(original.preprocessing.is_empty());
+    assert_eq!(
+        original.lines[0].bounding_box,
+        rect(1020.0, 620.0, 300.0, 40.0)
+    );
+    let words = original.lines[0].words.as_ref().unwrap();
+    assert_eq!(words[0].bounding_box, rect(1020.0, 620.0, 80.0, 40.0));
+
+    // The chain is stored with the result.
+    let reloaded = OcrResult::from_json(&result.to_json()).unwrap();
+    assert_eq!(reloaded.preprocessing, result.preprocessing);
+    assert_eq!(reloaded.to_original_coordinates().unwrap(), original);
+}
+
+#[test]
+fn transformed_results_map_back() {
+    let result = exported_result();
+    let rotation = Affine::rotate_around(
+        90.0,
+        BoundingBox::from_rect(0.0, 0.0, 200.0, 100.0).centroid(),
+    );
+
+    let rotated = result.transform(&rotation);
+
+    assert_eq!((rotated.image_width, rotated.image_height), (100, 200));
+    assert!((rotated.lines[0].bounding_box.angle() - 90.0).abs() < 1e-3);
+    assert_eq!(rotated.preprocessing.len(), 1);
+    let restored = rotated.to_original_coordinates().unwrap();
+    assert_boxes_close(&restored, &result);
+    assert_eq!((restored.image_width, restored.image_height), (200, 100));
+
+    let collapsed = result.transform(&Affine::scale(0.0, 1.0));
+    let err = collapsed.to_original_coordinates().unwrap_err();
+    assert!(matches!(err, OneOcrError::InvalidTransform(_)), "{err}");
+}
+
+#[test]
+fn normalized_coordinates_are_resolution_independent() {
+    let result = exported_result();
+
+    let normalized = result.normalized().unwrap();
+
+    let line = normalized.lines[0].bounding_box.bounds();
+    for (actual, expected) in [
+        (line.x, 0.05),
+        (line.y, 0.1),
+        (line.width, 0.75),
+        (line.height, 0.2),
+    ] {
+        assert!((actual - expected).abs() < 1e-6, "{line:?}");
+    }
+    assert_eq!((normalized.image_width, normalized.image_height), (1, 1));
+    assert_boxes_close(&normalized.to_original_coordinates().unwrap(), &result);
+
+    let doubled = normalized.denormalized(400, 200);
+    assert_eq!((doubled.image_width, doubled.image_height), (400, 200));
+    assert_boxes_close(&doubled, &result.transform(&Affine::scale(2.0, 2.0)));
+    assert_boxes_close(&doubled.to_original_coordinates().unwrap(), &result);
+
+    let err = OcrResult::new(Vec::new(), 0.0).normalized().unwrap_err();
+    assert!(matches!(err, OneOcrError::InvalidTransform(_)), "{err}");
+}
+
+#[test]
+fn normalized_coordinates_round_trip_at_page_sizes() {
+    // An A4 page scanned at 300 DPI and a 12 megapixel photo.
+    for (width, height) in [(2480, 3508), (4000, 3000)] {
+        let mut page = exported_result()
+            .transform(&Affine::scale(width as f32 / 200.0, height as f32 / 100.0));
+        page.preprocessing.clear();
+        assert_eq!((page.image_width, page.image_height), (width, height));
+
+        let normalized = page.normalized().unwrap();
+
+        assert_boxes_close(&normalized.to_original_coordinates().unwrap(), &page);
+        assert_boxes_close(&normalized.denormalized(width, height), &page);
+    }
+}
+
+#[test]
+fn transforms_of_the_boxes_are_undone_with_the_preprocessing() {
+    // Recognized on a 200x100 tile at (1000, 600) of a scan.
+    let result =
+        exported_result().with_preprocessing(PreprocessingStep::crop(1000, 600, 2000, 1000));
+    let in_scan = result.to_original_coordinates().unwrap();
+
+    let shifted = result.transform(&Affine::translate(5.0, 5.0));
+
+    assert_eq!(shifted.preprocessing.len(), 2);
+    // The original coordinates are still the pixels of the scan, not the shifted boxes.
+    assert_boxes_close(&shifted.to_original_coordinates().unwrap(), &in_scan);
+    let mut kept = shifted.clone();
+    kept.preprocessing.clear();
+    assert_boxes_close(&kept.to_original_coordinates().unwrap(), &shifted);
+}
+
+#[test]
+fn rotations_and_resizes_of_the_image() {
+    // Recognized after `image::imageops::rotate90` of the 100x200 original.
+    let rotated = exported_result().with_preprocessing(PreprocessingStep::rotate(90.0, 100, 200));
+    // The top left corner of the original becomes the top right corner.
+    let corner = rotated.preprocessing[0]
+        .transform
+        .apply(Point::new(0.0, 0.0));
+    assert!(corner.distance(Point::new(200.0, 0.0)) < 1e-3, "{corner}");
+
+    let original = rotated.to_original_coordinates().unwrap();
+
+    assert_eq!((original.image_width, original.image_height), (100, 200));
+    // The first line reads upwards from near the bottom left corner of the original.
+    let line = original.lines[0].bounding_box;
+    assert!(
+        line.top_left.distance(Point::new(10.0, 190.0)) < 1e-3,
+        "{line}"
+    );
+    assert!((line.angle() + 90.0).abs() < 1e-3, "{line}");
+
+    let err = PreprocessingStep::resize(0, 200, 200, 100).unwrap_err();
+    assert!(matches!(err, OneOcrError::InvalidTransform(_)), "{err}");
+}
@@ -0,0 +1,291 @@
+#![cfg(feature = "cpu")]
+
+mod common;
+
+use common::onemodel_container;
+use image::{ImageBuffer, Rgba, RgbaImage};
+use oneocr_rs::{CpuBackend, CpuOptions, LineStyle, OcrEngine, OcrOptions, OneOcrError};
+use std::path::{Path, PathBuf};
+
+/// A minimal protobuf writer, enough to build ONNX `ModelProto`s.
+#[derive(Default)]
+struct Proto(Vec<u8>);
+
+impl Proto {
+    fn varint(mut self, mut value: u64) -> Self {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.0.push(byte);
+                return self;
+            }
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    fn int(self, field: u64, value: i64) -> Self {
+        self.varint(field << 3).varint(value as u64)
+    }
+
+    fn bytes(self, field: u64, bytes: &[u8]) -> Self {
+        let mut proto = self.varint((field << 3) | 2).varint(bytes.len() as u64);
+        proto.0.extend_from_slice(bytes);
+        proto
+    }
+
+    fn string(self, field: u64, value: &str) -> Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    fn message(self, field: u64, message: Proto) -> Self {
+        self.bytes(field, &message.0)
+    }
+}
+
+/// A float tensor input or output. `None` dimensions are symbolic, named after their axis.
+fn value_info(name: &str, dims: &[Option<i64>]) -> Proto {
+    let shape = dims
+        .iter()
+        .enumerate()
+        .fold(Proto::default(), |shape, (i, dim)| {
+            let dim = match dim {
+                Some(size) => Proto::default().int(1, *size),
+                None => Proto::default().string(2, &format!("dim{i}")),
+            };
+            shape.message(1, dim)
+        });
+    let tensor = Proto::default().int(1, 1).message(2, shape);
+    Proto::default()
+        .string(1, name)
+        .message(2, Proto::default().message(1, tensor))
+}
+
+fn node(op_type: &str, inputs: &[&str], output: &str, attributes: Vec<Proto>) -> Proto {
+    let node = inputs
+        .iter()
+        .fold(Proto::default(), |node, input| node.string(1, input))
+        .string(2, output)
+        .string(4, op_type);
+    attributes
+        .into_iter()
+        .fold(node, |node, attribute| node.message(5, attribute))
+}
+
+fn ints_attribute(name: &str, values: &[i64]) -> Proto {
+    values
+        .iter()
+        .fold(Proto::default().string(1, name), |attribute, value| {
+            attribute.int(8, *value)
+        })
+        .int(20, 7)
+}
+
+fn int_attribute(name: &str, value: i64) -> Proto {
+    Proto::default().string(1, name).int(3, value).int(20, 2)
+}
+
+fn float_tensor(name: &str, dims: &[i64], values: &[f32]) -> Proto {
+    let tensor = dims
+        .iter()
+        .fold(Proto::default(), |tensor, dim| tensor.int(1, *dim))
+        .int(2, 1);
+    let data: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+    tensor.string(8, name).bytes(9, &data)
+}
+
+fn onnx_model(nodes: Vec<Proto>, initializers: Vec<Proto>, input: Proto, output: Proto) -> Vec<u8> {
+    let graph = nodes
+        .into_iter()
+        .fold(Proto::default(), |graph, node| graph.message(1, node))
+        .string(2, "graph");
+    let graph = initializers
+        .into_iter()
+        .fold(graph, |graph, tensor| graph.message(5, tensor))
+        .message(11, input)
+        .message(12, output);
+    Proto::default()
+        .int(1, 8)
+        .string(2, "pytorch")
+        .message(7, graph)
+        .message(8, Proto::default().int(2, 13))
+        .0
+}
+
+/// A detector whose text probability is the darkness of a pixel, in `-1.0..=1.0` once the input
+/// is normalized with a mean and standard deviation of 0.5.
+fn detector() -> Vec<u8> {
+    onnx_model(
+        vec![
+            node("Neg", &["image"], "negated", vec![]),
+            node(
+                "ReduceMean",
+                &["negated"],
+                "probabilities",
+                vec![ints_attribute("axes", &[1]), int_attribute("keepdims", 1)],
+            ),
+        ],
+        vec![],
+        value_info("image", &[Some(1), Some(3), None, None]),
+        value_info("probabilities", &[Some(1), Some(1), None, None]),
+    )
+}
+
+const CHARACTER_MAP: &str = " \ne\nh\ni\nr\nt\n";
+
+/// A recognizer that reads "hi there" on every line, with the classes of [`CHARACTER_MAP`].
+/// Its input width is fixed if `width` is given.
+fn recognizer(width: Option<i64>) -> Vec<u8> {
+    // h h i ␣ t h e r <blank> e
+    let steps = [3, 3, 4, 1, 6, 3, 2, 5, 0, 2];
+    let logits: Vec<f32> = steps
+        .iter()
+        .flat_map(|class| (0..7).map(move |i| if i == *class { 5.0 } else { 0.0 }))
+        .collect();
+    onnx_model(
+        vec![
+            node(
+                "ReduceMean",
+                &["image"],
+                "mean",
+                vec![
+                    ints_attribute("axes", &[1, 2, 3]),
+                    int_attribute("keepdims", 0),
+                ],
+            ),
+            node("Mul", &["mean", "zero"], "nothing", vec![]),
+            node("Add", &["nothing", "logits"], "output", vec![]),
+        ],
+        vec![
+            float_tensor("zero", &[], &[0.0]),
+            float_tensor("logits", &[1, 10, 7], &logits),
+        ],
+        value_info("image", &[Some(1), Some(3), None, width]),
+        value_info("output", &[Some(1), Some(10), Some(7)]),
+    )
+}
+
+fn write_model(dir: &Path, entries: &[&[u8]]) -> PathBuf {
+    let path = dir.join("oneocr.onemodel");
+    std::fs::write(&path, onemodel_container(b"", entries)).unwrap();
+    path
+}
+
+fn engine(model_path: PathBuf, mut ocr_options: OcrOptions) -> Result<OcrEngine, OneOcrError> {
+    let mut cpu_options = CpuOptions::default();
+    cpu_options.detector_mean = [0.5; 3];
+    cpu_options.detector_std = [0.5; 3];
+    let backend = CpuBackend::with_options(cpu_options);
+    ocr_options.model_path = Some(model_path);
+    ocr_options.include_word_level_details = true;
+    OcrEngine::new_with_backend(backend, ocr_options)
+}
+
+/// Two lines of black blocks on white; the first line is made of two blocks.
+fn two_line_image() -> RgbaImage {
+    let mut image = ImageBuffer::from_pixel(200, 100, Rgba([255, 255, 255, 255]));
+    for (left, top, right, bottom) in [(20, 20, 70, 35), (80, 20, 150, 35), (20, 60, 100, 75)] {
+        for y in top..bottom {
+            for x in left..right {
+                image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+    image
+}
+
+#[test]
+fn lines_are_detected_grouped_and_decoded() {
+    let dir = tempfile::tempdir().unwrap();
+    let detector = detector();
+    let recognizer = recognizer(None);
+    let model_path = write_model(
+        dir.path(),
+        &[&detector, &recognizer, CHARACTER_MAP.as_bytes()],
+    );
+    let engine = engine(model_path, OcrOptions::default()).unwrap();
+
+    let result = engine.run(two_line_image().into()).unwrap();
+
+    assert_eq!(result.image_angle, 0.0);
+    assert_eq!(result.lines.len(), 2);
+    let first = &result.lines[0].bounding_box;
+    assert!(first.top_left.x < 20.0 && first.top_left.y < 20.0);
+    assert!(first.bottom_right.x > 150.0 && first.bottom_right.y > 35.0);
+    assert!(first.bottom_right.y < result.lines[1].bounding_box.top_left.y);
+    for line in &result.lines {
+        assert_eq!(line.text, "hi there");
+        let words = line.words.as_ref().unwrap();
+        let texts: Vec<_> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(texts, ["hi", "there"]);
+        assert!(words[0].bounding_box.top_right.x <= words[1].bounding_box.top_left.x);
+        assert!(words.iter().all(|word| word.confidence > 0.9));
+        assert_eq!(
+            (line.style, line.style_confidence),
+            (LineStyle::Printed, 0.0)
+        );
+    }
+    assert_eq!(result.lines_with_style_confidence(0.9).count(), 0);
+}
+
+#[test]
+fn line_count_is_limited_by_the_process_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let detector = detector();
+    let recognizer = recognizer(None);
+    let model_path = write_model(
+        dir.path(),
+        &[&detector, &recognizer, CHARACTER_MAP.as_bytes()],
+    );
+    let mut options = OcrOptions::default();
+    options.max_recognition_line_count = 1;
+    let engine = engine(model_path, options).unwrap();
+
+    let result = engine.run(two_line_image().into()).unwrap();
+
+    assert_eq!(result.lines.len(), 1);
+}
+
+#[test]
+fn words_are_placed_along_lines_squeezed_into_a_fixed_width() {
+    let dir = tempfile::tempdir().unwrap();
+    let detector = detector();
+    // Far narrower than the lines resized to the recognizer height.
+    let recognizer = recognizer(Some(64));
+    let model_path = write_model(
+        dir.path(),
+        &[&detector, &recognizer, CHARACTER_MAP.as_bytes()],
+    );
+    let engine = engine(model_path, OcrOptions::default()).unwrap();
+
+    let result = engine.run(two_line_image().into()).unwrap();
+
+    assert_eq!(result.lines.len(), 2);
+    for line in &result.lines {
+        let (left, right) = (line.bounding_box.top_left.x, line.bounding_box.top_right.x);
+        let words = line.words.as_ref().unwrap();
+        // "there" starts at time step 4 and ends with the last of the 10 steps.
+        let there = &words[1].bounding_box;
+        let expected_left = left + 0.4 * (right - left);
+        assert!(
+            (there.top_left.x - expected_left).abs() <= 1.0,
+            "{} != {expected_left}",
+            there.top_left.x
+        );
+        assert!((there.top_right.x - right).abs() <= 1.0, "{there}");
+    }
+}
+
+#[test]
+fn model_without_recognizer_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let detector = detector();
+    let model_path = write_model(dir.path(), &[&detector, CHARACTER_MAP.as_bytes()]);
+
+    let err = engine(model_path, OcrOptions::default()).unwrap_err();
+
+    assert!(matches!(err, OneOcrError::InvalidModelFormat(_)), "{err}");
+}
@@ -0,0 +1,13 @@
+#!/bin/sh
+# Downloads the official XML schemas the exports are validated against by the ignored tests:
+#   sh tests/fixtures/fetch-schemas.sh && cargo test --test alto --test page_xml -- --ignored
+set -eu
+cd "$(dirname "$0")"
+fetch() {
+    curl --fail --silent --show-error --location --output "$1" "$2"
+}
+fetch alto-4-4.xsd https://www.loc.gov/standards/alto/v4/alto-4-4.xsd
+# Imported by the ALTO schema, and found through catalog.xml.
+fetch xlink.xsd https://www.loc.gov/standards/xlink/xlink.xsd
+fetch pagecontent-2019-07-15.xsd \
+    https://www.primaresearch.org/schema/PAGE/gts/pagecontent/2019-07-15/pagecontent.xsd
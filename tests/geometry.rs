@@ -0,0 +1,173 @@
+use oneocr_rs::{Affine, BoundingBox, Point, Rect};
+use proptest::prelude::*;
+
+fn close(a: f32, b: f32, scale: f32) -> bool {
+    (a - b).abs() <= 1e-3 * (1.0 + scale)
+}
+
+fn close_points(p: Point, q: Point, scale: f32) -> bool {
+    close(p.x, q.x, scale) && close(p.y, q.y, scale)
+}
+
+prop_compose! {
+    fn rects()(x in -500.0f32..500.0, y in -500.0f32..500.0, w in 1.0f32..300.0, h in 1.0f32..100.0) -> Rect {
+        Rect::new(x, y, w, h)
+    }
+}
+
+prop_compose! {
+    /// A text box of any size, position and rotation.
+    fn boxes()(rect in rects(), degrees in -180.0f32..180.0) -> BoundingBox {
+        BoundingBox::from(rect).rotate(degrees)
+    }
+}
+
+prop_compose! {
+    fn transforms()(
+        dx in -100.0f32..100.0,
+        dy in -100.0f32..100.0,
+        // Scales from 1e-4 to 1e4, stretched by up to four times along one axis.
+        exponent in -4.0f32..4.0,
+        stretch in 0.25f32..4.0,
+        degrees in -180.0f32..180.0,
+    ) -> Affine {
+        let scale = 10f32.powf(exponent);
+        Affine::scale(scale, scale * stretch).then(&Affine::rotate(degrees)).then(&Affine::translate(dx, dy))
+    }
+}
+
+proptest! {
+    #[test]
+    fn rects_convert_to_boxes_and_back(rect in rects()) {
+        let bounding_box = BoundingBox::from(rect);
+
+        let bounds = bounding_box.bounds();
+        prop_assert!(close(bounds.x, rect.x, 500.0) && close(bounds.y, rect.y, 500.0));
+        prop_assert!(close(bounds.width, rect.width, 500.0));
+        prop_assert!(close(bounds.height, rect.height, 500.0));
+        prop_assert!(close(bounding_box.area(), rect.width * rect.height, rect.width * rect.height));
+        let tuple: (f32, f32, f32, f32) = rect.into();
+        prop_assert_eq!(Rect::from(tuple), rect);
+    }
+
+    #[test]
+    fn rotation_keeps_size_and_centroid(rect in rects(), degrees in -179.0f32..179.0) {
+        let upright = BoundingBox::from(rect);
+
+        let rotated = upright.rotate(degrees);
+
+        let scale = rect.width.max(rect.height);
+        prop_assert!(close(rotated.area(), upright.area(), upright.area()));
+        prop_assert!(close(rotated.width(), rect.width, scale));
+        prop_assert!(close(rotated.height(), rect.height, scale));
+        prop_assert!(close(rotated.angle(), degrees, 180.0));
+        prop_assert!(close_points(rotated.centroid(), upright.centroid(), 500.0));
+    }
+
+    #[test]
+    fn boxes_contain_their_centroid_and_corners(bounding_box in boxes()) {
+        let bounds = bounding_box.bounds();
+
+        prop_assert!(bounding_box.contains(bounding_box.centroid()));
+        for corner in bounding_box.corners() {
+            prop_assert!(bounding_box.contains(corner));
+        }
+        prop_assert!(!bounding_box.contains(Point::new(bounds.right() + 1.0, bounds.y)));
+    }
+
+    #[test]
+    fn iou_is_symmetric_and_bounded(a in boxes(), b in boxes()) {
+        let iou = a.iou(&b);
+
+        prop_assert!((0.0..=1.0).contains(&iou));
+        prop_assert!(close(iou, b.iou(&a), 1.0));
+        prop_assert!(a.intersection_area(&b) <= a.area().min(b.area()) * 1.001 + 1e-3);
+        prop_assert!(close(a.iou(&a), 1.0, 1.0));
+    }
+
+    #[test]
+    fn intersections_lie_in_both_boxes(a in boxes(), b in boxes()) {
+        for point in a.intersection(&b) {
+            prop_assert!(a.contains(point) && b.contains(point));
+        }
+    }
+
+    #[test]
+    fn unions_and_merges_enclose_both_boxes(a in boxes(), b in boxes()) {
+        let union = a.union(&b);
+        let merged = a.merge(&b);
+
+        for corner in a.corners().into_iter().chain(b.corners()) {
+            prop_assert!(union.contains(corner));
+            prop_assert!(merged.contains(corner));
+        }
+        // -180 and 180 degrees are the same angle.
+        prop_assert!(close(merged.angle(), a.angle(), 180.0) || close(merged.angle().abs(), 180.0, 180.0));
+        let scale = a.width().max(a.height());
+        prop_assert!(close(a.merge(&a).area(), a.area(), scale * scale));
+    }
+
+    #[test]
+    fn transforms_are_undone_by_their_inverse(transform in transforms(), rect in rects()) {
+        let bounding_box = BoundingBox::from(rect);
+        let inverse = transform.inverse().unwrap();
+
+        let restored = bounding_box.transform(&transform).transform(&inverse);
+
+        for (p, q) in restored.corners().into_iter().zip(bounding_box.corners()) {
+            prop_assert!(close_points(p, q, 1000.0), "{} != {}", p, q);
+        }
+        prop_assert!(close_points(transform.then(&inverse).apply(Point::new(3.0, 4.0)), Point::new(3.0, 4.0), 100.0));
+    }
+}
+
+#[test]
+fn overlapping_squares() {
+    let a = BoundingBox::from_rect(0.0, 0.0, 10.0, 10.0);
+    let b = a.translate(5.0, 0.0);
+
+    assert_eq!(a.intersection_area(&b), 50.0);
+    assert!((a.iou(&b) - 1.0 / 3.0).abs() < 1e-6);
+    assert_eq!(a.union(&b).bounds(), Rect::new(0.0, 0.0, 15.0, 10.0));
+    assert_eq!(a.iou(&a.translate(20.0, 0.0)), 0.0);
+    assert!(a.intersection(&a.translate(20.0, 0.0)).is_empty());
+
+    // A square turned by 45 degrees around the same center cuts off the corners of the other.
+    let diamond = a.rotate(45.0);
+    let expected = 100.0 * (2.0 * 2f32.sqrt() - 2.0);
+    assert!((a.intersection_area(&diamond) - expected).abs() < 1e-2);
+}
+
+#[test]
+fn baseline_measurements() {
+    let line = BoundingBox::from_corners([
+        Point::new(0.0, 0.0),
+        Point::new(40.0, 30.0),
+        Point::new(34.0, 38.0),
+        Point::new(-6.0, 8.0),
+    ]);
+
+    assert!((line.angle() - 36.869_9).abs() < 1e-3);
+    assert!((line.width() - 50.0).abs() < 1e-4);
+    assert!((line.height() - 10.0).abs() < 1e-4);
+    assert_eq!(line.centroid(), Point::new(17.0, 19.0));
+    assert_eq!(line.bounds(), Rect::new(-6.0, 0.0, 46.0, 38.0));
+    assert_eq!(line.scale(2.0, 0.5).bottom_right, Point::new(68.0, 19.0));
+    assert!(Affine::scale(0.0, 1.0).inverse().is_none());
+    assert!(Affine::scale(f32::INFINITY, 1.0).inverse().is_none());
+    let collapsing = Affine {
+        a: 1.0,
+        b: 2.0,
+        c: 2.0,
+        d: 4.0,
+        e: 0.0,
+        f: 0.0,
+    };
+    assert!(collapsing.inverse().is_none());
+    let tiny = Affine::scale(1e-4, 1e-4).inverse().unwrap();
+    assert!(close_points(
+        tiny.apply(Point::new(1e-4, 2e-4)),
+        Point::new(1.0, 2.0),
+        0.0
+    ));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::exported_result;
+use oneocr_rs::OcrResult;
+
+#[test]
+fn hocr_has_page_lines_and_words() {
+    let hocr = exported_result().to_hocr();
+
+    let document = roxmltree::Document::parse_with_options(
+        &hocr,
+        roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let by_class = |class: &str| {
+        document
+            .descendants()
+            .filter(|node| node.attribute("class") == Some(class))
+            .collect::<Vec<_>>()
+    };
+
+    let pages = by_class("ocr_page");
+    assert_eq!(pages.len(), 1);
+    assert_eq!(
+        pages[0].attribute("title"),
+        Some("bbox 0 0 200 100; ppageno 0; textangle 1.5")
+    );
+
+    // Both lines share a block, but the gap between them separates two paragraphs.
+    let blocks = by_class("ocr_carea");
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].attribute("title"), Some("bbox 10 10 160 81"));
+    let paragraphs = by_class("ocr_par");
+    assert_eq!(paragraphs.len(), 2);
+    assert!(
+        paragraphs
+            .iter()
+            .all(|paragraph| paragraph.parent() == Some(blocks[0]))
+    );
+
+    let lines = by_class("ocr_line");
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].parent(), Some(paragraphs[1]));
+    assert_eq!(lines[0].attribute("title"), Some("bbox 10 10 160 30"));
+    // The box of the second line is skewed: its bounds enclose every corner.
+    assert_eq!(lines[1].attribute("title"), Some("bbox 10 58 101 81"));
+    assert_eq!(lines[1].text(), Some("Total 42"));
+
+    let words = by_class("ocrx_word");
+    let texts: Vec<_> = words.iter().map(|word| word.text().unwrap()).collect();
+    assert_eq!(texts, ["Fish", "&", "<Chips>"]);
+    assert_eq!(
+        words[0].attribute("title"),
+        Some("bbox 10 10 50 30; x_wconf 99")
+    );
+    assert_eq!(words[1].attribute("id"), Some("word_1_1_2"));
+    assert!(hocr.contains("&lt;Chips&gt;"));
+}
+
+#[test]
+fn hocr_page_falls_back_to_the_extent_of_the_lines() {
+    let mut result = exported_result();
+    result.image_width = 0;
+
+    let hocr = result.to_hocr();
+
+    assert!(hocr.contains("title=\"bbox 0 0 160 81; ppageno 0; textangle 1.5\""));
+    assert!(
+        OcrResult::new(Vec::new(), 0.0)
+            .to_hocr()
+            .contains("bbox 0 0 0 0")
+    );
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::line;
+use oneocr_rs::OcrResult;
+
+#[test]
+fn table_columns_stay_aligned() {
+    // A price list whose cells are recognized as separate lines, out of reading order and
+    // slightly off the row baselines.
+    let result = OcrResult::new(
+        vec![
+            line("Price", 200.0, 100.0, 50.0),
+            line("Item", 100.0, 102.0, 40.0),
+            line("Qty", 300.0, 99.0, 30.0),
+            line("Fish", 100.0, 125.0, 40.0),
+            line("4.50", 200.0, 126.0, 40.0),
+            line("2", 300.0, 124.0, 10.0),
+            line("Chips", 100.0, 150.0, 50.0),
+            line("12.00", 200.0, 151.0, 50.0),
+            line("10", 300.0, 149.0, 20.0),
+        ],
+        0.0,
+    );
+
+    assert_eq!(
+        result.to_layout_text(),
+        concat!(
+            "Item      Price     Qty\n",
+            "Fish      4.50      2\n",
+            "Chips     12.00     10\n",
+        )
+    );
+}
+
+#[test]
+fn gaps_become_blank_rows_and_overlaps_a_space() {
+    let result = OcrResult::new(
+        vec![
+            line("Title", 120.0, 10.0, 50.0),
+            // Two lines overlapping horizontally on the same row.
+            line("left part", 100.0, 70.0, 90.0),
+            line("right", 150.0, 72.0, 50.0),
+            line("   ", 0.0, 0.0, 30.0),
+        ],
+        0.0,
+    );
+
+    assert_eq!(result.to_layout_text(), "  Title\n\n\nleft part right\n");
+    assert_eq!(OcrResult::new(Vec::new(), 0.0).to_layout_text(), "");
+}
@@ -0,0 +1,29 @@
+use oneocr_rs::{DllBackend, OneOcrError};
+
+#[test]
+fn missing_library_is_reported_with_path() {
+    let err = DllBackend::load("./does-not-exist/oneocr.dll").unwrap_err();
+
+    match err {
+        OneOcrError::LibraryLoadError { path, .. } => {
+            assert!(path.ends_with("does-not-exist/oneocr.dll"))
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn missing_symbols_are_listed() {
+    // libc is a valid shared object that exports none of the OneOCR functions.
+    let err = DllBackend::load("libc.so.6").unwrap_err();
+
+    match err {
+        OneOcrError::MissingSymbols { symbols, .. } => {
+            assert_eq!(symbols.len(), 24);
+            assert!(symbols.contains(&"CreateOcrPipeline".to_string()));
+            assert!(symbols.contains(&"ReleaseOcrProcessOptions".to_string()));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{MODEL_KEY as KEY, onemodel_container as container};
+use oneocr_rs::{ModelEntryKind, OneModel, OneOcrError};
+use sha2::{Digest, Sha256};
+
+const DETECTOR: &[u8] = b"\x08\x07\x12\x07pytorch\x1a\x05detector";
+const RECOGNIZER: &[u8] = b"\x08\x07\x12\x07ai.onnx\x1a\x0arecognizer";
+const CHARACTER_MAP: &[u8] = "a 0\nb 1\n\u{00e9} 2\n".as_bytes();
+const CONFIG: &[u8] = b"\x00\x01\x02\xff\xfe";
+
+#[test]
+fn entries_are_decrypted_and_classified() {
+    let bytes = container(b"header", &[DETECTOR, RECOGNIZER, CHARACTER_MAP, CONFIG]);
+
+    let model = OneModel::parse(&bytes, KEY).unwrap();
+
+    assert_eq!(model.header(), b"header");
+    let kinds: Vec<_> = model.entries().iter().map(|entry| entry.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ModelEntryKind::Detector,
+            ModelEntryKind::Recognizer,
+            ModelEntryKind::CharacterMap,
+            ModelEntryKind::Config
+        ]
+    );
+    let recognizer = &model.entries()[1];
+    assert_eq!(recognizer.data, RECOGNIZER);
+    assert_eq!(recognizer.size(), RECOGNIZER.len());
+    assert_eq!(recognizer.sha256(), hex(&Sha256::digest(RECOGNIZER)));
+    assert_eq!(recognizer.file_name(), "001_recognizer.onnx");
+}
+
+#[test]
+fn entries_are_extracted_to_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let model = OneModel::parse(&container(b"", &[DETECTOR, CHARACTER_MAP]), KEY).unwrap();
+
+    let paths = model.extract_all(dir.path()).unwrap();
+
+    assert_eq!(paths[0], dir.path().join("000_detector.onnx"));
+    assert_eq!(std::fs::read(&paths[1]).unwrap(), CHARACTER_MAP);
+}
+
+#[test]
+fn entries_that_cannot_be_written_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("file");
+    std::fs::write(&file, b"").unwrap();
+    let model = OneModel::parse(&container(b"", &[DETECTOR]), KEY).unwrap();
+
+    let err = model.extract_all(&file).unwrap_err();
+
+    match err {
+        OneOcrError::ModelExtractError { path, .. } => assert_eq!(path, file),
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+/// A container encrypted with the OpenSSL command line instead of this crate, so that the
+/// layout, key derivation and cipher mode are checked against an independent implementation:
+///
+/// ```sh
+/// iv=$(printf 'Copyright @ OneO' | xxd -p)
+/// key=$(printf '%s' "$MODEL_KEY" | xxd -p -c64)
+/// # The header: magic number, payload length 3 and "hdr".
+/// printf '4a1a082b25000000 0300000000000000 686472' | xxd -r -p |
+///     openssl enc -aes-256-cfb -K "$key" -iv "$iv" -nopad | xxd -p
+/// # An entry salted with 00 01 .. 0f: magic number, payload length 4 and "a\nb\n".
+/// entry_key=$( (printf '%s' "$MODEL_KEY"; printf 000102030405060708090a0b0c0d0e0f | xxd -r -p) |
+///     openssl dgst -sha256 -binary | xxd -p -c64)
+/// printf '4a1a082b25000000 0400000000000000 610a620a' | xxd -r -p |
+///     openssl enc -aes-256-cfb -K "$entry_key" -iv "$iv" -nopad | xxd -p
+/// ```
+const KNOWN_ANSWER: &str = concat!(
+    "1300000000000000",
+    "e722761da7b276cc91aa4beaa2a3120957f99b",
+    "1400000000000000",
+    "000102030405060708090a0b0c0d0e0f",
+    "a3d43861b2d9e387078cf6c61e7d1a66f8095b92",
+);
+
+#[test]
+fn known_answer_container_is_decrypted() {
+    let bytes: Vec<u8> = (0..KNOWN_ANSWER.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&KNOWN_ANSWER[i..i + 2], 16).unwrap())
+        .collect();
+
+    let model = OneModel::parse(&bytes, KEY).unwrap();
+
+    assert_eq!(model.header(), b"hdr");
+    assert_eq!(model.entries().len(), 1);
+    let entry = &model.entries()[0];
+    assert_eq!(entry.data, b"a\nb\n");
+    assert_eq!(
+        (entry.offset, entry.kind),
+        (27, ModelEntryKind::CharacterMap)
+    );
+}
+
+#[test]
+fn wrong_key_is_rejected() {
+    let bytes = container(b"header", &[DETECTOR]);
+
+    let err = OneModel::parse(&bytes, &[0u8; 32]).unwrap_err();
+
+    assert!(matches!(err, OneOcrError::InvalidModelKey(_)));
+}
+
+#[test]
+fn truncated_container_is_rejected() {
+    let bytes = container(b"header", &[DETECTOR]);
+
+    let err = OneModel::parse(&bytes[..bytes.len() - 4], KEY).unwrap_err();
+
+    assert!(matches!(err, OneOcrError::InvalidModelFormat(_)));
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::{assert_valid_xml, exported_result, two_block_result};
+use oneocr_rs::{OcrResult, PageXmlOptions};
+use std::time::{Duration, UNIX_EPOCH};
+
+const PAGE_NS: &str = "http://schema.primaresearch.org/PAGE/gts/pagecontent/2019-07-15";
+
+fn options() -> PageXmlOptions {
+    let mut options = PageXmlOptions::default();
+    options.image_file_name = "scans/fish & chips.png".to_string();
+    options.created = UNIX_EPOCH + Duration::from_secs(1_757_075_400);
+    options
+}
+
+#[test]
+fn page_xml_keeps_the_quadrilateral_geometry() {
+    let page = exported_result().to_page_xml_with(&options());
+
+    let document = roxmltree::Document::parse(&page).unwrap();
+    let elements = |name: &str| {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name((PAGE_NS, name)))
+            .collect::<Vec<_>>()
+    };
+    let coords = |node: &roxmltree::Node| {
+        node.children()
+            .find(|child| child.has_tag_name((PAGE_NS, "Coords")))
+            .and_then(|coords| coords.attribute("points"))
+            .map(str::to_string)
+    };
+    let text_equiv = |node: &roxmltree::Node| {
+        let equiv = node
+            .children()
+            .find(|child| child.has_tag_name((PAGE_NS, "TextEquiv")))
+            .unwrap();
+        (
+            equiv.attribute("conf").map(str::to_string),
+            equiv
+                .first_element_child()
+                .unwrap()
+                .text()
+                .map(str::to_string),
+        )
+    };
+
+    let page_element = &elements("Page")[0];
+    assert_eq!(
+        page_element.attribute("imageFilename"),
+        Some("scans/fish & chips.png")
+    );
+    assert_eq!(page_element.attribute("imageWidth"), Some("200"));
+    assert_eq!(page_element.attribute("orientation"), Some("1.5"));
+    assert_eq!(elements("Created")[0].text(), Some("2025-09-05T12:30:00Z"));
+
+    let lines = elements("TextLine");
+    assert_eq!(lines.len(), 2);
+    // The skewed corner of the second line is kept instead of being flattened to a rectangle.
+    assert_eq!(
+        coords(&lines[1]).as_deref(),
+        Some("10,60 100,58 100,81 10,81")
+    );
+    assert_eq!(
+        text_equiv(&lines[0]),
+        (
+            Some("0.796".to_string()),
+            Some("Fish & <Chips>".to_string())
+        )
+    );
+    assert_eq!(text_equiv(&lines[1]), (None, Some("Total 42".to_string())));
+
+    let words = elements("Word");
+    assert_eq!(words.len(), 3);
+    assert_eq!(words[2].attribute("id"), Some("r1_l1_w3"));
+    assert_eq!(
+        coords(&words[0]).as_deref(),
+        Some("10,10 50,10 50,30 10,30")
+    );
+    assert_eq!(
+        text_equiv(&words[0]),
+        (Some("0.987".to_string()), Some("Fish".to_string()))
+    );
+
+    let region = &elements("TextRegion")[0];
+    assert_eq!(coords(region).as_deref(), Some("10,10 160,10 160,81 10,81"));
+}
+
+#[test]
+fn page_orientation_is_normalized() {
+    let mut result = exported_result();
+    result.image_angle = 270.0;
+
+    let page = result.to_page_xml_with(&options());
+
+    assert!(page.contains("orientation=\"-90\""));
+}
+
+#[test]
+fn blocks_are_text_regions() {
+    let page = two_block_result().to_page_xml_with(&options());
+
+    let document = roxmltree::Document::parse(&page).unwrap();
+    let regions: Vec<_> = document
+        .descendants()
+        .filter(|node| node.has_tag_name((PAGE_NS, "TextRegion")))
+        .collect();
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[1].attribute("id"), Some("r2"));
+    let lines: Vec<_> = regions[1]
+        .children()
+        .filter(|node| node.has_tag_name((PAGE_NS, "TextLine")))
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].attribute("id"), Some("r2_l2"));
+}
+
+#[test]
+fn empty_results_have_no_regions() {
+    let page = OcrResult::new(Vec::new(), 0.0).to_page_xml();
+
+    assert!(!page.contains("TextRegion"));
+}
+
+#[test]
+#[ignore = "needs xmllint and the schemas of tests/fixtures/fetch-schemas.sh"]
+fn exports_are_valid_page_xml_2019() {
+    let mut rotated = exported_result();
+    rotated.image_angle = 270.0;
+
+    for page in [
+        exported_result().to_page_xml_with(&options()),
+        rotated.to_page_xml_with(&options()),
+        two_block_result().to_page_xml(),
+        OcrResult::new(Vec::new(), 0.0).to_page_xml(),
+    ] {
+        assert_valid_xml(&page, "pagecontent-2019-07-15.xsd");
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{FakeBackend, blank_image, sample_lines};
+use oneocr_rs::{ImageInput, OcrEngine, OcrEnginePool, OcrOptions, OneOcrError, RunOptions};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+fn pool(size: usize) -> OcrEnginePool {
+    OcrEnginePool::new_with_backend(
+        size,
+        FakeBackend::new(sample_lines(), 0.0),
+        OcrOptions::default(),
+    )
+    .unwrap()
+}
+
+fn images(count: usize) -> impl Iterator<Item = ImageInput> {
+    (0..count).map(|_| blank_image().into())
+}
+
+#[test]
+fn empty_pool_is_rejected() {
+    let err = OcrEnginePool::new_with_backend(
+        0,
+        FakeBackend::new(sample_lines(), 0.0),
+        OcrOptions::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, OneOcrError::InvalidPoolSize(0)), "{err}");
+}
+
+#[test]
+fn engines_are_returned_when_guards_drop() {
+    let pool = pool(2);
+
+    let first = pool.get();
+    let second = pool.try_get().unwrap();
+    assert!(pool.try_get().is_none());
+    assert!(pool.get_timeout(Duration::from_millis(10)).is_none());
+    assert_eq!(pool.stats().in_use, 2);
+    assert_eq!(first.run(blank_image().into()).unwrap().lines.len(), 2);
+
+    drop(second);
+    assert_eq!(pool.stats().in_use, 1);
+    assert!(pool.try_get().is_some());
+    drop(first);
+
+    let stats = pool.stats();
+    assert_eq!(stats.in_use, 0);
+    assert_eq!(stats.checkouts, 3);
+    assert!(stats.busy_time > Duration::ZERO);
+    assert!((0.0..=1.0).contains(&stats.utilization()));
+}
+
+#[test]
+fn batch_runs_in_parallel_across_pipelines() {
+    let backend = FakeBackend::new(sample_lines(), 0.0);
+    let max_in_flight = backend.max_in_flight.clone();
+    let live_objects = backend.live_objects.clone();
+    let pool = OcrEnginePool::new_with_backend(4, backend, OcrOptions::default()).unwrap();
+    let live_before = live_objects.load(Ordering::SeqCst);
+
+    let results = pool.run_batch(images(64));
+
+    assert_eq!(results.len(), 64);
+    assert!(results.iter().all(|r| r.as_ref().unwrap().lines.len() == 2));
+    // Runs on one pipeline are serialized, but the pool's four pipelines run side by side.
+    let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+    assert!((2..=4).contains(&max_in_flight), "{max_in_flight}");
+    drop(results);
+    assert_eq!(live_objects.load(Ordering::SeqCst), live_before);
+    let stats = pool.stats();
+    assert_eq!(stats.checkouts, 64);
+    assert_eq!(stats.in_use, 0);
+}
+
+#[test]
+fn batch_keeps_input_order_and_run_options() {
+    let pool = OcrEngine::builder()
+        .backend(FakeBackend::new(sample_lines(), 0.0))
+        .build_pool(3)
+        .unwrap();
+    let inputs = (0..10).map(|i| {
+        if i % 2 == 0 {
+            blank_image().into()
+        } else {
+            ImageInput::FilePath("missing.png".into())
+        }
+    });
+    let mut run_options = RunOptions::default();
+    run_options.max_recognition_line_count = 1;
+
+    let results = pool.run_batch_with(inputs, &run_options);
+
+    for (i, result) in results.iter().enumerate() {
+        match result {
+            Ok(result) if i % 2 == 0 => assert_eq!(result.lines.len(), 1),
+            Err(OneOcrError::ImageOpenError(_)) if i % 2 == 1 => {}
+            other => panic!("unexpected result {i}: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::line;
+use oneocr_rs::{Affine, BoundingBox, OcrResult, Point, ReadingOrderOptions, TextDirection};
+
+/// A two-column page under a title spanning both columns, with a footer, in the order the
+/// engine emits the lines of a baseline-aligned layout: across the columns, row by row.
+fn two_column_page() -> OcrResult {
+    OcrResult::new(
+        vec![
+            line("Title", 50.0, 20.0, 500.0),
+            line("L1", 50.0, 80.0, 230.0),
+            line("R1", 320.0, 80.0, 230.0),
+            line("L2", 50.0, 105.0, 230.0),
+            line("R2", 320.0, 105.0, 230.0),
+            line("L3", 50.0, 130.0, 120.0),
+            line("R3", 320.0, 130.0, 230.0),
+            line("R4", 320.0, 155.0, 90.0),
+            line("Footer", 50.0, 200.0, 500.0),
+        ],
+        0.0,
+    )
+    .with_image_size(600, 240)
+}
+
+fn texts(result: &OcrResult) -> Vec<&str> {
+    result
+        .lines_in_reading_order()
+        .into_iter()
+        .map(|line| line.text.as_str())
+        .collect()
+}
+
+#[test]
+fn columns_are_read_one_after_the_other() {
+    let mut result = two_column_page();
+
+    assert_eq!(result.text_direction(), TextDirection::LeftToRight);
+    assert_eq!(result.reading_order(), [0, 1, 3, 5, 2, 4, 6, 7, 8]);
+    assert_eq!(result.text(), "Title\nL1\nL2\nL3\nR1\nR2\nR3\nR4\nFooter");
+
+    // A slightly rotated scan keeps its columns.
+    let center = BoundingBox::from_rect(0.0, 0.0, 600.0, 240.0).centroid();
+    let skewed = result.transform(&Affine::rotate_around(4.0, center));
+    assert_eq!(skewed.reading_order(), result.reading_order());
+
+    // Without a wide enough gutter, the page is read row by row.
+    let mut options = ReadingOrderOptions::default();
+    options.min_column_gap = 3.0;
+    assert_eq!(
+        result.reading_order_with(&options),
+        [0, 1, 2, 3, 4, 5, 6, 7, 8]
+    );
+
+    result.sort_by_reading_order();
+    assert_eq!(result.reading_order(), [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(result.lines[4].text, "R1");
+}
+
+#[test]
+fn right_to_left_text_starts_at_the_right() {
+    let result = OcrResult::new(
+        vec![
+            line("כותרת", 50.0, 20.0, 500.0),
+            line("שמאל א", 50.0, 80.0, 230.0),
+            line("ימין א", 320.0, 80.0, 230.0),
+            line("שמאל ב", 50.0, 105.0, 230.0),
+            line("ימין ב", 320.0, 105.0, 230.0),
+            // Two lines of one row, closer than a column gap.
+            line("ערך", 50.0, 150.0, 100.0),
+            line("שם:", 160.0, 150.0, 60.0),
+        ],
+        0.0,
+    );
+
+    assert_eq!(result.text_direction(), TextDirection::RightToLeft);
+    assert_eq!(
+        texts(&result),
+        [
+            "כותרת",
+            "ימין א",
+            "ימין ב",
+            "שמאל א",
+            "שמאל ב",
+            "שם:",
+            "ערך"
+        ]
+    );
+
+    let mut options = ReadingOrderOptions::default();
+    options.direction = Some(TextDirection::LeftToRight);
+    assert_eq!(result.reading_order_with(&options), [0, 1, 3, 2, 4, 5, 6]);
+}
+
+#[test]
+fn degenerate_pages() {
+    let empty = OcrResult::new(Vec::new(), 0.0);
+    assert!(empty.reading_order().is_empty());
+    assert_eq!(empty.text(), "");
+
+    // Lines of a single column stay in place even if the engine emitted them out of order,
+    // and a vertical line doesn't skew the page.
+    let mut vertical = line("Side", 560.0, 20.0, 100.0);
+    vertical.bounding_box = BoundingBox::from_corners([
+        Point::new(580.0, 20.0),
+        Point::new(580.0, 120.0),
+        Point::new(560.0, 120.0),
+        Point::new(560.0, 20.0),
+    ]);
+    let result = OcrResult::new(
+        vec![
+            line("Second", 50.0, 45.0, 200.0),
+            line("First", 50.0, 20.0, 200.0),
+            line("Third", 50.0, 70.0, 150.0),
+            vertical,
+        ],
+        0.0,
+    );
+    assert_eq!(texts(&result), ["First", "Second", "Third", "Side"]);
+}
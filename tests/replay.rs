@@ -0,0 +1,82 @@
+mod common;
+
+use common::{FakeBackend, blank_image, sample_lines};
+use oneocr_rs::{OcrEngine, OcrOptions, OneOcrError, ReplayBackend, image_hash};
+
+fn word_level_options() -> OcrOptions {
+    let mut options = OcrOptions::default();
+    options.include_word_level_details = true;
+    options
+}
+
+#[test]
+fn recorded_results_are_replayed_exactly() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let recorder = ReplayBackend::record(FakeBackend::new(sample_lines(), 2.5), dir.path());
+    let engine = OcrEngine::new_with_backend(recorder, word_level_options()).unwrap();
+    let recorded = engine.run(blank_image().into()).unwrap();
+    let fixture = format!("{}_100_1152x768.json", image_hash(&blank_image()));
+    assert!(dir.path().join(fixture).exists());
+
+    let engine =
+        OcrEngine::new_with_backend(ReplayBackend::new(dir.path()), word_level_options()).unwrap();
+    let replayed = engine.run(blank_image().into()).unwrap();
+
+    assert_eq!(replayed.image_angle, recorded.image_angle);
+    // Line equality covers the style and its confidence.
+    assert_eq!(replayed.lines, recorded.lines);
+}
+
+#[test]
+fn missing_fixture_is_reported_with_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine =
+        OcrEngine::new_with_backend(ReplayBackend::new(dir.path()), OcrOptions::default()).unwrap();
+
+    let err = engine.run(blank_image().into()).unwrap_err();
+
+    match err {
+        OneOcrError::FixtureNotFound { hash, .. } => assert_eq!(hash, image_hash(&blank_image())),
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn fixtures_are_kept_per_process_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut limited = word_level_options();
+    limited.max_recognition_line_count = 1;
+
+    for options in [word_level_options(), limited.clone()] {
+        let recorder = ReplayBackend::record(FakeBackend::new(sample_lines(), 2.5), dir.path());
+        let engine = OcrEngine::new_with_backend(recorder, options).unwrap();
+        engine.run(blank_image().into()).unwrap();
+    }
+
+    let replay = |options| {
+        let engine = OcrEngine::new_with_backend(ReplayBackend::new(dir.path()), options).unwrap();
+        engine.run(blank_image().into())
+    };
+    assert_eq!(replay(word_level_options()).unwrap().lines.len(), 2);
+    assert_eq!(replay(limited.clone()).unwrap().lines.len(), 1);
+    limited.max_recognition_line_count = 2;
+    let err = replay(limited).unwrap_err();
+    assert!(matches!(err, OneOcrError::FixtureNotFound { .. }), "{err}");
+    // Only the fixtures are left behind, no temporary files.
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn negative_line_counts_are_not_recorded() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut backend = FakeBackend::new(sample_lines(), 0.0);
+    backend.reported_line_count = Some(-1);
+    let recorder = ReplayBackend::record(backend, dir.path());
+    let engine = OcrEngine::new_with_backend(recorder, OcrOptions::default()).unwrap();
+
+    let err = engine.run(blank_image().into()).unwrap_err();
+
+    assert!(matches!(err, OneOcrError::InvalidCount { .. }), "{err}");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
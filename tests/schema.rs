@@ -0,0 +1,106 @@
+mod common;
+
+use common::{FakeBackend, blank_image, rect, sample_lines};
+use oneocr_rs::{
+    LineStyle, OcrEngine, OcrLine, OcrOptions, OcrResult, OcrWord, OneOcrError, PreprocessingStep,
+};
+
+fn recognized_result() -> OcrResult {
+    let mut options = OcrOptions::default();
+    options.include_word_level_details = true;
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(sample_lines(), 1.5), options).unwrap();
+    engine.run(blank_image().into()).unwrap()
+}
+
+fn validator() -> jsonschema::Validator {
+    let schema: serde_json::Value = serde_json::from_str(OcrResult::JSON_SCHEMA).unwrap();
+    jsonschema::validator_for(&schema).unwrap()
+}
+
+#[test]
+fn results_round_trip_through_json() {
+    let built = OcrResult::new(
+        vec![OcrLine {
+            text: "Total: 42 €".to_string(),
+            bounding_box: rect(10.0, 20.0, 80.0, 12.0),
+            words: None,
+            style: LineStyle::Handwritten,
+            style_confidence: 0.75,
+        }],
+        -2.5,
+    );
+
+    for result in [recognized_result(), built] {
+        let json = result.to_json();
+        assert_eq!(OcrResult::from_json(&json).unwrap(), result);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], OcrResult::SCHEMA_VERSION);
+    }
+}
+
+#[test]
+fn serialized_results_match_the_json_schema() {
+    let validator = validator();
+    let mut result = recognized_result()
+        .with_preprocessing(PreprocessingStep::resize(400, 200, 200, 100).unwrap());
+    result.lines[0].words = Some(vec![OcrWord {
+        text: "<&>".to_string(),
+        confidence: 1.0,
+        bounding_box: rect(0.0, 0.0, 1.0, 1.0),
+    }]);
+
+    assert!(!result.blocks.is_empty());
+
+    let value = serde_json::to_value(&result).unwrap();
+
+    let errors: Vec<String> = validator
+        .iter_errors(&value)
+        .map(|e| e.to_string())
+        .collect();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert!(!validator.is_valid(&serde_json::json!({ "lines": [{ "text": "no box" }] })));
+}
+
+#[test]
+fn results_without_version_or_style_load_as_version_1() {
+    let json = r#"{
+        "lines": [{
+            "text": "legacy",
+            "bounding_box": {
+                "top_left": { "x": 0, "y": 0 },
+                "top_right": { "x": 10, "y": 0 },
+                "bottom_right": { "x": 10, "y": 5 },
+                "bottom_left": { "x": 0, "y": 5 }
+            },
+            "words": null
+        }],
+        "image_angle": 0.0
+    }"#;
+    assert!(validator().is_valid(&serde_json::from_str(json).unwrap()));
+
+    let result = OcrResult::from_json(json).unwrap();
+
+    assert_eq!(result.schema_version, 1);
+    assert_eq!(result.lines[0].style, LineStyle::Printed);
+    assert_eq!(result.lines[0].bounding_box, rect(0.0, 0.0, 10.0, 5.0));
+}
+
+#[test]
+fn newer_or_malformed_results_are_rejected() {
+    let json = r#"{ "schema_version": 2, "lines": [], "image_angle": 0.0 }"#;
+    let err = OcrResult::from_json(json).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            OneOcrError::UnsupportedSchemaVersion {
+                found: 2,
+                supported: 1
+            }
+        ),
+        "{err}"
+    );
+
+    let err = OcrResult::from_json(r#"{ "lines": 3 }"#).unwrap_err();
+    assert!(matches!(err, OneOcrError::InvalidResultJson(_)), "{err}");
+}
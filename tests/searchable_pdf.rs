@@ -0,0 +1,299 @@
+#![cfg(feature = "pdf")]
+
+mod common;
+
+use common::{exported_result, rect};
+use image::{DynamicImage, GrayImage, RgbImage};
+use lopdf::content::Content;
+use lopdf::{Document, Object};
+use oneocr_rs::{OcrLine, OcrResult, OneOcrError, PdfOptions, SearchablePdf};
+
+/// Options for pages of `dpi` dots per inch.
+fn options(dpi: f32) -> PdfOptions {
+    let mut options = PdfOptions::default();
+    options.dpi = dpi;
+    options
+}
+
+fn load(bytes: &[u8]) -> Document {
+    Document::load_mem(bytes).unwrap()
+}
+
+fn page_text(document: &Document, page: u32) -> String {
+    document.extract_text(&[page]).unwrap()
+}
+
+/// Returns the operations of a page's content stream.
+fn operations(document: &Document, page: u32) -> Vec<(String, Vec<f32>)> {
+    let page_id = document.get_pages()[&page];
+    let content = Content::decode(&document.get_page_content(page_id).unwrap()).unwrap();
+    content
+        .operations
+        .into_iter()
+        .map(|op| {
+            let operands = op
+                .operands
+                .iter()
+                .filter_map(|operand| operand.as_float().ok())
+                .collect();
+            (op.operator, operands)
+        })
+        .collect()
+}
+
+/// Returns the image XObject of a single-page document.
+fn image_xobject(document: &Document) -> &lopdf::Stream {
+    document
+        .objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .find(|stream| stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image"))
+        .unwrap()
+}
+
+#[test]
+fn pages_show_the_image_under_invisible_words() {
+    let image = DynamicImage::ImageRgb8(RgbImage::new(200, 100));
+
+    let mut result = exported_result();
+    // Tilt the baseline of the second line upwards.
+    result.lines[1].bounding_box.bottom_right.y -= 9.0;
+
+    let bytes = result.to_searchable_pdf(image, options(72.0)).unwrap();
+
+    let document = load(&bytes);
+    let page_id = document.get_pages()[&1];
+    let page = document.get_dictionary(page_id).unwrap();
+    let media_box: Vec<f32> = page
+        .get(b"MediaBox")
+        .and_then(Object::as_array)
+        .unwrap()
+        .iter()
+        .map(|value| value.as_float().unwrap())
+        .collect();
+    assert_eq!(media_box, [0.0, 0.0, 200.0, 100.0]);
+
+    let text = page_text(&document, 1);
+    for word in ["Fish", "&", "<Chips>", "Total 42"] {
+        assert!(text.contains(word), "{word:?} not in {text:?}");
+    }
+
+    let operations = operations(&document, 1);
+    assert!(operations.contains(&("Do".to_string(), Vec::new())));
+    // Render mode 3 draws nothing.
+    assert!(operations.contains(&("Tr".to_string(), vec![3.0])));
+    // The first word sits on its baseline, 30 pixels from the top, as high as its box.
+    let matrices: Vec<_> = operations
+        .iter()
+        .filter(|(operator, _)| operator == "Tm")
+        .map(|(_, operands)| operands.clone())
+        .collect();
+    assert_eq!(matrices.len(), 4);
+    assert_eq!(matrices[0], [1.0, 0.0, 0.0, 1.0, 10.0, 70.0]);
+    assert!(operations.contains(&("Tf".to_string(), vec![20.0])));
+    // The tilted second line is rotated to follow its baseline.
+    assert!(
+        matrices[3][1] > 0.0 && matrices[3][2] < 0.0,
+        "{:?}",
+        matrices[3]
+    );
+}
+
+#[test]
+fn multi_page_documents_scale_results_to_each_image() {
+    let mut pdf = SearchablePdf::new(PdfOptions::default()).unwrap();
+    pdf.add_page(
+        DynamicImage::ImageRgb8(RgbImage::new(200, 100)),
+        &exported_result(),
+    )
+    .unwrap();
+    // A result recognized on a half-size image is scaled up to the full-size page.
+    let second = OcrResult::new(
+        vec![OcrLine {
+            text: "Straße №2 🐟".to_string(),
+            bounding_box: rect(10.0, 10.0, 60.0, 10.0),
+            words: None,
+            style: Default::default(),
+            style_confidence: 1.0,
+        }],
+        0.0,
+    )
+    .with_image_size(100, 50);
+    pdf.add_page(DynamicImage::ImageLuma8(GrayImage::new(200, 100)), &second)
+        .unwrap();
+    assert_eq!(pdf.page_count(), 2);
+
+    let document = load(&pdf.finish());
+
+    assert_eq!(document.get_pages().len(), 2);
+    assert!(page_text(&document, 2).contains("Straße №2 🐟"));
+    let matrix = operations(&document, 2)
+        .into_iter()
+        .find(|(operator, _)| operator == "Tm")
+        .unwrap()
+        .1;
+    // 300 DPI: 20 pixels on the full-size image are 4.8 points.
+    assert!((matrix[4] - 20.0 * 72.0 / 300.0).abs() < 1e-4, "{matrix:?}");
+}
+
+#[test]
+fn documents_are_saved_and_invalid_resolutions_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("scan.pdf");
+    let mut pdf = SearchablePdf::new(PdfOptions::default()).unwrap();
+    pdf.add_page(
+        DynamicImage::ImageRgb8(RgbImage::new(20, 10)),
+        &OcrResult::new(Vec::new(), 0.0),
+    )
+    .unwrap();
+
+    pdf.save(&path).unwrap();
+
+    assert_eq!(load(&std::fs::read(&path).unwrap()).get_pages().len(), 1);
+    let err = SearchablePdf::new(options(0.0)).unwrap_err();
+    assert!(matches!(err, OneOcrError::InvalidDpi(_)), "{err}");
+}
+
+#[test]
+fn empty_images_are_rejected() {
+    let mut pdf = SearchablePdf::new(PdfOptions::default()).unwrap();
+
+    let err = pdf
+        .add_page(
+            DynamicImage::ImageRgb8(RgbImage::new(0, 10)),
+            &exported_result(),
+        )
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            OneOcrError::EmptyImage {
+                width: 0,
+                height: 10
+            }
+        ),
+        "{err}"
+    );
+    assert_eq!(pdf.page_count(), 0);
+}
+
+#[test]
+fn jpeg_files_are_embedded_as_they_are() {
+    let dir = tempfile::tempdir().unwrap();
+    let cases = [
+        (
+            "color.jpg",
+            DynamicImage::ImageRgb8(RgbImage::new(40, 30)),
+            b"DCTDecode".as_slice(),
+            b"DeviceRGB".as_slice(),
+        ),
+        (
+            "gray.jpg",
+            DynamicImage::ImageLuma8(GrayImage::new(40, 30)),
+            b"DCTDecode",
+            b"DeviceGray",
+        ),
+        (
+            "color.png",
+            DynamicImage::ImageRgb8(RgbImage::new(40, 30)),
+            b"FlateDecode",
+            b"DeviceRGB",
+        ),
+    ];
+    for (name, image, filter, color_space) in cases {
+        let path = dir.path().join(name);
+        image.save(&path).unwrap();
+
+        let bytes = exported_result()
+            .to_searchable_pdf(path.as_path(), PdfOptions::default())
+            .unwrap();
+
+        let document = load(&bytes);
+        let image = image_xobject(&document);
+        let name_of = |key: &[u8]| image.dict.get(key).and_then(Object::as_name).unwrap();
+        assert_eq!(name_of(b"Filter"), filter, "{name}");
+        assert_eq!(name_of(b"ColorSpace"), color_space, "{name}");
+        assert_eq!(image.dict.get(b"Width").unwrap().as_i64().unwrap(), 40);
+        assert_eq!(image.dict.get(b"Height").unwrap().as_i64().unwrap(), 30);
+        if filter == b"DCTDecode" {
+            assert_eq!(image.content, std::fs::read(&path).unwrap(), "{name}");
+        }
+    }
+}
+
+#[test]
+fn results_without_image_size_are_in_image_pixels() {
+    let result = OcrResult::new(
+        vec![OcrLine {
+            text: "Corner".to_string(),
+            bounding_box: rect(10.0, 10.0, 60.0, 10.0),
+            words: None,
+            style: Default::default(),
+            style_confidence: 1.0,
+        }],
+        0.0,
+    );
+    assert_eq!((result.image_width, result.image_height), (0, 0));
+
+    let bytes = result
+        .to_searchable_pdf(
+            DynamicImage::ImageRgb8(RgbImage::new(200, 100)),
+            options(72.0),
+        )
+        .unwrap();
+
+    // The line stays 10 pixels from the left and 20 pixels from the top, instead of being
+    // stretched over the page.
+    let document = load(&bytes);
+    let operations = operations(&document, 1);
+    let matrix = &operations
+        .iter()
+        .find(|(operator, _)| operator == "Tm")
+        .unwrap()
+        .1;
+    assert_eq!(matrix[..], [1.0, 0.0, 0.0, 1.0, 10.0, 80.0]);
+    assert!(operations.contains(&("Tf".to_string(), vec![10.0])));
+}
+
+#[test]
+fn the_text_layer_font_is_embedded() {
+    let bytes = exported_result()
+        .to_searchable_pdf(
+            DynamicImage::ImageRgb8(RgbImage::new(200, 100)),
+            PdfOptions::default(),
+        )
+        .unwrap();
+
+    let document = load(&bytes);
+    let descriptor = document
+        .objects
+        .values()
+        .filter_map(|object| object.as_dict().ok())
+        .find(|dict| dict.has_type(b"FontDescriptor"))
+        .unwrap();
+    let font_file = document
+        .get_object(
+            descriptor
+                .get(b"FontFile2")
+                .unwrap()
+                .as_reference()
+                .unwrap(),
+        )
+        .and_then(Object::as_stream)
+        .unwrap();
+    let font = font_file.decompressed_content().unwrap();
+    assert_eq!(
+        font_file.dict.get(b"Length1").unwrap().as_i64().unwrap(),
+        font.len() as i64
+    );
+
+    let face = ttf_parser::Face::parse(&font, 0).unwrap();
+    assert_eq!(face.number_of_glyphs(), 2);
+    assert_eq!(face.units_per_em(), 1000);
+    for glyph in [0, 1] {
+        let glyph = ttf_parser::GlyphId(glyph);
+        assert_eq!(face.glyph_hor_advance(glyph), Some(500));
+        assert!(face.glyph_bounding_box(glyph).is_none());
+    }
+}
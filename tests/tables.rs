@@ -0,0 +1,251 @@
+mod common;
+
+use common::rect;
+use oneocr_rs::{Affine, BoundingBox, OcrLine, OcrResult, OcrWord, Table, TableOptions};
+
+/// A row of a table as one line of 10-pixel-wide characters, 20 pixels high, with its words
+/// at the given left edges.
+fn row(words: &[(&str, f32)], top: f32) -> OcrLine {
+    let words: Vec<OcrWord> = words
+        .iter()
+        .map(|(text, left)| OcrWord {
+            text: text.to_string(),
+            confidence: 0.9,
+            bounding_box: rect(*left, top, 10.0 * text.chars().count() as f32, 20.0),
+        })
+        .collect();
+    let bounding_box = words
+        .iter()
+        .skip(1)
+        .fold(words[0].bounding_box, |bounding_box, word| {
+            bounding_box.union(&word.bounding_box)
+        });
+    OcrLine {
+        text: words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        bounding_box,
+        words: Some(words),
+        style: Default::default(),
+        style_confidence: 1.0,
+    }
+}
+
+/// A line without word-level details.
+fn cell(text: &str, left: f32, top: f32, height: f32) -> OcrLine {
+    OcrLine {
+        text: text.to_string(),
+        bounding_box: rect(left, top, 10.0 * text.chars().count() as f32, height),
+        words: None,
+        style: Default::default(),
+        style_confidence: 1.0,
+    }
+}
+
+fn invoice() -> OcrResult {
+    OcrResult::new(
+        vec![
+            row(&[("Invoice", 50.0), ("2024-001", 130.0)], 20.0),
+            row(
+                &[
+                    ("Item", 50.0),
+                    ("Qty", 300.0),
+                    ("Unit", 400.0),
+                    ("price", 450.0),
+                    ("Total", 550.0),
+                ],
+                100.0,
+            ),
+            row(
+                &[
+                    ("Fish,", 50.0),
+                    ("chips", 110.0),
+                    ("2", 300.0),
+                    ("4.50", 400.0),
+                    ("9.00", 550.0),
+                ],
+                130.0,
+            ),
+            row(
+                &[
+                    ("Mushy", 50.0),
+                    ("peas", 110.0),
+                    ("1", 300.0),
+                    ("1.50", 400.0),
+                    ("1.50", 550.0),
+                ],
+                160.0,
+            ),
+            // The label runs under the quantity column.
+            row(
+                &[
+                    ("Total", 50.0),
+                    ("excl.", 110.0),
+                    ("VAT", 170.0),
+                    ("and", 210.0),
+                    ("service", 250.0),
+                    ("10.50", 550.0),
+                ],
+                190.0,
+            ),
+            row(&[("Thank", 50.0), ("you", 110.0), ("for", 150.0)], 260.0),
+        ],
+        0.0,
+    )
+}
+
+#[test]
+fn invoice_items_become_a_grid() {
+    let tables = invoice().detect_tables();
+
+    assert_eq!(tables.len(), 1);
+    let table = &tables[0];
+    assert_eq!((table.row_count, table.column_count), (4, 4));
+    assert!(
+        (table.confidence - 15.0 / 16.0).abs() < 1e-6,
+        "{}",
+        table.confidence
+    );
+    assert_eq!(
+        table.bounding_box.bounds(),
+        rect(50.0, 100.0, 550.0, 110.0).bounds()
+    );
+
+    let total = table.cell(3, 1).unwrap();
+    assert_eq!(total.text, "Total excl. VAT and service");
+    assert_eq!((total.row, total.column), (3, 0));
+    assert_eq!((total.row_span, total.column_span), (1, 2));
+    assert!(table.cell(3, 2).is_none());
+
+    assert_eq!(
+        table.to_csv(),
+        concat!(
+            "Item,Qty,Unit price,Total\n",
+            "\"Fish, chips\",2,4.50,9.00\n",
+            "Mushy peas,1,1.50,1.50\n",
+            "Total excl. VAT and service,,,10.50\n",
+        )
+    );
+    assert_eq!(
+        table.to_markdown(),
+        concat!(
+            "| Item | Qty | Unit price | Total |\n",
+            "| --- | --- | --- | --- |\n",
+            "| Fish, chips | 2 | 4.50 | 9.00 |\n",
+            "| Mushy peas | 1 | 1.50 | 1.50 |\n",
+            "| Total excl. VAT and service |  |  | 10.50 |\n",
+        )
+    );
+    let json: Table = serde_json::from_str(&table.to_json()).unwrap();
+    assert_eq!(&json, table);
+
+    let mut options = TableOptions::default();
+    options.min_rows = 5;
+    assert!(invoice().detect_tables_with(&options).is_empty());
+}
+
+#[test]
+fn prose_columns_are_not_tables() {
+    let sentence = "The quick brown fox jumps over";
+    let lines = (0..5)
+        .flat_map(|row| {
+            let top = 100.0 + 25.0 * row as f32;
+            [
+                cell(sentence, 50.0, top, 20.0),
+                cell(sentence, 400.0, top, 20.0),
+            ]
+        })
+        .collect();
+    let result = OcrResult::new(lines, 0.0);
+
+    assert!(result.detect_tables().is_empty());
+
+    let mut options = TableOptions::default();
+    options.min_confidence = 0.0;
+    let tables = result.detect_tables_with(&options);
+    assert_eq!(tables.len(), 1);
+    assert!((tables[0].confidence - 0.25).abs() < 1e-6);
+}
+
+#[test]
+fn cells_span_rows_on_skewed_pages() {
+    let result = OcrResult::new(
+        vec![
+            cell("Region", 50.0, 100.0, 20.0),
+            cell("Month", 200.0, 100.0, 20.0),
+            cell("Sales", 350.0, 100.0, 20.0),
+            // Centered on the two rows it covers.
+            cell("North", 50.0, 135.0, 40.0),
+            cell("Jan", 200.0, 130.0, 20.0),
+            cell("10", 350.0, 130.0, 20.0),
+            cell("Feb", 200.0, 160.0, 20.0),
+            cell("12", 350.0, 160.0, 20.0),
+            cell("South", 50.0, 190.0, 20.0),
+            cell("Jan", 200.0, 190.0, 20.0),
+            cell("7", 350.0, 190.0, 20.0),
+        ],
+        0.0,
+    );
+    let center = BoundingBox::from_rect(0.0, 0.0, 500.0, 300.0).centroid();
+    let skewed = result.transform(&Affine::rotate_around(3.0, center));
+
+    for result in [result, skewed] {
+        let tables = result.detect_tables();
+
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert_eq!((table.row_count, table.column_count), (4, 3));
+        let north = table.cell(2, 0).unwrap();
+        assert_eq!(
+            (north.text.as_str(), north.row, north.row_span),
+            ("North", 1, 2)
+        );
+        assert_eq!(
+            table.to_csv(),
+            "Region,Month,Sales\nNorth,Jan,10\n,Feb,12\nSouth,Jan,7\n"
+        );
+        assert_eq!(table.confidence, 1.0);
+    }
+}
+
+#[test]
+fn tables_are_found_in_any_coordinate_units() {
+    let normalized = invoice().with_image_size(700, 300).normalized().unwrap();
+    let huge = invoice().transform(&Affine::scale(1e30, 1e30));
+    let expected = invoice().detect_tables()[0].to_csv();
+
+    for result in [normalized, huge] {
+        let tables = result.detect_tables();
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!((tables[0].row_count, tables[0].column_count), (4, 4));
+        assert_eq!(tables[0].to_csv(), expected);
+    }
+}
+
+#[test]
+fn infinite_coordinates_do_not_break_detection() {
+    let mut result = invoice();
+    // A row with a box running off to infinity, then boxes at infinity or NaN.
+    let mut runaway = row(&[("Tip", 50.0), ("1.00", 550.0)], 220.0);
+    let words = runaway.words.as_mut().unwrap();
+    words[1].bounding_box.top_right.x = f32::INFINITY;
+    words[1].bounding_box.bottom_right.x = f32::INFINITY;
+    let lost = vec![
+        cell("Lost", f32::NEG_INFINITY, 245.0, 20.0),
+        cell("NaN", f32::NAN, 245.0, 20.0),
+        cell("0.00", 550.0, 245.0, 20.0),
+    ];
+    result.lines.splice(5..5, [runaway].into_iter().chain(lost));
+
+    let mut options = TableOptions::default();
+    options.min_confidence = 0.0;
+    let tables = result.detect_tables_with(&options);
+
+    // The runaway box still joins its row; the boxes without a position are left out.
+    assert_eq!(tables.len(), 1);
+    assert_eq!((tables[0].row_count, tables[0].column_count), (5, 4));
+    assert_eq!(tables[0].grid()[4], ["Tip", "", "", "1.00"]);
+}
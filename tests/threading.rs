@@ -0,0 +1,101 @@
+mod common;
+
+use common::{FakeBackend, blank_image, sample_lines};
+use oneocr_rs::{OcrEngine, OcrLine, OcrOptions, OcrResult, RunOptions};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+const THREADS: usize = 8;
+const RUNS_PER_THREAD: usize = 50;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn engine_and_results_are_send_and_sync() {
+    assert_send_sync::<OcrEngine>();
+    assert_send_sync::<OcrResult>();
+    assert_send_sync::<OcrLine>();
+}
+
+#[test]
+fn engine_can_be_moved_to_a_worker_thread() {
+    let engine =
+        OcrEngine::new_with_backend(FakeBackend::new(sample_lines(), 0.0), OcrOptions::default())
+            .unwrap();
+
+    let result = thread::spawn(move || engine.run(blank_image().into()).unwrap())
+        .join()
+        .unwrap();
+
+    assert_eq!(result.lines.len(), 2);
+}
+
+/// Hammers a shared engine with `run_with` calls using different line limits, `run` calls,
+/// and setters changing the engine's line limit, and checks every result.
+fn hammer(engine: &Arc<OcrEngine>) {
+    let workers: Vec<_> = (0..THREADS)
+        .map(|thread| {
+            let engine = engine.clone();
+            thread::spawn(move || {
+                for i in 0..RUNS_PER_THREAD {
+                    match (thread + i) % 3 {
+                        0 => {
+                            let limit = (thread % 2) as i32 + 1;
+                            let mut run_options = RunOptions::default();
+                            run_options.max_recognition_line_count = limit;
+                            run_options.include_word_level_details = true;
+                            let result =
+                                engine.run_with(blank_image().into(), &run_options).unwrap();
+                            assert_eq!(result.lines.len(), limit as usize);
+                            assert!(result.lines.iter().all(|line| line.words.is_some()));
+                        }
+                        1 => {
+                            let result = engine.run(blank_image().into()).unwrap();
+                            assert!((1..=2).contains(&result.lines.len()));
+                            assert_eq!(result.lines[0].text, "Hello world");
+                        }
+                        _ => {
+                            engine
+                                .set_max_recognition_line_count((i % 2) as i32 + 1)
+                                .unwrap();
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn shared_engine_serves_many_threads() {
+    let mut backend = FakeBackend::new(sample_lines(), 0.0);
+    backend.concurrent_runs = true;
+    let live_objects = backend.live_objects.clone();
+    let runs = backend.runs.clone();
+    let engine = Arc::new(OcrEngine::new_with_backend(backend, OcrOptions::default()).unwrap());
+    let live_before = live_objects.load(Ordering::SeqCst);
+
+    hammer(&engine);
+
+    let expected_runs = (0..THREADS)
+        .flat_map(|thread| (0..RUNS_PER_THREAD).map(move |i| (thread + i) % 3))
+        .filter(|kind| *kind != 2)
+        .count();
+    assert_eq!(runs.lock().unwrap().len(), expected_runs);
+    assert_eq!(live_objects.load(Ordering::SeqCst), live_before);
+}
+
+#[test]
+fn runs_are_serialized_when_backend_requires_it() {
+    let backend = FakeBackend::new(sample_lines(), 0.0);
+    let max_in_flight = backend.max_in_flight.clone();
+    let engine = Arc::new(OcrEngine::new_with_backend(backend, OcrOptions::default()).unwrap());
+
+    hammer(&engine);
+
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{exported_result, two_block_result};
+use oneocr_rs::OcrResult;
+
+#[test]
+fn tsv_matches_the_tesseract_column_layout() {
+    let tsv = exported_result().to_tsv();
+
+    let rows: Vec<Vec<&str>> = tsv.lines().map(|row| row.split('\t').collect()).collect();
+    assert_eq!(
+        rows[0],
+        [
+            "level",
+            "page_num",
+            "block_num",
+            "par_num",
+            "line_num",
+            "word_num",
+            "left",
+            "top",
+            "width",
+            "height",
+            "conf",
+            "text"
+        ]
+    );
+    assert!(rows.iter().all(|row| row.len() == 12), "{tsv}");
+    let levels: Vec<&str> = rows[1..].iter().map(|row| row[0]).collect();
+    assert_eq!(levels, ["1", "2", "3", "4", "5", "5", "5", "3", "4", "5"]);
+
+    assert_eq!(
+        rows[1],
+        [
+            "1", "1", "0", "0", "0", "0", "0", "0", "200", "100", "-1", ""
+        ]
+    );
+    assert_eq!(
+        rows[4],
+        [
+            "4", "1", "1", "1", "1", "0", "10", "10", "150", "20", "-1", ""
+        ]
+    );
+    assert_eq!(
+        rows[5],
+        [
+            "5", "1", "1", "1", "1", "1", "10", "10", "40", "20", "98.7", "Fish"
+        ]
+    );
+    assert_eq!(rows[7][11], "<Chips>");
+    // Without word-level details, the line's text becomes a single word of unknown confidence.
+    assert_eq!(
+        rows[10],
+        [
+            "5", "1", "1", "2", "1", "1", "10", "58", "91", "23", "-1", "Total 42"
+        ]
+    );
+}
+
+#[test]
+fn tsv_text_never_splits_columns() {
+    let mut result = exported_result();
+    result.lines[1].text = "tab\there\nand there".to_string();
+
+    let tsv = result.to_tsv();
+
+    assert!(tsv.lines().all(|row| row.split('\t').count() == 12));
+    assert!(tsv.contains("\ttab here and there\n"));
+    assert_eq!(OcrResult::new(Vec::new(), 0.0).to_tsv().lines().count(), 2);
+}
+
+#[test]
+fn tsv_numbers_blocks_paragraphs_and_lines() {
+    let tsv = two_block_result().to_tsv();
+
+    let rows: Vec<Vec<&str>> = tsv.lines().map(|row| row.split('\t').collect()).collect();
+    let ids: Vec<&[&str]> = rows[2..].iter().map(|row| &row[..6]).collect();
+    assert_eq!(ids[0], ["2", "1", "1", "0", "0", "0"]);
+    assert_eq!(ids[6], ["2", "1", "2", "0", "0", "0"]);
+    assert_eq!(ids[7], ["3", "1", "2", "1", "0", "0"]);
+    // Lines are numbered within their paragraph.
+    assert_eq!(ids[9], ["5", "1", "2", "1", "1", "1"]);
+    assert_eq!(rows[8][6..10], ["10", "300", "90", "20"]);
+}